@@ -1,7 +1,6 @@
 extern crate dcss_api;
 
-use dcss_api::Webtile;
-use dcss_api::{BlockingError, Error as APIError};
+use dcss_api::{Action, BlockingError, Direction, Error as APIError, Webtile};
 use serde_json::Value;
 use std::process;
 
@@ -30,8 +29,8 @@ fn main() {
 
     // Depending on what is found in the "map" data, a move up may make sense (up to the
     // bot to decide this) -- note this may if a north wall exists (no bot intelligence here).
-    write_key_bot(&mut webtile, "key_dir_n", "player").expect("Failed");
-    write_key_bot(&mut webtile, "key_dir_s", "player").expect("Failed");
+    act_bot(&mut webtile, Action::Move(Direction::N)).expect("Failed");
+    act_bot(&mut webtile, Action::Move(Direction::S)).expect("Failed");
 
     // Quit game (same as dying)
     webtile.quit_game().expect("Failed to quit");
@@ -40,30 +39,29 @@ fn main() {
     webtile.disconnect().expect("Failed to disconnect");
 }
 
-fn write_key_bot(webtile: &mut Webtile, to_send: &str, to_receive: &str) -> Result<(), APIError> {
-    println!("SEND: {}", to_send);
+fn act_bot(webtile: &mut Webtile, action: Action) -> Result<(), APIError> {
+    println!("SEND: {:?}", action);
 
-    webtile.write_key(to_send)?;
+    // Sends the keys and waits for the message confirming the action
+    let outcome = webtile.act(action)?;
 
     // Make sure you verify for blocking errors;
-    if let Err(e) = webtile.read_until(to_receive, None, None) {
-        match e {
-            APIError::Blocking(BlockingError::More) => webtile.write_key(" ")?,
-            APIError::Blocking(BlockingError::TextInput) => {
-                println!("ERROR: Likely level up choice");
-            }
-            APIError::Blocking(BlockingError::Pickup) => println!("ERROR: Pickup"),
-            APIError::Blocking(BlockingError::Acquirement(_)) => println!("ERROR: Acquirement"),
-            APIError::Blocking(BlockingError::Identify(_)) => println!("ERROR: Identify"),
-            APIError::Blocking(BlockingError::EnchantWeapon(_)) => println!("ERROR: EnchantWeapon"),
-            APIError::Blocking(BlockingError::EnchantItem(_)) => println!("ERROR: EnchantItem"),
-            APIError::Blocking(BlockingError::BrandWeapon(_)) => println!("ERROR: BrandWeapon"),
-            APIError::Blocking(BlockingError::Skill) => println!("ERROR: Skill"),
-            APIError::Blocking(BlockingError::Died) => {
+    if let Some(blocking) = outcome.blocking {
+        match blocking {
+            BlockingError::More => webtile.write_key(" ")?,
+            BlockingError::TextInput => println!("ERROR: Likely level up choice"),
+            BlockingError::Pickup => println!("ERROR: Pickup"),
+            BlockingError::Acquirement(_) => println!("ERROR: Acquirement"),
+            BlockingError::Identify(_) => println!("ERROR: Identify"),
+            BlockingError::EnchantWeapon(_) => println!("ERROR: EnchantWeapon"),
+            BlockingError::EnchantItem(_) => println!("ERROR: EnchantItem"),
+            BlockingError::BrandWeapon(_) => println!("ERROR: BrandWeapon"),
+            BlockingError::Skill => println!("ERROR: Skill"),
+            BlockingError::Died => {
                 println!("ERROR: Died");
                 process::exit(0);
             }
-            _ => Err(e)?,
+            _ => Err(APIError::Blocking(blocking))?,
         }
     }
 
@@ -0,0 +1,55 @@
+extern crate dcss_api;
+
+use dcss_api::Webtile;
+use std::sync::{Arc, Mutex};
+
+fn main() {
+    // Connect to DCSS Webtile
+    let mut webtile =
+        Webtile::connect("ws://localhost:8080/socket", 100, "0.32").expect("Failed to connect");
+
+    // Log in (to a user called "Username", with a password "Password")
+    let gameid = webtile
+        .login_with_credentials("Username", "Password")
+        .expect("Failed to login");
+
+    // Print the position of the character every time it is sent
+    webtile.on("player", |message| {
+        if let Some(pos) = message.get("pos") {
+            println!("PROCESS: player position {}", pos);
+        }
+    });
+
+    // Count the number of map updates (shared with the callback)
+    let map_count = Arc::new(Mutex::new(0));
+    let map_subscriber = Arc::clone(&map_count);
+    webtile.on("map", move |_message| {
+        *map_subscriber.lock().unwrap() += 1;
+    });
+
+    // Start a random game on 'dcss-web-trunk', for Minotaur berserker with a mace.
+    webtile
+        .start_game_seeded(&gameid[0], "1", false, "b", "f", "b")
+        .expect("Failed to start game");
+
+    // Move up and back, the callbacks are called as the messages are received
+    webtile.write_key("key_dir_n").expect("Failed to write key");
+    webtile
+        .read_until("player", None, None)
+        .expect("Failed to read");
+    webtile.write_key("key_dir_s").expect("Failed to write key");
+    webtile
+        .read_until("player", None, None)
+        .expect("Failed to read");
+
+    println!("PROCESS: {} map updates", map_count.lock().unwrap());
+
+    // Stop printing the position
+    webtile.off("player");
+
+    // Quit game (same as dying)
+    webtile.quit_game().expect("Failed to quit");
+
+    // Disconnect from webtile
+    webtile.disconnect().expect("Failed to disconnect");
+}
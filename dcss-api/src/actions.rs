@@ -0,0 +1,223 @@
+use crate::Error;
+use crate::{BlockingError, Webtile};
+
+/// Direction of a move, attack or target, relative to the character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction {
+    /// Key (see [`Webtile::write_key`]) to move in this direction.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Direction::N => "key_dir_n",
+            Direction::NE => "key_dir_ne",
+            Direction::E => "key_dir_e",
+            Direction::SE => "key_dir_se",
+            Direction::S => "key_dir_s",
+            Direction::SW => "key_dir_sw",
+            Direction::W => "key_dir_w",
+            Direction::NW => "key_dir_nw",
+        }
+    }
+
+    /// Key (CTRL + the vi-key of the direction) to attack in this direction,
+    /// without moving.
+    pub fn attack_key(&self) -> &'static str {
+        match self {
+            Direction::N => "key_ctrl_k",
+            Direction::NE => "key_ctrl_u",
+            Direction::E => "key_ctrl_l",
+            Direction::SE => "key_ctrl_n",
+            Direction::S => "key_ctrl_j",
+            Direction::SW => "key_ctrl_b",
+            Direction::W => "key_ctrl_h",
+            Direction::NW => "key_ctrl_y",
+        }
+    }
+}
+
+/// Destination of an [Action::Travel].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TravelTarget {
+    /// Closest down staircase on the level (through the level map, `X>.`).
+    DownStairs,
+    /// Closest up staircase on the level (through the level map, `X<.`).
+    UpStairs,
+    /// Level of a branch (through the travel prompt, `G`), e.g. `Level('D', 3)`.
+    Level(char, u32),
+}
+
+/// Target of an [Action::Cast].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpellTarget {
+    /// Spell without a target (no targeting keys are sent).
+    None,
+    /// Default target selected by DCSS (usually the closest monster).
+    Auto,
+    /// Cell adjacent to the character.
+    Direction(Direction),
+}
+
+/// High-level action of the character, sent with [`Webtile::act`]. Slots are the
+/// inventory (or spell, or ability) letters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Move(Direction),
+    Attack(Direction),
+    Rest,
+    Explore,
+    Travel(TravelTarget),
+    PickUp,
+    Drop(char),
+    Quaff(char),
+    Read(char),
+    Wield(char),
+    Wear(char),
+    Cast(char, SpellTarget),
+    Evoke(char),
+    UseAbility(char),
+}
+
+impl Action {
+    /// Keys (see [`Webtile::write_key`]) sent, in order, for the action.
+    pub fn keys(&self) -> Vec<String> {
+        match self {
+            Action::Move(direction) => vec![direction.key().to_owned()],
+            Action::Attack(direction) => vec![direction.attack_key().to_owned()],
+            Action::Rest => vec!["5".to_owned()],
+            Action::Explore => vec!["o".to_owned()],
+            Action::Travel(TravelTarget::DownStairs) => vec!["X>.".to_owned()],
+            Action::Travel(TravelTarget::UpStairs) => vec!["X<.".to_owned()],
+            Action::Travel(TravelTarget::Level(branch, depth)) => {
+                vec![format!("G{}{}", branch, depth), "key_enter".to_owned()]
+            }
+            Action::PickUp => vec![",".to_owned()],
+            Action::Drop(slot) => vec![format!("d{}", slot)],
+            Action::Quaff(slot) => vec![format!("q{}", slot)],
+            Action::Read(slot) => vec![format!("r{}", slot)],
+            Action::Wield(slot) => vec![format!("w{}", slot)],
+            Action::Wear(slot) => vec![format!("W{}", slot)],
+            Action::Cast(spell, target) => {
+                let mut keys = vec![format!("z{}", spell)];
+                match target {
+                    SpellTarget::None => (),
+                    SpellTarget::Auto => keys.push(".".to_owned()),
+                    SpellTarget::Direction(direction) => {
+                        keys.push(direction.key().to_owned());
+                        keys.push(".".to_owned());
+                    }
+                }
+                keys
+            }
+            Action::Evoke(slot) => vec![format!("V{}", slot)],
+            Action::UseAbility(slot) => vec![format!("a{}", slot)],
+        }
+    }
+
+    /// Message (as the `msg`, `key` and `value` arguments of [`Webtile::read_until`])
+    /// that confirms an action was done.
+    ///
+    /// It is the same for every action: whatever the action does (including
+    /// multi-turn actions, like travel or resting), DCSS sends `input_mode` 1 once
+    /// it is done and ready for a new command. Messages that depend on the action
+    /// would not always be sent: there is no `player` message when a move fails
+    /// (e.g. into a wall), and no log message for most moves. Whether the action
+    /// succeeded is read from the messages received before the confirmation.
+    pub const CONFIRMATION: (&'static str, Option<&'static str>, Option<u64>) =
+        ("input_mode", Some("mode"), Some(1));
+}
+
+/// Result of an [Action] sent with [`Webtile::act`].
+#[derive(Debug)]
+pub struct ActionOutcome {
+    /// The [Action] that was sent.
+    pub action: Action,
+    /// If the confirming message of the action (see [`Action::CONFIRMATION`]) was
+    /// received.
+    pub completed: bool,
+    /// [BlockingError] (e.g. a pickup menu or a 'more' prompt) received while
+    /// waiting for the confirming message, if any. It has to be dealt with before
+    /// sending another action.
+    pub blocking: Option<BlockingError>,
+}
+
+impl Webtile {
+    /// Send the keys of an [Action] and wait for the message confirming it (see
+    /// [`Action::CONFIRMATION`]). Messages received are added to the queue read
+    /// by [`Webtile::get_message`], as with [`Webtile::read_until`].
+    ///
+    /// Blocking errors are not returned as an [Error], but in the [ActionOutcome].
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The [Action] to send.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// let outcome = webtile.act(Action::Move(Direction::N))?;
+    ///
+    /// let outcome = webtile.act(Action::PickUp)?;
+    /// if let Some(BlockingError::Pickup) = outcome.blocking {
+    ///     // More than one item, select in the menu
+    /// }
+    /// ```
+    pub fn act(&mut self, action: Action) -> Result<ActionOutcome, Error> {
+        for key in action.keys() {
+            self.write_key(&key)?;
+        }
+
+        let (msg, key, value) = Action::CONFIRMATION;
+
+        match self.read_until(msg, key, value) {
+            Ok(_) => Ok(ActionOutcome {
+                action,
+                completed: true,
+                blocking: None,
+            }),
+            Err(Error::Blocking(blocking)) => {
+                // The confirming message may have been received along with the
+                // blocking message: report it here, instead of in the next read
+                let completed = self.message_found;
+                self.message_found = false;
+
+                Ok(ActionOutcome {
+                    action,
+                    completed,
+                    blocking: Some(blocking),
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_action_keys() {
+        assert_eq!(Action::Move(Direction::NE).keys(), vec!["key_dir_ne"]);
+        assert_eq!(Action::Attack(Direction::W).keys(), vec!["key_ctrl_h"]);
+        assert_eq!(Action::Quaff('c').keys(), vec!["qc"]);
+        assert_eq!(
+            Action::Travel(TravelTarget::Level('D', 3)).keys(),
+            vec!["GD3", "key_enter"]
+        );
+        assert_eq!(
+            Action::Cast('a', SpellTarget::Direction(Direction::S)).keys(),
+            vec!["za", "key_dir_s", "."]
+        );
+    }
+}
@@ -20,6 +20,26 @@ pub enum Error {
     LoginFailed,
     #[error("Failed to register.")]
     RegisterFailed,
+    #[error("Unknown RC profile: {0}.")]
+    UnknownRcProfile(String),
+    #[error("Requested seed {requested} does not match the game seed {actual}.")]
+    SeedMismatch { requested: u64, actual: u64 },
+    #[error("Failed to find the game seed.")]
+    SeedNotFound,
+    #[error("Message queue is full ({0} messages).")]
+    QueueFull(usize),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("HTTP error: {0}")]
+    Http(String),
+    #[error("Not logged in.")]
+    NotLoggedIn,
+    #[error("The character dump was not written.")]
+    DumpFailed,
+    #[error("Message \"{0}\" was not received.")]
+    MessageNotFound(&'static str),
+    #[error("Missing field \"{0}\" in the message received.")]
+    MissingField(&'static str),
 }
 
 /// Errors that will block the game from processing normally. Since each read
@@ -68,6 +88,13 @@ pub enum BlockingError {
     Died,
 }
 
+/// RC file options that make DCSS wait for a key with an `input_mode` of 5 (a
+/// [BlockingError::More]), with the value that prevents it: the 'more' prompt
+/// when the message window is full, and the messages always followed by one.
+#[cfg(test)]
+pub(crate) const MORE_TRIGGERS: [(&str, &str); 2] =
+    [("show_more", "false"), ("force_more_message", "")];
+
 /// This function will "pre-process" each received message and return an
 /// error if a BlockingError type message is received, through various
 /// message types received by the DCSS webtile.
@@ -1,6 +1,9 @@
 use crate::Error;
 use flate2::{Decompress, FlushDecompress};
+use native_tls::TlsConnector;
 use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::TcpStream;
 use std::result::Result;
 use std::str;
 
@@ -62,6 +65,129 @@ pub(crate) fn keys(key: &str) -> Value {
     }
 }
 
+/// Find the game seed (e.g. `Game seed: 158985`) in a message received from DCSS
+/// Webtiles. Returns [None] if the message does not contain the seed.
+///
+/// # Arguments
+///
+/// * `message` - A [serde_json::Value] received from DCSS Webtiles.
+pub(crate) fn find_game_seed(message: &Value) -> Option<u64> {
+    match message["msg"].as_str()? {
+        "msgs" => message["messages"]
+            .as_array()?
+            .iter()
+            .find_map(|text_obj| seed_in_text(text_obj["text"].as_str()?)),
+        "ui-push" if message["type"] == "version" => seed_in_text(message["information"].as_str()?),
+        _ => None,
+    }
+}
+
+/// Read the seed following "Game seed: " in a text.
+fn seed_in_text(text: &str) -> Option<u64> {
+    let start = text.find("Game seed: ")? + "Game seed: ".len();
+
+    text[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse::<u64>()
+        .ok()
+}
+
+/// Convert a websocket URL (e.g. `ws://localhost:8080/socket`) to the HTTP URL of
+/// the same host (e.g. `http://localhost:8080`).
+///
+/// # Arguments
+///
+/// * `ws_url` - A string slice of the `ws://` or `wss://` URL.
+pub(crate) fn http_base_url(ws_url: &str) -> String {
+    let (scheme, rest) = match ws_url.split_once("://") {
+        Some(("wss", rest)) => ("https", rest),
+        Some((_, rest)) => ("http", rest),
+        None => ("http", ws_url),
+    };
+    let host = rest.split('/').next().unwrap_or("");
+
+    format!("{}://{}", scheme, host)
+}
+
+/// Split the host (without the brackets of an IPv6 address) from the port, if any
+/// (e.g. `localhost:8080`, `[::1]:8080`, `[::1]`).
+///
+/// # Arguments
+///
+/// * `host_port` - A string slice of the host, with an optional port.
+pub(crate) fn split_host_port(host_port: &str) -> (&str, Option<&str>) {
+    if let Some(rest) = host_port.strip_prefix('[') {
+        return match rest.split_once(']') {
+            Some((host, after)) => (host, after.strip_prefix(':')),
+            None => (rest, None),
+        };
+    }
+
+    match host_port.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (host_port, None),
+    }
+}
+
+/// Send an HTTP GET request (`http://` or `https://`) and return the body of the
+/// response as a [String].
+///
+/// # Arguments
+///
+/// * `url` - A string slice of the URL to get.
+pub(crate) fn http_get(url: &str) -> Result<String, Error> {
+    let (tls, rest) = match url.split_once("://") {
+        Some(("https", rest)) => (true, rest),
+        Some(("http", rest)) => (false, rest),
+        _ => return Err(Error::Http(format!("Unsupported URL: {}", url))),
+    };
+    let (host_port, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = split_host_port(host_port);
+    let address = match (port, tls) {
+        (Some(_), _) => host_port.to_owned(),
+        (None, true) => format!("{}:443", host_port),
+        (None, false) => format!("{}:80", host_port),
+    };
+
+    // HTTP/1.0, to get the full body without chunked encoding
+    let request = format!(
+        "GET {} HTTP/1.0\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        path, host_port
+    );
+
+    let stream = TcpStream::connect(address)?;
+    let mut response = vec![];
+    if tls {
+        let connector = TlsConnector::new().map_err(|e| Error::Http(e.to_string()))?;
+        let mut stream = connector
+            .connect(host, stream)
+            .map_err(|e| Error::Http(e.to_string()))?;
+        stream.write_all(request.as_bytes())?;
+        stream.read_to_end(&mut response)?;
+    } else {
+        let mut stream = stream;
+        stream.write_all(request.as_bytes())?;
+        stream.read_to_end(&mut response)?;
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let (header, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or(Error::Http("Malformed HTTP response.".to_owned()))?;
+
+    let status = header.lines().next().unwrap_or("");
+    if status.split(' ').nth(1) != Some("200") {
+        return Err(Error::Http(status.to_owned()));
+    }
+
+    Ok(body.to_owned())
+}
+
 /// Decompresses (deflate) a message from DCSS Webtiles. Returns a [serde_json::Value] object of the data.
 ///
 /// # Arguments
@@ -134,4 +260,87 @@ mod tests {
         let decode_2 = deflate_to_json(&mut decompressor, &mut data_2_bin);
         assert!(decode_2.is_err());
     }
+
+    #[test]
+    fn test_http_base_url() {
+        assert_eq!(
+            http_base_url("ws://localhost:8080/socket"),
+            "http://localhost:8080"
+        );
+        assert_eq!(
+            http_base_url("wss://crawl.example.org/socket"),
+            "https://crawl.example.org"
+        );
+        assert_eq!(http_base_url("ws://[::1]:8080/socket"), "http://[::1]:8080");
+    }
+
+    #[test]
+    fn test_split_host_port() {
+        assert_eq!(split_host_port("localhost"), ("localhost", None));
+        assert_eq!(
+            split_host_port("localhost:8080"),
+            ("localhost", Some("8080"))
+        );
+        assert_eq!(split_host_port("[::1]:8080"), ("::1", Some("8080")));
+        assert_eq!(split_host_port("[2001:db8::1]"), ("2001:db8::1", None));
+    }
+
+    #[test]
+    fn test_http_get() {
+        use std::io::{BufRead, BufReader};
+        use std::net::TcpListener;
+        use std::thread;
+
+        // Local stand-in for the morgue server
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request_line = String::new();
+                BufReader::new(&stream)
+                    .read_line(&mut request_line)
+                    .unwrap();
+
+                let response = if request_line.starts_with("GET /morgue/Username/Username.txt ") {
+                    "HTTP/1.0 200 OK\r\nContent-Type: text/plain\r\n\r\nCharacter dump"
+                } else {
+                    "HTTP/1.0 404 Not Found\r\n\r\n"
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        let base = format!("http://127.0.0.1:{}", port);
+        let body = http_get(&format!("{}/morgue/Username/Username.txt", base)).unwrap();
+        assert_eq!(body, "Character dump");
+
+        let missing = http_get(&format!("{}/morgue/Username/Other.txt", base));
+        assert!(matches!(missing, Err(Error::Http(_))));
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_find_game_seed() {
+        let custom = json!({"msg": "msgs", "messages": [
+            {"text": "<yellow>Game seed: 158985 (custom seed)</yellow>", "channel": 0}
+        ]});
+        assert_eq!(find_game_seed(&custom), Some(158985));
+
+        let version = json!({"msg": "ui-push", "type": "version",
+            "information": "Dungeon Crawl Stone Soup version 0.32.1\nGame seed: 1, levelgen mode: deterministic"});
+        assert_eq!(find_game_seed(&version), Some(1));
+
+        let no_seed = json!({"msg": "msgs", "messages": [
+            {"text": "Welcome, Username the Minotaur Berserker.", "channel": 0}
+        ]});
+        assert_eq!(find_game_seed(&no_seed), None);
+
+        // Only the text of the messages is read
+        let other_field = json!({"msg": "msgs", "messages": [
+            {"text": "You see here a scroll.", "channel": 0, "note": "Game seed: 42"}
+        ]});
+        assert_eq!(find_game_seed(&other_field), None);
+    }
 }
@@ -0,0 +1,238 @@
+use std::fmt;
+
+/// Colour of a colour tag (e.g. `<lightred>`), as used by the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Colour {
+    Black,
+    Blue,
+    Green,
+    Cyan,
+    Red,
+    Magenta,
+    Brown,
+    LightGrey,
+    DarkGrey,
+    LightBlue,
+    LightGreen,
+    LightCyan,
+    LightRed,
+    LightMagenta,
+    Yellow,
+    White,
+}
+
+/// Part of a [FormattedText] with a single colour.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    /// Colour of the text, or [None] for the default colour.
+    pub colour: Option<Colour>,
+}
+
+/// Text with colour tags, as sent by the game in the messages of the log, the
+/// statuses, the menus, etc. (e.g. `<lightred>You die...</lightred>`).
+///
+/// # Example
+///
+/// ```ignore
+/// let text = FormattedText::parse("<white>Hello</white> <<3");
+/// assert_eq!(text.plain(), "Hello <3");
+/// assert!(text.has_colour(Colour::White));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FormattedText {
+    spans: Vec<Span>,
+}
+
+impl Colour {
+    /// Colour from the name of a tag (e.g. "lightred"), or [None] if the tag is
+    /// not a colour.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - A string slice of the name of the tag, without `<` and `>`.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let colour = match tag.to_lowercase().as_str() {
+            "black" => Self::Black,
+            "blue" => Self::Blue,
+            "green" => Self::Green,
+            "cyan" => Self::Cyan,
+            "red" => Self::Red,
+            "magenta" => Self::Magenta,
+            "brown" => Self::Brown,
+            "lightgrey" | "lightgray" => Self::LightGrey,
+            "darkgrey" | "darkgray" => Self::DarkGrey,
+            "lightblue" => Self::LightBlue,
+            "lightgreen" => Self::LightGreen,
+            "lightcyan" => Self::LightCyan,
+            "lightred" => Self::LightRed,
+            "lightmagenta" => Self::LightMagenta,
+            "yellow" => Self::Yellow,
+            "white" => Self::White,
+            _ => return None,
+        };
+
+        Some(colour)
+    }
+
+    /// ANSI escape code (foreground) of the colour.
+    pub fn ansi_code(&self) -> u8 {
+        match self {
+            Self::Black => 30,
+            Self::Red => 31,
+            Self::Green => 32,
+            Self::Brown => 33,
+            Self::Blue => 34,
+            Self::Magenta => 35,
+            Self::Cyan => 36,
+            Self::LightGrey => 37,
+            Self::DarkGrey => 90,
+            Self::LightRed => 91,
+            Self::LightGreen => 92,
+            Self::Yellow => 93,
+            Self::LightBlue => 94,
+            Self::LightMagenta => 95,
+            Self::LightCyan => 96,
+            Self::White => 97,
+        }
+    }
+}
+
+impl FormattedText {
+    /// Parse the colour tags of a text. Closing tags end the last colour opened,
+    /// `<<` is an escaped `<`, and tags that are not colours are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string slice of the text, with colour tags.
+    pub fn parse(text: &str) -> Self {
+        let mut formatted = Self::default();
+        let mut colours: Vec<Colour> = vec![];
+        let mut current = String::new();
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != '<' {
+                current.push(c);
+                continue;
+            }
+
+            if chars.peek() == Some(&'<') {
+                chars.next();
+                current.push('<');
+                continue;
+            }
+
+            let mut tag = String::new();
+            for c in chars.by_ref() {
+                if c == '>' {
+                    break;
+                }
+                tag.push(c);
+            }
+
+            formatted.push(&mut current, colours.last().copied());
+
+            match tag.strip_prefix('/') {
+                Some(closing) if Colour::from_tag(closing).is_some() => {
+                    colours.pop();
+                }
+                Some(_) => (),
+                None => {
+                    if let Some(colour) = Colour::from_tag(&tag) {
+                        colours.push(colour);
+                    }
+                }
+            }
+        }
+
+        formatted.push(&mut current, colours.last().copied());
+
+        formatted
+    }
+
+    /// Add the text as a span (merged with the last span if of the same colour).
+    fn push(&mut self, text: &mut String, colour: Option<Colour>) {
+        if text.is_empty() {
+            return;
+        }
+
+        match self.spans.last_mut() {
+            Some(last) if last.colour == colour => last.text.push_str(text),
+            _ => self.spans.push(Span {
+                text: text.clone(),
+                colour,
+            }),
+        }
+        text.clear();
+    }
+
+    /// Spans of the text, in order.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// Text without the colour tags.
+    pub fn plain(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    /// Text with ANSI escape codes for the colours, to print in a terminal.
+    pub fn to_ansi(&self) -> String {
+        self.spans
+            .iter()
+            .map(|span| match span.colour {
+                Some(colour) => format!("\x1b[{}m{}\x1b[0m", colour.ansi_code(), span.text),
+                None => span.text.clone(),
+            })
+            .collect()
+    }
+
+    /// If some of the text is of the [Colour] (e.g. `Colour::LightRed` for
+    /// errors and dangers).
+    pub fn has_colour(&self, colour: Colour) -> bool {
+        self.spans.iter().any(|span| span.colour == Some(colour))
+    }
+
+    /// If the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+}
+
+impl fmt::Display for FormattedText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.plain())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_formatted_text() {
+        let text = FormattedText::parse(
+            "<lightred>You die...</lightred> <<3 <white>Hi <yellow>there</yellow>!</white>",
+        );
+
+        assert_eq!(text.plain(), "You die... <3 Hi there!");
+        assert_eq!(
+            text.spans()[0],
+            Span {
+                text: "You die...".to_owned(),
+                colour: Some(Colour::LightRed)
+            }
+        );
+        assert_eq!(text.spans()[4].colour, Some(Colour::White));
+        assert!(text.has_colour(Colour::Yellow));
+        assert!(!text.has_colour(Colour::Red));
+        assert!(text.to_ansi().starts_with("\x1b[91mYou die...\x1b[0m <3 "));
+
+        // Unknown tags are removed, and unbalanced closing tags ignored
+        let text = FormattedText::parse("<h1>Title</h1></red>");
+        assert_eq!(text.to_string(), "Title");
+        assert!(!text.has_colour(Colour::Red));
+    }
+}
@@ -1,27 +1,43 @@
 //! An API library to interact with [DCSS Webtile](http://crawl.develz.org/wordpress/howto).
 
+mod actions;
 mod api_errors;
 mod common;
+mod formatted_text;
 mod lobby;
 mod play;
+mod queue;
+mod rc_profiles;
+mod subscribers;
 
+pub use actions::{Action, ActionOutcome, Direction, SpellTarget, TravelTarget};
 pub use api_errors::{BlockingError, Error};
+pub use formatted_text::{Colour, FormattedText, Span};
+pub use play::DEFAULT_MORGUE_PATH;
+pub use queue::OverflowPolicy;
+pub use rc_profiles::RcProfile;
 
 use api_errors::blocking_messages;
 use flate2::Decompress;
+use queue::MessageQueue;
 use serde_json::Value;
-use std::collections::VecDeque;
+use std::collections::HashMap;
 use std::net::TcpStream;
 use std::result::Result;
 use std::str;
 use std::thread;
 use std::time::{Duration, SystemTime};
+use subscribers::Subscriber;
 use tungstenite::Message;
 use tungstenite::{self, protocol::WebSocket, stream::MaybeTlsStream};
 
 /// Webtile connection, using websocket ([tungstenite]) and a Deflate decoder ([flate2]).
 #[derive(Debug)]
 pub struct Webtile {
+    /// URL of the websocket (e.g. `ws://localhost:8080/socket`).
+    url: String,
+    /// Username of the logged in user.
+    username: Option<String>,
     /// Websocket (using [tungstenite::WebSocket]) to send and receive data from
     /// [DCSS Webtile](http://crawl.develz.org/wordpress/howto).
     socket: WebSocket<MaybeTlsStream<TcpStream>>,
@@ -35,8 +51,28 @@ pub struct Webtile {
     message_found: bool,
     /// Speed limit in milliseconds between each command sent to DCSS Webtiles.
     speed_ms: u32,
-    /// [VecDeque] of messages received from DCSS.
-    received_messages: VecDeque<Value>,
+    /// Queue of messages received from DCSS (see [`Webtile::set_queue_capacity`]
+    /// and [`Webtile::filter_message`]).
+    received_messages: MessageQueue,
+    /// [RcProfile] to apply when starting or continuing a game.
+    rc_profile: Option<RcProfile>,
+    /// Content of the RC files replaced by the [RcProfile], by game ID, to be
+    /// restored when the game is saved, quit or lost.
+    previous_rc: HashMap<String, String>,
+    /// Callbacks called on each message received (see [`Webtile::on`]).
+    subscribers: Vec<Subscriber>,
+    /// Last game seed found in the messages read (see [`Webtile::game_seed`]).
+    seen_seed: Option<u64>,
+    /// Content of the last "set_game_links" message (the playable games).
+    game_links: Option<String>,
+    /// If the "Char dumped" log message was read (see [`Webtile::character_dump`]).
+    char_dumped: bool,
+    /// Message found by the last [`Webtile::read_until`], even if it was not
+    /// added to the queue (filtered or dropped).
+    found_message: Option<Value>,
+    /// [Error::QueueFull] that happened in a read that returned a blocking error,
+    /// returned by the next [`Webtile::read_until`].
+    pending_overflow: Option<Error>,
 }
 
 impl Webtile {
@@ -66,12 +102,22 @@ impl Webtile {
 
         // Create webtile object
         let mut webtile = Self {
+            url: url.to_owned(),
+            username: None,
             socket,
             decompressor,
             last_send: SystemTime::now(),
             speed_ms,
             message_found: false,
-            received_messages: VecDeque::new(),
+            received_messages: MessageQueue::new(),
+            rc_profile: None,
+            previous_rc: HashMap::new(),
+            subscribers: Vec::new(),
+            seen_seed: None,
+            game_links: None,
+            char_dumped: false,
+            found_message: None,
+            pending_overflow: None,
         };
 
         // Wait until the "lobby_complete" message is received -- meaning a
@@ -81,7 +127,8 @@ impl Webtile {
         Ok(webtile)
     }
 
-    /// Close the websocket connection.
+    /// Close the websocket connection. If an [RcProfile] was applied and the
+    /// previous RC files were not yet restored, they are restored before closing.
     ///
     /// # Example
     ///
@@ -89,18 +136,25 @@ impl Webtile {
     /// webtile.disconnect()?;
     /// ```
     pub fn disconnect(&mut self) -> Result<(), Error> {
+        self.restore_rc_file()?;
+
         self.socket.close(None).map_err(Error::Websocket)?;
 
         Ok(())
     }
 
     /// Read the websocket messages until a specified message is found. Stores the
-    /// messages in a [VecDeque] that can be accessed by the user through the
-    /// [`Webtile::get_message()`] function. Any known blocking message (e.g.
+    /// messages in a queue that can be accessed by the user through the
+    /// [`Webtile::get_message()`] function, and sends them to the subscribed
+    /// callbacks (see [`Webtile::on`]). Any known blocking message (e.g.
     /// a 'more' log statement) will return a [api_errors::BlockingError].
     ///
     /// Will block forever if the expected message never comes.
     ///
+    /// If the queue was full (see [`Webtile::set_queue_capacity`]) while reading
+    /// a blocking message, the blocking error is returned first, and the
+    /// [Error::QueueFull] by the next call.
+    ///
     /// # Arguments
     ///
     /// * `msg` - A [&str] that holds the value expected in the "msg" field of any returned message.
@@ -124,6 +178,11 @@ impl Webtile {
         key: Option<&str>,
         value: Option<u64>,
     ) -> Result<(), Error> {
+        // Overflow of a previous read that returned a blocking error
+        if let Some(overflow) = self.pending_overflow.take() {
+            return Err(overflow);
+        }
+
         // loop until break (found expected results or found a blocking type)
         // use self variable in order to retain the info when there is a blocking error
         while !self.message_found {
@@ -141,15 +200,30 @@ impl Webtile {
             // Alert if blocking
             let mut blocking = Ok(());
 
+            // Alert if the queue is full
+            let mut overflow = Ok(());
+
             // Will get array of message, go through them until what is expected is found
             for message in messages["msgs"].as_array().unwrap() {
-                // Send data to a VecDeque to be pulled by user;
-                self.received_messages.push_back(message.to_owned());
+                // Send data to a queue to be pulled by user;
+                if let Err(e) = self.received_messages.push(message) {
+                    overflow = Err(e);
+                }
+
+                // Send data to the subscribed callbacks
+                self.notify_subscribers(message);
+
+                // Keep what the API needs, even if the message is not queued
+                self.remember(message);
 
                 // Pre-process the data to identify blocking
                 if let Err(e) = blocking_messages(message) {
                     match e {
-                        Error::Blocking(BlockingError::Died) => return Err(e), // Automatic return when death
+                        // Automatic return when death
+                        Error::Blocking(BlockingError::Died) => {
+                            self.restore_rc_file()?;
+                            return Err(e);
+                        }
                         _ => blocking = Err(e),
                     }
                 };
@@ -169,10 +243,23 @@ impl Webtile {
                         message[key.unwrap()].as_u64().unwrap() == value.unwrap())))
                 {
                     self.message_found = true;
+                    self.found_message = Some(message.clone());
+                }
+            }
+
+            if let Err(e) = blocking {
+                if let Err(overflow) = overflow {
+                    self.pending_overflow = Some(overflow);
                 }
+                return Err(e);
             }
 
-            blocking?
+            // The message found may be the one that was not queued
+            if overflow.is_err() {
+                self.message_found = false;
+                self.found_message = None;
+                overflow?
+            }
         }
 
         self.message_found = false;
@@ -180,6 +267,30 @@ impl Webtile {
         Ok(())
     }
 
+    /// Keep the data of a message needed later by the API (the seed of the game,
+    /// the playable games and if the character was dumped), as it may be
+    /// filtered or dropped from the queue.
+    fn remember(&mut self, message: &Value) {
+        if let Some(seed) = common::find_game_seed(message) {
+            self.seen_seed = Some(seed);
+        }
+
+        if let Some(messages) = message["messages"].as_array() {
+            let dumped = messages.iter().any(|text_obj| {
+                text_obj["text"]
+                    .as_str()
+                    .is_some_and(|text| text.contains("Char dumped"))
+            });
+            self.char_dumped |= dumped;
+        }
+
+        if message["msg"] == "set_game_links" {
+            self.game_links = message["content"]
+                .as_str()
+                .map(|content| content.to_owned());
+        }
+    }
+
     /// Write a [serde_json::Value] to the websocket. Will only send if sufficient time has
     /// elapsed since the last sent data, according to the [`Webtile::connect`] speed_ms option.
     ///
@@ -265,7 +376,8 @@ impl Webtile {
     }
 
     /// Get the messages received by the DCSS Webtile (as [serde_json::Value]), in
-    /// order of reception. Will return [None] if the queue is empty.
+    /// order of reception. Will return [None] if the queue is empty. See
+    /// [`Webtile::find_message`] to search the queue without removing messages.
     ///
     /// # Example
     ///
@@ -278,12 +390,26 @@ impl Webtile {
     pub fn get_message(&mut self) -> Option<Value> {
         self.received_messages.pop_front()
     }
+}
 
-    /// Get a copy of the messages currently in the received queue.
-    pub(crate) fn read_only_messages(&self) -> Vec<Value> {
-        self.received_messages
-            .clone()
-            .into_iter()
-            .collect::<Vec<Value>>()
+impl Webtile {
+    /// Limit how long a read of the websocket waits for data ([None] to wait
+    /// forever, the default).
+    pub(crate) fn set_read_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+        match self.socket.get_ref() {
+            MaybeTlsStream::Plain(stream) => stream.set_read_timeout(timeout),
+            MaybeTlsStream::NativeTls(stream) => stream.get_ref().set_read_timeout(timeout),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Drop for Webtile {
+    /// Restore the RC files replaced by an [RcProfile], if the connection is
+    /// dropped without saving, quitting or disconnecting.
+    fn drop(&mut self) {
+        if !self.previous_rc.is_empty() {
+            let _ = self.restore_rc_file();
+        }
     }
 }
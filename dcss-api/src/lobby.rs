@@ -1,4 +1,5 @@
 use crate::Error;
+use crate::RcProfile;
 use crate::Webtile;
 use serde_json::json;
 
@@ -29,13 +30,15 @@ impl Webtile {
         }))?;
 
         self.read_until("login_success", None, None)?;
+        self.set_username();
 
         self.write_json(json!({
             "msg": "go_lobby"
         }))?;
         self.read_until("go_lobby", None, None)?;
 
-        Ok(self.get_playable_games())
+        self.get_playable_games()
+            .ok_or(Error::MessageNotFound("set_game_links"))
     }
 
     /// Login to the game, using a cookie. It returns a vector of all playable
@@ -55,13 +58,15 @@ impl Webtile {
         self.write_json(json!({"msg": "token_login", "cookie": cookie}))?;
 
         self.read_until("login_success", None, None)?;
+        self.set_username();
 
         self.write_json(json!({
             "msg": "go_lobby"
         }))?;
         self.read_until("go_lobby", None, None)?;
 
-        Ok(self.get_playable_games())
+        self.get_playable_games()
+            .ok_or(Error::MessageNotFound("set_game_links"))
     }
 
     /// Create an account and login to the game, using a username and password. It returns a vector
@@ -90,11 +95,11 @@ impl Webtile {
             json!({"msg": "register", "username": username, "password": password, "email": email.unwrap_or("")}),
         )?;
 
-        if let Err(e) = self.read_until("login_success", None, None) {
-            match e {
-                Error::RegisterFailed => self.login_with_credentials(username, password)?,
-                _ => Err(e)?,
-            };
+        // If the account exists, login instead
+        match self.read_until("login_success", None, None) {
+            Ok(_) => self.set_username(),
+            Err(Error::RegisterFailed) => return self.login_with_credentials(username, password),
+            Err(e) => return Err(e),
         };
 
         self.write_json(json!({
@@ -102,7 +107,8 @@ impl Webtile {
         }))?;
         self.read_until("go_lobby", None, None)?;
 
-        Ok(self.get_playable_games())
+        self.get_playable_games()
+            .ok_or(Error::MessageNotFound("set_game_links"))
     }
 
     /// Request a cookie from the DCSS Webtile.
@@ -117,14 +123,15 @@ impl Webtile {
 
         self.read_until("login_cookie", None, None)?;
 
-        for message in self.read_only_messages() {
-            let message_obj = message.as_object().unwrap();
-            if message_obj["msg"] == "login_cookie" {
-                return Ok(message_obj["cookie"].as_str().unwrap().to_owned());
-            }
-        }
+        let message = self
+            .found_message
+            .take()
+            .ok_or(Error::MessageNotFound("login_cookie"))?;
 
-        unreachable!()
+        message["cookie"]
+            .as_str()
+            .map(|cookie| cookie.to_owned())
+            .ok_or(Error::MissingField("cookie"))
     }
 
     /// Get the RC file content for a specific game ID.
@@ -143,14 +150,15 @@ impl Webtile {
 
         self.read_until("rcfile_contents", None, None)?;
 
-        for message in self.read_only_messages() {
-            let message_obj = message.as_object().unwrap();
-            if message_obj["msg"] == "rcfile_contents" {
-                return Ok(message_obj["contents"].as_str().unwrap().to_owned());
-            }
-        }
+        let message = self
+            .found_message
+            .take()
+            .ok_or(Error::MessageNotFound("rcfile_contents"))?;
 
-        unreachable!()
+        message["contents"]
+            .as_str()
+            .map(|contents| contents.to_owned())
+            .ok_or(Error::MissingField("contents"))
     }
 
     /// Set the RC file content of a specific game ID.
@@ -171,16 +179,69 @@ impl Webtile {
         Ok(())
     }
 
-    /// Process the data received when successfully logging in, to extract the playable games
-    fn get_playable_games(&self) -> Vec<String> {
-        for message in self.read_only_messages() {
-            let message_obj = message.as_object().unwrap();
-            if message_obj["msg"] == "set_game_links" {
-                return process_playable_game(message_obj["content"].as_str().unwrap());
-            }
+    /// Select a built-in [RcProfile] to be applied to the RC file when starting
+    /// or continuing a game (see [`Webtile::start_game_seeded`]). The RC files
+    /// that were replaced (one per game ID) are restored when the game is saved
+    /// ([`Webtile::save_game`]), quit ([`Webtile::quit_game`]) or lost, and when
+    /// disconnecting ([`Webtile::disconnect`]) or dropping the [Webtile].
+    /// Use [None] to stop applying a profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - An optional [RcProfile] to apply.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// webtile.set_rc_profile(Some(RcProfile::BotMinimal));
+    /// webtile.start_game("dcss-web-trunk", "b", "f", "b")?;
+    /// ```
+    pub fn set_rc_profile(&mut self, profile: Option<RcProfile>) {
+        self.rc_profile = profile;
+    }
+
+    /// Replace the RC file of a specific game ID by the selected [RcProfile] (if
+    /// any), keeping a copy of the previous RC file to be restored.
+    pub(crate) fn apply_rc_profile(&mut self, game_id: &str) -> Result<(), Error> {
+        let Some(profile) = self.rc_profile else {
+            return Ok(());
+        };
+
+        // Only keep the original RC file, if a profile is applied twice
+        if !self.previous_rc.contains_key(game_id) {
+            let previous = self.get_rc_file(game_id)?;
+            self.previous_rc.insert(game_id.to_owned(), previous);
+        }
+
+        self.set_rc_file(game_id, profile.rc_content())
+    }
+
+    /// Restore the RC files that were replaced by [`Webtile::apply_rc_profile`].
+    pub(crate) fn restore_rc_file(&mut self) -> Result<(), Error> {
+        let game_ids = self.previous_rc.keys().cloned().collect::<Vec<String>>();
+
+        for game_id in game_ids {
+            let content = self.previous_rc[&game_id].clone();
+            self.set_rc_file(&game_id, &content)?;
+            self.previous_rc.remove(&game_id);
         }
 
-        unreachable!()
+        Ok(())
+    }
+
+    /// Keep the username received when successfully logging in (the message
+    /// found by the last [`Webtile::read_until`])
+    fn set_username(&mut self) {
+        self.username = self
+            .found_message
+            .take()
+            .and_then(|message| message["username"].as_str().map(|name| name.to_owned()));
+    }
+
+    /// Process the data received when successfully logging in, to extract the
+    /// playable games. Returns [None] if the game links were not received.
+    fn get_playable_games(&self) -> Option<Vec<String>> {
+        self.game_links.as_deref().map(process_playable_game)
     }
 }
 
@@ -1,11 +1,21 @@
 use crate::api_errors::BlockingError;
+use crate::common::{http_base_url, http_get};
 use crate::Error;
 use crate::Webtile;
 use serde_json::json;
+use std::io::ErrorKind;
+use std::time::{Duration, Instant};
+
+/// How long to wait for the "Char dumped" log message, after the `#` command.
+const DUMP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Path of the character dumps on a DCSS Webtiles server with the default
+/// configuration, where `%n` is replaced by the username.
+pub const DEFAULT_MORGUE_PATH: &str = "/morgue/%n/%n.txt";
 
 impl Webtile {
     /// Start an unseeded game by selecting the game_id and the character's
-    /// specifications.
+    /// specifications. Returns the seed of the game (see [`Webtile::game_seed`]).
     ///
     /// # Arguments
     ///
@@ -18,7 +28,7 @@ impl Webtile {
     ///
     /// ```no_run
     /// // Start a game on "dcss-web-trunk", for a Minotaur (b), Berserker (f), with a mace (b)
-    /// webtile.start_game("dcss-web-trunk", "b", "f", "b")?;
+    /// let seed = webtile.start_game("dcss-web-trunk", "b", "f", "b")?;
     /// ```
     pub fn start_game(
         &mut self,
@@ -26,11 +36,12 @@ impl Webtile {
         species: &str,
         background: &str,
         weapon: &str,
-    ) -> Result<(), Error> {
+    ) -> Result<u64, Error> {
         self.start_game_seeded(game_id, "0", false, species, background, weapon)
     }
 
-    /// Continue a saved game by selecting it's game ID.
+    /// Continue a saved game by selecting it's game ID. Returns the seed of the
+    /// game (see [`Webtile::game_seed`]).
     ///
     /// # Arguments
     ///
@@ -40,14 +51,18 @@ impl Webtile {
     ///
     /// ```no_run
     /// // Continue a game on "dcss-web-trunk"
-    /// webtile.continue_game("dcss-web-trunk")?;
+    /// let seed = webtile.continue_game("dcss-web-trunk")?;
     /// ```
-    pub fn continue_game(&mut self, game_id: &str) -> Result<(), Error> {
+    pub fn continue_game(&mut self, game_id: &str) -> Result<u64, Error> {
         self.start_game_seeded(game_id, "", false, "", "", "")
     }
 
     /// Start an seeded game by selecting the game_id, the seed and the character's
-    /// specifications.
+    /// specifications. If an [crate::RcProfile] was selected (see
+    /// [`Webtile::set_rc_profile`]), it is written to the RC file before the game starts.
+    ///
+    /// Returns the seed of the game. If a seed was requested (anything other than
+    /// `0`), returns an [Error::SeedMismatch] if the game was started with another seed.
     ///
     /// # Arguments
     ///
@@ -63,7 +78,8 @@ impl Webtile {
     /// ```no_run
     /// // Start a game on "dcss-web-trunk", for the "123" seed (pregenerated) for a
     /// // Minotaur (b), Berserker (i), with a mace (b)
-    /// webtile.start_game_seeded("dcss-web-trunk", "123", true, "b", "f", "b")?;
+    /// let seed = webtile.start_game_seeded("dcss-web-trunk", "123", true, "b", "f", "b")?;
+    /// assert_eq!(seed, 123);
     /// ```
     pub fn start_game_seeded(
         &mut self,
@@ -73,13 +89,18 @@ impl Webtile {
         species: &str,
         background: &str,
         weapon: &str,
-    ) -> Result<(), Error> {
+    ) -> Result<u64, Error> {
+        self.apply_rc_profile(game_id)?;
+
+        // Only keep the seed shown for this game
+        self.seen_seed = None;
+
         self.write_json(json!({"msg": "play", "game_id": game_id}))?;
 
         let mut newgame_count = 0;
         loop {
             match self.read_until("map", None, None) {
-                Ok(_) => return Ok(()),
+                Ok(_) => break,
                 Err(e) => match e {
                     Error::Blocking(BlockingError::SeedSelection) => {
                         self.write_key("-")?;
@@ -105,9 +126,143 @@ impl Webtile {
                 },
             };
         }
+
+        // Custom seeds are written to the log when the game starts, otherwise
+        // the seed has to be read from the version screen
+        let actual = match self.seen_seed.take() {
+            Some(actual) => actual,
+            None => self.game_seed()?,
+        };
+
+        match seed.parse::<u64>() {
+            Ok(requested) if requested != 0 && requested != actual => {
+                Err(Error::SeedMismatch { requested, actual })
+            }
+            _ => Ok(actual),
+        }
     }
 
-    /// Save a game by sending the `CTRL + S` command.
+    /// Get the seed of the current game, by opening the version screen (`?V`)
+    /// and closing it. The messages received meanwhile are added to the queue read
+    /// by [`Webtile::get_message`] afterwards, except the screens opened and
+    /// closed (`ui-push` and `ui-pop`).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// let seed = webtile.game_seed()?;
+    /// ```
+    pub fn game_seed(&mut self) -> Result<u64, Error> {
+        self.seen_seed = None;
+        self.received_messages.hold();
+
+        // Help menu, followed by the version screen, then close both
+        let keys = [
+            ("?", "ui-push"),
+            ("V", "ui-push"),
+            ("key_esc", "ui-pop"),
+            ("key_esc", "ui-pop"),
+        ];
+        let result = 'read: {
+            for (key, msg) in keys {
+                if let Err(e) = self.write_key(key) {
+                    break 'read Err(e);
+                }
+                if let Err(e) = self.read_until(msg, None, None) {
+                    break 'read Err(e);
+                }
+            }
+            Ok(())
+        };
+
+        // Other messages (e.g. "player", "map") are still needed by the caller
+        let held = self.received_messages.release();
+        let mut replayed = Ok(());
+        for message in held {
+            if matches!(message["msg"].as_str(), Some("ui-push" | "ui-pop")) {
+                continue;
+            }
+            if replayed.is_ok() {
+                replayed = self.received_messages.push(&message);
+            }
+        }
+
+        result?;
+        replayed?;
+        self.seen_seed.take().ok_or(Error::SeedNotFound)
+    }
+
+    /// Write the character dump (by sending the `#` command) and download it from
+    /// the server's morgue URL, on the same host as the websocket. Returns the content
+    /// of the dump, which can be parsed with `dcss_data::CharacterDump`. Returns an
+    /// [Error::DumpFailed] if the game does not confirm, within 10 seconds, that the
+    /// dump was written.
+    ///
+    /// # Arguments
+    ///
+    /// * `morgue_path` - An optional string slice of the path to the dump on the
+    ///   server, where `%n` is replaced by the username. [None] for the default
+    ///   path ([DEFAULT_MORGUE_PATH]).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// // Download the dump from http://localhost:8080/morgue/Username/Username.txt
+    /// let dump = webtile.character_dump(None)?;
+    ///
+    /// // Server with another morgue location
+    /// let dump = webtile.character_dump(Some("/dumps/%n.txt"))?;
+    /// ```
+    pub fn character_dump(&mut self, morgue_path: Option<&str>) -> Result<String, Error> {
+        let username = self.username.clone().ok_or(Error::NotLoggedIn)?;
+
+        // Only look for the log message received after the command
+        self.char_dumped = false;
+
+        self.write_key("#")?;
+
+        // Wait for the "Char dumped to 'Username.txt'." log message, each read
+        // limited to the time left
+        let deadline = Instant::now() + DUMP_TIMEOUT;
+        let waited = 'wait: {
+            while !self.char_dumped {
+                let left = deadline.saturating_duration_since(Instant::now());
+                if left.is_zero() {
+                    break 'wait Err(Error::DumpFailed);
+                }
+                if let Err(e) = self.set_read_timeout(Some(left)) {
+                    break 'wait Err(Error::Io(e));
+                }
+
+                match self.read_until("msgs", None, None) {
+                    Ok(()) => (),
+                    Err(Error::Websocket(tungstenite::Error::Io(e)))
+                        if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                    {
+                        break 'wait Err(Error::DumpFailed)
+                    }
+                    Err(e) => break 'wait Err(e),
+                }
+            }
+            Ok(())
+        };
+
+        self.set_read_timeout(None)?;
+        waited?;
+
+        let url = format!(
+            "{}{}",
+            http_base_url(&self.url),
+            morgue_path
+                .unwrap_or(DEFAULT_MORGUE_PATH)
+                .replace("%n", &username)
+        );
+
+        http_get(&url)
+    }
+
+    /// Save a game by sending the `CTRL + S` command. Restores the RC file
+    /// replaced by an [crate::RcProfile], if any.
     ///
     /// # Example
     ///
@@ -119,11 +274,13 @@ impl Webtile {
 
         self.read_until("go_lobby", None, None)?;
 
+        self.restore_rc_file()?;
+
         Ok(())
     }
 
     /// Quit the game (same result as dying), by sending a `CTRL + Q` and
-    /// answering `yes`.
+    /// answering `yes`. Restores the RC file replaced by an [crate::RcProfile], if any.
     ///
     /// # Example
     ///
@@ -156,7 +313,7 @@ impl Webtile {
         loop {
             self.write_key("key_esc")?;
             match self.read_until("go_lobby", None, None) {
-                Ok(_) => return Ok(()),
+                Ok(_) => return self.restore_rc_file(),
                 Err(e) => match e {
                     Error::Blocking(BlockingError::More) => (),
                     _ => return Err(e),
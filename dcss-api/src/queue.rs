@@ -0,0 +1,252 @@
+use crate::Error;
+use crate::Webtile;
+use serde_json::Value;
+use std::collections::VecDeque;
+
+/// What to do when a message is received while the queue of received messages is
+/// full (see [`Webtile::set_queue_capacity`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Remove the oldest message in the queue to make room for the new one.
+    DropOldest,
+    /// Do not add the new message and return an [Error::QueueFull].
+    Error,
+}
+
+/// Queue of messages received from DCSS, read by the user through
+/// [`Webtile::get_message`].
+#[derive(Debug)]
+pub(crate) struct MessageQueue {
+    /// [VecDeque] of messages received from DCSS.
+    messages: VecDeque<Value>,
+    /// Maximum number of messages in the queue ([None] for unbounded).
+    capacity: Option<usize>,
+    /// What to do when a message is received while the queue is full.
+    policy: OverflowPolicy,
+    /// Types of message (the "msg" field) that are not added to the queue.
+    filters: Vec<String>,
+    /// Messages held back instead of being added to the queue (e.g. while reading
+    /// the version screen), see [`MessageQueue::hold`].
+    held: Option<Vec<Value>>,
+}
+
+impl MessageQueue {
+    /// Create an unbounded queue, without any filters.
+    pub(crate) fn new() -> Self {
+        Self {
+            messages: VecDeque::new(),
+            capacity: None,
+            policy: OverflowPolicy::DropOldest,
+            filters: Vec::new(),
+            held: None,
+        }
+    }
+
+    /// Add a message to the back of the queue, unless its type is filtered. Returns
+    /// an [Error::QueueFull] if the queue is full and the policy is [OverflowPolicy::Error].
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - A [serde_json::Value] received from DCSS.
+    pub(crate) fn push(&mut self, message: &Value) -> Result<(), Error> {
+        let message_msg = message["msg"].as_str().unwrap_or("");
+        if self.filters.iter().any(|filter| filter == message_msg) {
+            return Ok(());
+        }
+
+        if let Some(held) = &mut self.held {
+            held.push(message.to_owned());
+            return Ok(());
+        }
+
+        if let Some(capacity) = self.capacity {
+            if self.messages.len() >= capacity {
+                match self.policy {
+                    OverflowPolicy::DropOldest => {
+                        self.messages.pop_front();
+                    }
+                    OverflowPolicy::Error => return Err(Error::QueueFull(capacity)),
+                }
+            }
+        }
+
+        // A capacity of 0 keeps nothing
+        if self.capacity != Some(0) {
+            self.messages.push_back(message.to_owned());
+        }
+
+        Ok(())
+    }
+
+    /// Hold back the messages received, until [`MessageQueue::release`].
+    pub(crate) fn hold(&mut self) {
+        self.held = Some(Vec::new());
+    }
+
+    /// Stop holding back the messages received, and return the ones held, to be
+    /// added to the queue with [`MessageQueue::push`].
+    pub(crate) fn release(&mut self) -> Vec<Value> {
+        self.held.take().unwrap_or_default()
+    }
+
+    pub(crate) fn pop_front(&mut self) -> Option<Value> {
+        self.messages.pop_front()
+    }
+
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = &Value> {
+        self.messages.iter()
+    }
+}
+
+impl Webtile {
+    /// Limit the number of messages kept in the queue read by [`Webtile::get_message`].
+    /// By default, the queue is unbounded. If the queue is already larger than the
+    /// capacity, the oldest messages are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - An optional [usize] of the maximum number of messages ([None] for
+    ///   unbounded).
+    /// * `policy` - An [OverflowPolicy] of what to do when a message is received while
+    ///   the queue is full.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// // Keep the last 1000 messages
+    /// webtile.set_queue_capacity(Some(1000), OverflowPolicy::DropOldest);
+    /// ```
+    pub fn set_queue_capacity(&mut self, capacity: Option<usize>, policy: OverflowPolicy) {
+        let queue = &mut self.received_messages;
+        queue.capacity = capacity;
+        queue.policy = policy;
+
+        if let Some(capacity) = capacity {
+            while queue.messages.len() > capacity {
+                queue.messages.pop_front();
+            }
+        }
+    }
+
+    /// Do not add a type of message (the "msg" field, e.g. `ping`) to the queue read by
+    /// [`Webtile::get_message`]. Filtered messages are still checked for blocking errors
+    /// and sent to the subscribed callbacks (see [`Webtile::on`]), but can't be found
+    /// with [`Webtile::find_message`].
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - A string slice of the type of message to filter.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// webtile.filter_message("ping");
+    /// webtile.filter_message("ui-state-sync");
+    /// ```
+    pub fn filter_message(&mut self, msg: &str) {
+        let filters = &mut self.received_messages.filters;
+        if !filters.iter().any(|filter| filter == msg) {
+            filters.push(msg.to_owned());
+        }
+    }
+
+    /// Remove a filter added with [`Webtile::filter_message`].
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - A string slice of the type of message to stop filtering.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// webtile.unfilter_message("ping");
+    /// ```
+    pub fn unfilter_message(&mut self, msg: &str) {
+        self.received_messages
+            .filters
+            .retain(|filter| filter != msg);
+    }
+
+    /// Find the most recent message of a type (the "msg" field) in the queue, without
+    /// removing it from the queue. Returns [None] if no message of that type is queued.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - A string slice of the type of message to find.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// if let Some(player) = webtile.find_message("player") {
+    ///     println!("{:?}", player["hp"]);
+    /// }
+    /// ```
+    pub fn find_message(&self, msg: &str) -> Option<&Value> {
+        self.find_message_by(|message| message["msg"] == msg)
+    }
+
+    /// Find the most recent message in the queue for which the predicate returns
+    /// `true`, without removing it from the queue.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - A function that takes a message (as a [serde_json::Value]).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// // Find the last "input_mode" message with a "mode" of 1
+    /// let input_mode = webtile
+    ///     .find_message_by(|message| message["msg"] == "input_mode" && message["mode"] == 1);
+    /// ```
+    pub fn find_message_by<P>(&self, predicate: P) -> Option<&Value>
+    where
+        P: Fn(&Value) -> bool,
+    {
+        self.received_messages
+            .iter()
+            .rev()
+            .find(|message| predicate(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_message_queue() {
+        // Unbounded, with a filter
+        let mut queue = MessageQueue::new();
+        queue.filters.push("ping".to_owned());
+        queue.push(&json!({"msg": "ping"})).unwrap();
+        queue.push(&json!({"msg": "player", "hp": 10})).unwrap();
+        assert_eq!(queue.messages.len(), 1);
+
+        // Drop oldest
+        queue.capacity = Some(2);
+        queue.push(&json!({"msg": "map"})).unwrap();
+        queue.push(&json!({"msg": "msgs"})).unwrap();
+        assert_eq!(queue.messages.len(), 2);
+        assert_eq!(queue.pop_front(), Some(json!({"msg": "map"})));
+
+        // Error
+        queue.policy = OverflowPolicy::Error;
+        queue.push(&json!({"msg": "map"})).unwrap();
+        let result = queue.push(&json!({"msg": "player"}));
+        assert!(matches!(result, Err(Error::QueueFull(2))));
+        assert_eq!(queue.messages.len(), 2);
+
+        // Held back, then added again
+        let mut queue = MessageQueue::new();
+        queue.hold();
+        queue.push(&json!({"msg": "player"})).unwrap();
+        assert!(queue.messages.is_empty());
+        for message in queue.release() {
+            queue.push(&message).unwrap();
+        }
+        assert_eq!(queue.messages.len(), 1);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Built-in RC file profiles that can be applied automatically by
+/// [`crate::Webtile::start_game`], [`crate::Webtile::start_game_seeded`] and
+/// [`crate::Webtile::continue_game`] (see [`crate::Webtile::set_rc_profile`]).
+///
+/// All profiles disable the 'more' prompts (i.e. `show_more` and the
+/// `force_more_message` list), which would otherwise return a
+/// [`crate::BlockingError::More`] in the middle of a bot's actions, and the
+/// autopickup (items are picked up with `,`), which can stop a move or an
+/// explore with a prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RcProfile {
+    /// Only the options required for a bot to play: no 'more' prompts and no
+    /// travel, explore or rest delays (`bot-minimal`).
+    BotMinimal,
+    /// Same as [`RcProfile::BotMinimal`], but also removes animations and the
+    /// travel trail, to reduce the amount of data sent by DCSS (`bot-fast`).
+    BotFast,
+    /// No 'more' prompts, but keeps the default travel, explore and rest
+    /// delays, so that a human spectator can follow the game (`human`).
+    Human,
+}
+
+impl RcProfile {
+    /// Content of the RC file for the profile.
+    pub fn rc_content(&self) -> &'static str {
+        match self {
+            Self::BotMinimal => concat!(
+                "show_more = false\n",
+                "force_more_message =\n",
+                "default_autopickup = false\n",
+                "travel_delay = -1\n",
+                "explore_delay = -1\n",
+                "rest_delay = -1\n",
+            ),
+            Self::BotFast => concat!(
+                "show_more = false\n",
+                "force_more_message =\n",
+                "default_autopickup = false\n",
+                "travel_delay = -1\n",
+                "explore_delay = -1\n",
+                "rest_delay = -1\n",
+                "use_animations =\n",
+                "show_travel_trail = false\n",
+                "view_delay = 0\n",
+            ),
+            Self::Human => concat!(
+                "show_more = false\n",
+                "force_more_message =\n",
+                "default_autopickup = false\n",
+                "travel_delay = 20\n",
+                "explore_delay = -1\n",
+                "rest_delay = 0\n",
+            ),
+        }
+    }
+
+    /// Name of the profile (e.g. `bot-minimal`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::BotMinimal => "bot-minimal",
+            Self::BotFast => "bot-fast",
+            Self::Human => "human",
+        }
+    }
+}
+
+impl fmt::Display for RcProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for RcProfile {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "bot-minimal" => Ok(Self::BotMinimal),
+            "bot-fast" => Ok(Self::BotFast),
+            "human" => Ok(Self::Human),
+            _ => Err(Error::UnknownRcProfile(name.to_owned())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::api_errors::MORE_TRIGGERS;
+    use std::collections::HashMap;
+
+    /// Final value of each option of an RC file (`+=` appends to the value).
+    fn options(content: &str) -> HashMap<&str, String> {
+        let mut options = HashMap::new();
+
+        for line in content.lines() {
+            if let Some((option, value)) = line.split_once("+=") {
+                let current: &mut String = options.entry(option.trim()).or_default();
+                current.push_str(value.trim());
+            } else if let Some((option, value)) = line.split_once('=') {
+                options.insert(option.trim(), value.trim().to_owned());
+            }
+        }
+
+        options
+    }
+
+    #[test]
+    fn test_profiles_prevent_more() {
+        for profile in [RcProfile::BotMinimal, RcProfile::BotFast, RcProfile::Human] {
+            let options = options(profile.rc_content());
+
+            for (option, value) in MORE_TRIGGERS {
+                assert_eq!(
+                    options.get(option).map(String::as_str),
+                    Some(value),
+                    "{profile}: {option}"
+                );
+            }
+
+            // Picking up items when moving can open a pickup menu
+            assert_eq!(options["default_autopickup"], "false", "{profile}");
+        }
+
+        // A 'more' prompt forced by a later option is detected
+        let options = options("force_more_message =\nforce_more_message += You die");
+        assert_ne!(options["force_more_message"], "");
+    }
+
+    #[test]
+    fn test_profile_names() {
+        for profile in [RcProfile::BotMinimal, RcProfile::BotFast, RcProfile::Human] {
+            assert_eq!(profile.name().parse::<RcProfile>().unwrap(), profile);
+        }
+
+        assert!(matches!(
+            "bot-slow".parse::<RcProfile>(),
+            Err(Error::UnknownRcProfile(_))
+        ));
+    }
+}
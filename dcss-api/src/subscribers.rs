@@ -0,0 +1,86 @@
+use crate::Webtile;
+use serde_json::Value;
+use std::fmt;
+
+/// Callback called on each message of a specific type (the "msg" field), as it is
+/// received by [`Webtile::read_until`].
+pub(crate) struct Subscriber {
+    /// Value of the "msg" field of the messages sent to the callback (`*` for all messages).
+    msg: String,
+    /// Function called with each message.
+    callback: Box<dyn FnMut(&Value) + Send>,
+}
+
+impl fmt::Debug for Subscriber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscriber")
+            .field("msg", &self.msg)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Webtile {
+    /// Subscribe a callback to a type of message (the "msg" field, e.g. `player` or
+    /// `map`), or to all messages with `*`. While reading the websocket (see
+    /// [`Webtile::read_until`]), each callback is called, in the order they were
+    /// subscribed, as soon as a matching message is received. The messages are still
+    /// added to the queue read by [`Webtile::get_message`].
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - A string slice of the type of message (or `*` for all messages).
+    /// * `callback` - A function called with each message, as a [serde_json::Value].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// // Print the HP of the character every time it is sent
+    /// webtile.on("player", |message| {
+    ///     if let Some(hp) = message["hp"].as_u64() {
+    ///         println!("HP: {}", hp);
+    ///     }
+    /// });
+    ///
+    /// // Keep a `dcss_data::CrawlData` up to date
+    /// let data = Arc::new(Mutex::new(CrawlData::init(7, "0.32")));
+    /// let subscriber = Arc::clone(&data);
+    /// webtile.on("*", move |message| {
+    ///     subscriber.lock().unwrap().process_json(message).unwrap();
+    /// });
+    /// ```
+    pub fn on<F>(&mut self, msg: &str, callback: F)
+    where
+        F: FnMut(&Value) + Send + 'static,
+    {
+        self.subscribers.push(Subscriber {
+            msg: msg.to_owned(),
+            callback: Box::new(callback),
+        });
+    }
+
+    /// Remove all callbacks subscribed to a type of message (see [`Webtile::on`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - A string slice of the type of message (or `*`).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// webtile.off("player");
+    /// ```
+    pub fn off(&mut self, msg: &str) {
+        self.subscribers.retain(|subscriber| subscriber.msg != msg);
+    }
+
+    /// Call every callback subscribed to the type of the message.
+    pub(crate) fn notify_subscribers(&mut self, message: &Value) {
+        let message_msg = message["msg"].as_str().unwrap_or("");
+
+        for subscriber in self.subscribers.iter_mut() {
+            if subscriber.msg == "*" || subscriber.msg == message_msg {
+                (subscriber.callback)(message);
+            }
+        }
+    }
+}
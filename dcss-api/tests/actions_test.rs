@@ -0,0 +1,70 @@
+mod common;
+
+use dcss_api::{Action, BlockingError, Direction, Webtile};
+
+#[test]
+fn act_move_and_pickup() {
+    let game_id = std::env::var("GAME_ID").unwrap();
+
+    // Safe test -- login start game, quit, and then test
+    common::reset_test("Username", game_id.as_str());
+
+    // Connect to DCSS Webtile
+    let mut webtile =
+        Webtile::connect("ws://localhost:8080/socket", 0, "0.32").expect("Failed to connect");
+
+    // Log in (to a user called "Username", with a password "Password")
+    let _game_ids = webtile
+        .login_with_credentials("Username", "Password")
+        .expect("Failed to login");
+
+    webtile
+        .start_game_seeded(game_id.as_str(), "1", false, "b", "f", "b")
+        .expect("Failed to start game");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    // A move is confirmed when DCSS is ready for the next command
+    let outcome = webtile
+        .act(Action::Move(Direction::N))
+        .expect("Failed to move");
+    assert!(outcome.completed);
+    assert!(outcome.blocking.is_none());
+    assert!(webtile.find_message("player").is_some());
+
+    let outcome = webtile
+        .act(Action::Move(Direction::S))
+        .expect("Failed to move");
+    assert!(outcome.completed);
+
+    // Moving into a wall does not update the character, but is still confirmed
+    for _ in 0..20 {
+        let outcome = webtile
+            .act(Action::Move(Direction::W))
+            .expect("Failed to move");
+        assert!(outcome.completed);
+    }
+
+    // Drop both items, and pick them up, which opens a menu
+    let outcome = webtile.act(Action::Drop('a')).expect("Failed to drop");
+    assert!(outcome.blocking.is_none());
+    let outcome = webtile.act(Action::Drop('b')).expect("Failed to drop");
+    assert!(outcome.blocking.is_none());
+
+    let outcome = webtile.act(Action::PickUp).expect("Failed to pick up");
+    assert!(matches!(outcome.blocking, Some(BlockingError::Pickup)));
+
+    webtile.write_key("key_esc").expect("Failed to write key");
+    webtile
+        .read_until("msgs", None, None)
+        .expect("Failed to read");
+
+    // Resting is confirmed when ready for input
+    let outcome = webtile.act(Action::Rest).expect("Failed to rest");
+    assert!(outcome.completed);
+
+    webtile.quit_game().expect("Failed to quit game");
+
+    webtile.disconnect().expect("Failed to disconnect");
+}
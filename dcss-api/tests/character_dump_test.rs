@@ -0,0 +1,41 @@
+mod common;
+
+use dcss_api::Webtile;
+
+#[test]
+fn character_dump() {
+    let game_id = std::env::var("GAME_ID").unwrap();
+
+    // Safe test -- login start game, quit, and then test
+    common::reset_test("Username", game_id.as_str());
+
+    // Connect to DCSS Webtile
+    let mut webtile =
+        Webtile::connect("ws://localhost:8080/socket", 0, "0.32").expect("Failed to connect");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    // Log in (to a user called "Username", with a password "Password")
+    let _game_ids = webtile
+        .login_with_credentials("Username", "Password")
+        .expect("Failed to login");
+
+    webtile
+        .start_game_seeded(game_id.as_str(), "1", false, "b", "f", "b")
+        .expect("Failed to start game");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    let dump = webtile
+        .character_dump(None)
+        .expect("Failed to get character dump");
+
+    assert!(dump.contains("Username the"));
+    assert!(dump.contains("Minotaur Berserker"));
+
+    webtile.quit_game().expect("Failed to quit game");
+
+    webtile.disconnect().expect("Failed to disconnect");
+}
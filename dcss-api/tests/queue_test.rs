@@ -0,0 +1,90 @@
+use dcss_api::{Error, OverflowPolicy, Webtile};
+
+#[test]
+fn bounded_queue() {
+    let mut webtile =
+        Webtile::connect("ws://localhost:8080/socket", 0, "0.32").expect("Failed to connect.");
+
+    // Only keep the last 2 messages
+    webtile.set_queue_capacity(Some(2), OverflowPolicy::DropOldest);
+
+    webtile
+        .login_with_credentials("Username", "Password")
+        .expect("Login failed.");
+
+    let mut queue_count = 0;
+    while webtile.get_message().is_some() {
+        queue_count += 1;
+    }
+    assert!(queue_count <= 2);
+
+    // Error when full
+    webtile.set_queue_capacity(Some(0), OverflowPolicy::Error);
+
+    let result = webtile.get_rc_file("dcss-0.32");
+    assert!(matches!(result, Err(Error::QueueFull(0))));
+
+    // The message that could not be queued is not found by the next read
+    webtile.set_queue_capacity(None, OverflowPolicy::Error);
+    webtile
+        .get_rc_file("dcss-0.32")
+        .expect("Failed to read the RC file.");
+
+    webtile.disconnect().expect("Failed to disconnect");
+}
+
+#[test]
+fn filtered_queue() {
+    let mut webtile =
+        Webtile::connect("ws://localhost:8080/socket", 0, "0.32").expect("Failed to connect.");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    webtile.filter_message("ping");
+    webtile.filter_message("html");
+
+    webtile
+        .login_with_credentials("Username", "Password")
+        .expect("Login failed.");
+
+    assert!(webtile.find_message("login_success").is_some());
+    assert!(webtile.find_message("html").is_none());
+    assert!(webtile
+        .find_message_by(
+            |message| message["msg"] == "login_success" && message["username"] == "Username"
+        )
+        .is_some());
+
+    while let Some(message) = webtile.get_message() {
+        assert_ne!(message["msg"], "ping");
+        assert_ne!(message["msg"], "html");
+    }
+
+    webtile.disconnect().expect("Failed to disconnect");
+}
+
+#[test]
+fn replies_not_queued() {
+    let mut webtile =
+        Webtile::connect("ws://localhost:8080/socket", 0, "0.32").expect("Failed to connect.");
+
+    // Nothing is kept, and the replies to the requests are filtered
+    webtile.set_queue_capacity(Some(0), OverflowPolicy::DropOldest);
+    webtile.filter_message("login_success");
+    webtile.filter_message("set_game_links");
+    webtile.filter_message("rcfile_contents");
+
+    let game_ids = webtile
+        .login_with_credentials("Username", "Password")
+        .expect("Login failed.");
+    assert!(!game_ids.is_empty());
+
+    webtile
+        .get_rc_file(game_ids[0].as_str())
+        .expect("Failed to read the RC file.");
+
+    assert!(webtile.get_message().is_none());
+
+    webtile.disconnect().expect("Failed to disconnect");
+}
@@ -0,0 +1,197 @@
+mod common;
+
+use dcss_api::{BlockingError, Error, RcProfile, Webtile};
+
+/// RC file with a 'more' prompt on the welcome message, shown when the game starts
+const MORE_RC: &str = "show_more = true\nforce_more_message += Welcome";
+
+fn play_with_profile(profile: RcProfile) {
+    let game_id = std::env::var("GAME_ID").unwrap();
+
+    // Safe test -- login start game, quit, and then test
+    common::reset_test("Username", game_id.as_str());
+
+    // Connect to DCSS Webtile
+    let mut webtile =
+        Webtile::connect("ws://localhost:8080/socket", 0, "0.32").expect("Failed to connect");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    // Log in (to a user called "Username", with a password "Password")
+    let _game_ids = webtile
+        .login_with_credentials("Username", "Password")
+        .expect("Failed to login");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    // RC file with 'more' prompts (see `more_without_profile`)
+    webtile
+        .set_rc_file(game_id.as_str(), MORE_RC)
+        .expect("Failed to write");
+
+    webtile.set_rc_profile(Some(profile));
+
+    // Would fail with a BlockingError::More without the profile
+    webtile
+        .start_game_seeded(game_id.as_str(), "1", false, "b", "f", "b")
+        .expect("Failed to start game");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    // Walk around, search and rest, none of which should trigger a 'more' prompt
+    for key in ["key_dir_n", "key_dir_s", "key_dir_e", "key_dir_w", "s", "5"] {
+        webtile.write_key(key).expect("Failed to write key");
+
+        let result = webtile.read_until("input_mode", Some("mode"), Some(1));
+
+        assert!(!matches!(result, Err(Error::Blocking(BlockingError::More))));
+
+        // Empty message queue;
+        while webtile.get_message().is_some() {}
+    }
+
+    webtile.quit_game().expect("Failed to quit game");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    // Previous RC file restored after quitting
+    let rc_file = webtile
+        .get_rc_file(game_id.as_str())
+        .expect("Failed to read.");
+
+    assert_eq!(MORE_RC, rc_file);
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    webtile
+        .set_rc_file(game_id.as_str(), "show_more = false\nrest_delay = -1")
+        .expect("Failed to write");
+
+    webtile.disconnect().expect("Failed to disconnect");
+}
+
+#[test]
+fn more_without_profile() {
+    let game_id = std::env::var("GAME_ID").unwrap();
+
+    // Safe test -- login start game, quit, and then test
+    common::reset_test("Username", game_id.as_str());
+
+    // Connect to DCSS Webtile
+    let mut webtile =
+        Webtile::connect("ws://localhost:8080/socket", 0, "0.32").expect("Failed to connect");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    // Log in (to a user called "Username", with a password "Password")
+    let _game_ids = webtile
+        .login_with_credentials("Username", "Password")
+        .expect("Failed to login");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    webtile
+        .set_rc_file(game_id.as_str(), MORE_RC)
+        .expect("Failed to write");
+
+    // Without a profile, the welcome message stops the game start
+    let result = webtile.start_game_seeded(game_id.as_str(), "1", false, "b", "f", "b");
+
+    assert!(matches!(result, Err(Error::Blocking(BlockingError::More))));
+
+    webtile.write_key("key_esc").expect("Failed to write key");
+    webtile.quit_game().expect("Failed to quit game");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    webtile
+        .set_rc_file(game_id.as_str(), "show_more = false\nrest_delay = -1")
+        .expect("Failed to write");
+
+    webtile.disconnect().expect("Failed to disconnect");
+}
+
+#[test]
+fn bot_minimal_profile() {
+    play_with_profile(RcProfile::BotMinimal);
+}
+
+#[test]
+fn bot_fast_profile() {
+    play_with_profile(RcProfile::BotFast);
+}
+
+#[test]
+fn human_profile() {
+    play_with_profile(RcProfile::Human);
+}
+
+#[test]
+fn profile_restored_on_save() {
+    let game_id = std::env::var("GAME_ID").unwrap();
+
+    // Safe test -- login start game, quit, and then test
+    common::reset_test("Username", game_id.as_str());
+
+    // Connect to DCSS Webtile
+    let mut webtile =
+        Webtile::connect("ws://localhost:8080/socket", 0, "0.32").expect("Failed to connect");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    // Log in (to a user called "Username", with a password "Password")
+    let _game_ids = webtile
+        .login_with_credentials("Username", "Password")
+        .expect("Failed to login");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    webtile
+        .set_rc_file(game_id.as_str(), "this is a test")
+        .expect("Failed to write");
+
+    webtile.set_rc_profile(Some(RcProfile::BotMinimal));
+
+    webtile
+        .start_game(game_id.as_str(), "b", "f", "b")
+        .expect("Failed to start game");
+
+    webtile.save_game().expect("Failed to save game.");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    let rc_file = webtile
+        .get_rc_file(game_id.as_str())
+        .expect("Failed to read.");
+
+    assert_eq!("this is a test", rc_file);
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    webtile
+        .continue_game(game_id.as_str())
+        .expect("Failed to continue game");
+
+    webtile.quit_game().expect("Failed to quit game");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    webtile
+        .set_rc_file(game_id.as_str(), "show_more = false\nrest_delay = -1")
+        .expect("Failed to write");
+
+    webtile.disconnect().expect("Failed to disconnect");
+}
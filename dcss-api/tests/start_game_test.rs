@@ -25,10 +25,12 @@ fn start_game_seeded() {
     // Empty message queue;
     while webtile.get_message().is_some() {}
 
-    webtile
+    let seed = webtile
         .start_game_seeded(game_id.as_str(), "1", true, "b", "f", "b")
         .expect("Failed to start game");
 
+    assert_eq!(seed, 1);
+
     // Get last message
     let mut msgs = from_str("{}").unwrap();
     while let Some(message) = webtile.get_message() {
@@ -61,10 +63,12 @@ fn start_game_seeded() {
     // Empty message queue;
     while webtile.get_message().is_some() {}
 
-    webtile
+    let seed = webtile
         .start_game_seeded(game_id.as_str(), "158985", false, "b", "f", "b")
         .expect("Failed to start game");
 
+    assert_eq!(seed, 158985);
+
     // Get last message
     let mut msgs = from_str("{}").unwrap();
     while let Some(message) = webtile.get_message() {
@@ -154,7 +158,7 @@ fn save_game_continue() {
     // Empty message queue;
     while webtile.get_message().is_some() {}
 
-    webtile
+    let seed = webtile
         .start_game(game_id.as_str(), "b", "f", "b")
         .expect("Failed to start game");
 
@@ -176,10 +180,12 @@ fn save_game_continue() {
 
     assert!(last_message["msg"] == "go_lobby");
 
-    webtile
+    let continued_seed = webtile
         .continue_game(game_id.as_str())
         .expect("Failed to continue game");
 
+    assert_eq!(seed, continued_seed);
+
     // Get last message
     let mut last_message = from_str("{}").unwrap();
     while let Some(message) = webtile.get_message() {
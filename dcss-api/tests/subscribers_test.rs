@@ -0,0 +1,64 @@
+use dcss_api::Webtile;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn subscribed_callbacks() {
+    let mut webtile =
+        Webtile::connect("ws://localhost:8080/socket", 0, "0.32").expect("Failed to connect.");
+
+    // Empty message queue;
+    while webtile.get_message().is_some() {}
+
+    // Record the order in which the callbacks are called
+    let received = Arc::new(Mutex::new(vec![]));
+
+    let subscriber = Arc::clone(&received);
+    webtile.on("login_success", move |message| {
+        subscriber
+            .lock()
+            .unwrap()
+            .push(format!("first {}", message["msg"].as_str().unwrap()));
+    });
+
+    let subscriber = Arc::clone(&received);
+    webtile.on("*", move |message| {
+        subscriber
+            .lock()
+            .unwrap()
+            .push(format!("all {}", message["msg"].as_str().unwrap()));
+    });
+
+    webtile
+        .login_with_credentials("Username", "Password")
+        .expect("Login failed.");
+
+    let login_index = received
+        .lock()
+        .unwrap()
+        .iter()
+        .position(|x| x == "first login_success")
+        .expect("Callback not called.");
+
+    assert_eq!(
+        received.lock().unwrap()[login_index + 1],
+        "all login_success"
+    );
+
+    // Messages are still added to the queue
+    let mut queue_count = 0;
+    while webtile.get_message().is_some() {
+        queue_count += 1;
+    }
+    assert_eq!(queue_count + 1, received.lock().unwrap().len());
+
+    // No more callbacks after removing them
+    webtile.off("*");
+    webtile.off("login_success");
+    let count = received.lock().unwrap().len();
+
+    webtile.get_rc_file("dcss-0.32").expect("Failed to read.");
+
+    assert_eq!(count, received.lock().unwrap().len());
+
+    webtile.disconnect().expect("Failed to disconnect");
+}
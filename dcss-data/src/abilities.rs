@@ -1,3 +1,5 @@
+use crate::common::{get_str, invalid_field};
+use crate::data_errors::DataError;
 use rustc_hash::FxHashMap;
 use serde_json::Value;
 
@@ -20,24 +22,40 @@ impl Abilities {
         }
     }
 
-    pub(crate) fn process_ability_menu(&mut self, menu_items: Value) {
-        for item in menu_items.as_array().unwrap() {
+    pub(crate) fn process_ability_menu(&mut self, menu_items: Value) -> Result<(), DataError> {
+        let menu_items = menu_items
+            .as_array()
+            .ok_or_else(|| invalid_field("items", "an array"))?;
+
+        for item in menu_items {
             if item["level"] == 2 {
-                let text = item["text"]
-                    .as_str()
-                    .unwrap()
+                let line = get_str(item, "text")?;
+                let text = line
                     .split("  ")
                     .filter(|x| !x.is_empty())
                     .map(|x| x.trim())
                     .collect::<Vec<&str>>();
-                let key = &text[0][0..1];
-                let name = text[0].split(" - ").collect::<Vec<&str>>()[1];
-                let cost = text[1];
-                let failure_chance = text[2].replace('%', "").parse::<u64>().unwrap();
+
+                let parse_error = || DataError::Parse {
+                    what: "ability",
+                    text: line.to_owned(),
+                };
+
+                let (key, name) = text
+                    .first()
+                    .and_then(|ability| ability.split_once(" - "))
+                    .ok_or_else(parse_error)?;
+                let cost = text.get(1).ok_or_else(parse_error)?;
+                let failure_chance = text
+                    .get(2)
+                    .and_then(|chance| chance.replace('%', "").parse::<u64>().ok())
+                    .ok_or_else(parse_error)?;
                 self.abilities
                     .insert(name.to_owned(), Ability::new(key, cost, failure_chance));
             }
         }
+
+        Ok(())
     }
 }
 
@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+/// Errors that can be raised while processing the messages received from
+/// DCSS Webtiles.
+#[derive(Error, Debug)]
+pub enum DataError {
+    #[error("Unknown message type: {0}.")]
+    UnknownMessage(String),
+    #[error("Field '{field}' is missing or is not {expected}.")]
+    InvalidField {
+        field: String,
+        expected: &'static str,
+    },
+    #[error("Failed to parse {what} from: {text}")]
+    Parse { what: &'static str, text: String },
+    #[error("Unknown map feature (MF): {0}.")]
+    UnknownMapFeature(u64),
+    #[error("Monster data without an ID or a name received, and no monster is known.")]
+    UnknownMonster,
+    #[error("Monster description received without a title nor `ready_examine_monster`.")]
+    UnexpectedMonsterDescription,
+}
@@ -0,0 +1,313 @@
+use crate::monsters::Monsters;
+use crate::pickup::Pickup;
+use crate::tiles::Tiles;
+use crate::CrawlData;
+use rustc_hash::FxHashMap;
+use serde_json::Value;
+use std::mem;
+
+/// Place (e.g. `Dungeon`, `Lair`) and depth of a level, as sent in the
+/// "player" message.
+pub type LevelId = (String, u64);
+
+/// Map, monsters and items remembered for a level that is not the current one.
+#[derive(Debug)]
+pub(crate) struct Level {
+    pub(crate) tiles: Tiles,
+    pub(crate) monsters: Monsters,
+    pub(crate) pickup: Pickup,
+}
+
+/// Memory of every level visited. The current level is kept in [CrawlData]
+/// (`tiles`, `monsters` and `pickup`), the others are stored here.
+#[derive(Debug)]
+pub(crate) struct Dungeon {
+    pub(crate) current: Option<LevelId>,
+    pub(crate) levels: FxHashMap<LevelId, Level>,
+    /// If the level changed and no "map" message was received since.
+    pub(crate) entered: bool,
+    /// Current level as it was before a "map" message with the `clear` flag, until
+    /// the next "player" message tells if the level changed.
+    pub(crate) cleared: Option<Level>,
+}
+
+impl Dungeon {
+    pub(crate) fn init() -> Self {
+        Self {
+            current: None,
+            levels: FxHashMap::default(),
+            entered: false,
+            cleared: None,
+        }
+    }
+
+    /// Level described by a (possibly partial) "player" message, or [None] if
+    /// the message contains neither the place nor the depth.
+    pub(crate) fn level_from_message(&self, message: &Value) -> Option<LevelId> {
+        let place = message["place"].as_str();
+        let depth = message["depth"].as_u64();
+
+        if place.is_none() && depth.is_none() {
+            return None;
+        }
+
+        let current = self.current.clone().unwrap_or((String::new(), 0));
+
+        Some((
+            place.map(str::to_owned).unwrap_or(current.0),
+            depth.unwrap_or(current.1),
+        ))
+    }
+}
+
+impl CrawlData {
+    /// Switch the current level, if the "player" message indicates a new place
+    /// or depth. The map of the level left is stored, and the map of the new level
+    /// is restored if it was visited before.
+    ///
+    /// The "map" message with the `clear` flag, sent on every level change, may
+    /// come before the "player" message (see [`CrawlData::clear_level`]).
+    pub(crate) fn update_level(&mut self, message: &Value) {
+        let cleared = self.dungeon.cleared.take();
+
+        let level = self.dungeon.level_from_message(message);
+        let previous = self.dungeon.current.clone();
+
+        match (previous, level) {
+            (Some(previous), Some(level)) if previous != level => {
+                let stored = self.dungeon.levels.remove(&level).unwrap_or_else(|| Level {
+                    tiles: Tiles::init(),
+                    monsters: Monsters::init(),
+                    pickup: Pickup::init(),
+                });
+
+                match cleared {
+                    // The map of the new level was received first: it is the
+                    // current one, and the level left is as it was before
+                    Some(left) => {
+                        self.dungeon.levels.insert(previous, left);
+                        self.merge_level(stored);
+                    }
+                    None => {
+                        let left = Level {
+                            tiles: mem::replace(&mut self.tiles, stored.tiles),
+                            monsters: mem::replace(&mut self.monsters, stored.monsters),
+                            pickup: mem::replace(&mut self.pickup, stored.pickup),
+                        };
+                        self.dungeon.levels.insert(previous, left);
+                        self.dungeon.entered = true;
+                    }
+                }
+
+                self.dungeon.current = Some(level);
+            }
+            (previous, level) => {
+                // Same level (e.g. the Abyss being regenerated), only the map
+                // was sent again
+                if let Some(before) = cleared {
+                    self.merge_level(before);
+                }
+
+                // First level of the game (or first level received)
+                if previous.is_none() {
+                    self.dungeon.current = level;
+                }
+            }
+        }
+    }
+
+    /// Forget the map of the current level, when the "map" message has the
+    /// `clear` flag. The server then sends the whole map again (e.g. a new level,
+    /// or a level regenerated like the Abyss).
+    ///
+    /// If the "player" message of a level change was not received yet, the
+    /// current level is kept aside until it is (see [`CrawlData::update_level`]):
+    /// the map received next may be the one of the new level.
+    pub(crate) fn clear_level(&mut self) {
+        if self.dungeon.entered || self.dungeon.cleared.is_some() {
+            self.tiles = Tiles::init();
+            return;
+        }
+
+        self.dungeon.cleared = Some(Level {
+            tiles: mem::replace(&mut self.tiles, Tiles::init()),
+            monsters: mem::replace(&mut self.monsters, Monsters::init()),
+            pickup: mem::replace(&mut self.pickup, Pickup::init()),
+        });
+    }
+
+    /// Use the monsters and items of a level for the current one, keeping the
+    /// current map. Monsters and items received since are more recent.
+    fn merge_level(&mut self, mut level: Level) {
+        level
+            .monsters
+            .monsters
+            .extend(mem::take(&mut self.monsters.monsters));
+        if self.monsters.examine_loc.is_some() {
+            level.monsters.examine_loc = self.monsters.examine_loc;
+        }
+
+        for coord in mem::take(&mut self.pickup.unknown) {
+            level.pickup.update(coord);
+        }
+        level.pickup.names.extend(mem::take(&mut self.pickup.names));
+
+        self.monsters = level.monsters;
+        self.pickup = level.pickup;
+    }
+
+    /// Place and depth of the current level (e.g. `("Dungeon", 3)`), or [None]
+    /// before the first "player" message.
+    pub fn current_level(&self) -> Option<LevelId> {
+        self.dungeon.current.clone()
+    }
+
+    /// Place and depth of every level visited, including the current one.
+    pub fn visited_levels(&self) -> Vec<LevelId> {
+        self.dungeon
+            .levels
+            .keys()
+            .cloned()
+            .chain(self.dungeon.current.clone())
+            .collect()
+    }
+
+    /// If the level was visited (its map is remembered).
+    ///
+    /// # Arguments
+    ///
+    /// * `place` - A string slice of the place (e.g. `Dungeon`).
+    /// * `depth` - Depth of the level in the place.
+    pub fn level_visited(&self, place: &str, depth: u64) -> bool {
+        let level = (place.to_owned(), depth);
+
+        self.dungeon.current.as_ref() == Some(&level) || self.dungeon.levels.contains_key(&level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_level_memory() {
+        let mut data = CrawlData::init(7, "0.32");
+        data.update_player(&json!({"msg": "player", "place": "Dungeon", "depth": 1}))
+            .unwrap();
+        data.tiles.get_mut((0, 0)).mf = 13;
+
+        // Going down, the new level has an empty map
+        data.update_player(&json!({"msg": "player", "depth": 2}))
+            .unwrap();
+        assert_eq!(data.current_level(), Some(("Dungeon".to_owned(), 2)));
+        assert_eq!(data.tiles.get((0, 0)).mf, 0);
+
+        // Going back up restores the map
+        data.update_player(&json!({"msg": "player", "depth": 1}))
+            .unwrap();
+        assert_eq!(data.tiles.get((0, 0)).mf, 13);
+        assert!(data.level_visited("Dungeon", 2));
+        assert_eq!(data.visited_levels().len(), 2);
+    }
+
+    #[test]
+    fn test_clear_map() {
+        let mut data = CrawlData::init(7, "0.32");
+        data.update_player(&json!({"msg": "player", "place": "Abyss", "depth": 1}))
+            .unwrap();
+        data.update_map(&json!({"msg": "map", "cells": [{"x": 0, "y": 0, "mf": 1}]}))
+            .unwrap();
+
+        // A partial update keeps the map
+        data.update_map(&json!({"msg": "map", "cells": [{"x": 1, "y": 0, "mf": 2}]}))
+            .unwrap();
+        assert_eq!(data.tiles.get((0, 0)).mf, 1);
+
+        // The server sends the whole map again after clearing it
+        data.update_map(
+            &json!({"msg": "map", "clear": true, "cells": [{"x": 1, "y": 0, "mf": 1}]}),
+        )
+        .unwrap();
+        assert_eq!(data.tiles.get((0, 0)).mf, 0);
+        assert_eq!(data.tiles.get((1, 0)).mf, 1);
+    }
+
+    /// Dungeon:1 with an ignored item at (2, 2) and a goblin at (3, 3).
+    fn first_level() -> CrawlData {
+        let mut data = CrawlData::init(7, "0.32");
+        data.update_player(&json!({"msg": "player", "place": "Dungeon", "depth": 1}))
+            .unwrap();
+        data.update_map(&json!({"msg": "map", "clear": true, "cells": [
+            {"x": 2, "y": 2, "mf": 1},
+            {"x": 3, "y": 3, "mf": 1, "mon": {"id": 1, "name": "goblin"}},
+        ]}))
+        .unwrap();
+        data.update_player(&json!({"msg": "player", "pos": {"x": 2, "y": 2}}))
+            .unwrap();
+        data.new_ignore_item_curr_loc();
+        data
+    }
+
+    #[test]
+    fn test_clear_after_level_change() {
+        let mut data = first_level();
+
+        // Down and back up, each "player" message followed by the new map
+        data.update_player(&json!({"msg": "player", "depth": 2}))
+            .unwrap();
+        data.update_map(&json!({"msg": "map", "clear": true, "cells": [
+            {"x": 5, "y": 5, "mf": 1, "mon": {"id": 2, "name": "rat"}},
+        ]}))
+        .unwrap();
+        data.update_player(&json!({"msg": "player", "depth": 1}))
+            .unwrap();
+        data.update_map(&json!({"msg": "map", "clear": true, "cells": [
+            {"x": 2, "y": 2, "mf": 1},
+        ]}))
+        .unwrap();
+
+        assert_eq!(data.tiles.get((5, 5)).mf, 0);
+        assert_eq!(data.pickup.ignore, vec![(2, 2)]);
+        assert!(data.monsters.monsters.contains_key(&1));
+        assert!(!data.monsters.monsters.contains_key(&2));
+    }
+
+    #[test]
+    fn test_clear_before_level_change() {
+        let mut data = first_level();
+
+        // Down and back up, each new map followed by the "player" message
+        data.update_map(&json!({"msg": "map", "clear": true, "cells": [
+            {"x": 5, "y": 5, "mf": 1, "mon": {"id": 2, "name": "rat"}},
+        ]}))
+        .unwrap();
+        data.update_player(&json!({"msg": "player", "depth": 2}))
+            .unwrap();
+        assert_eq!(data.tiles.get((5, 5)).mf, 1);
+        assert!(data.monsters.monsters.contains_key(&2));
+        assert!(!data.monsters.monsters.contains_key(&1));
+
+        data.update_map(&json!({"msg": "map", "clear": true, "cells": [
+            {"x": 2, "y": 2, "mf": 1},
+        ]}))
+        .unwrap();
+        data.update_player(&json!({"msg": "player", "depth": 1}))
+            .unwrap();
+
+        // The level left was stored with its own map
+        assert_eq!(
+            data.dungeon.levels[&("Dungeon".to_owned(), 2)]
+                .tiles
+                .get((5, 5))
+                .mf,
+            1
+        );
+        assert_eq!(data.tiles.get((5, 5)).mf, 0);
+        assert_eq!(data.tiles.get((2, 2)).mf, 1);
+        assert_eq!(data.pickup.ignore, vec![(2, 2)]);
+        assert!(data.monsters.monsters.contains_key(&1));
+        assert!(!data.monsters.monsters.contains_key(&2));
+    }
+}
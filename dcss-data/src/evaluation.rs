@@ -0,0 +1,574 @@
+use rustc_hash::FxHashMap;
+
+use crate::items::armours::ArmourType;
+use crate::items::jewellery::JewelleryType;
+use crate::items::name::ItemName;
+use crate::items::weapons::WeaponType;
+use crate::items::{Item, WearSlots};
+use crate::CrawlData;
+
+/// Resistance (or other protection) given by an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Resistance {
+    Fire,
+    Cold,
+    Poison,
+    Negative,
+    Electricity,
+    Corrosion,
+    Will,
+    SeeInvisible,
+}
+
+/// What changes when equipping an item, in a [Difference].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aspect {
+    Ac,
+    Ev,
+    Sh,
+    /// Encumbrance rating of the armours (lower is better).
+    Encumbrance,
+    /// Raw spell failure added by the armours (lower is better).
+    SpellFailure,
+    /// Average damage of melee attacks, per turn of 10 aut.
+    DamagePerTurn,
+    /// Level of the resistance.
+    Resistance(Resistance),
+}
+
+/// Value of an [Aspect] with the equipped item, and with the item compared.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    pub aspect: Aspect,
+    pub current: f64,
+    pub candidate: f64,
+}
+
+/// Comparison of an item of the inventory with the equipped item it would
+/// replace, created with [`CrawlData::compare_item`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub item_index: usize,
+    /// Inventory slot of the item replaced, if the equipment slot is not empty.
+    pub replaces: Option<usize>,
+    /// Why the character can't use the item (e.g. "Octopodes can't use a
+    /// scale mail"), if so.
+    pub restriction: Option<String>,
+    /// Aspects improved by the item.
+    pub pros: Vec<Difference>,
+    /// Aspects worsened by the item.
+    pub cons: Vec<Difference>,
+}
+
+/// Skills and stats of the character used to evaluate items.
+struct Character<'a> {
+    skills: FxHashMap<&'a str, f64>,
+    str: f64,
+}
+
+/// Value of the aspects for one item. [None] if not known yet (e.g. not
+/// described).
+#[derive(Debug, Default)]
+struct Evaluation {
+    ac: Option<f64>,
+    ev: Option<f64>,
+    sh: Option<f64>,
+    encumbrance: Option<f64>,
+    spell_failure: Option<f64>,
+    damage_per_turn: Option<f64>,
+    resistances: FxHashMap<Resistance, i32>,
+}
+
+impl Comparison {
+    /// If the item can be used, and is better on some aspects and worse on none.
+    pub fn is_upgrade(&self) -> bool {
+        self.restriction.is_none() && !self.pros.is_empty() && self.cons.is_empty()
+    }
+}
+
+impl Character<'_> {
+    fn skill(&self, name: &str) -> f64 {
+        self.skills.get(name).copied().unwrap_or(0.0)
+    }
+
+    /// Evasion (and spell failure, x19) penalty of an armour, from its
+    /// encumbrance, reduced by the skill and the strength.
+    fn armour_penalty(&self, encumbrance: f64, skill: &str, base: f64) -> f64 {
+        let penalty = 2.0 * encumbrance * encumbrance * (base - self.skill(skill) * 10.0)
+            / (5.0 * (self.str + 3.0))
+            / base;
+        penalty.max(0.0)
+    }
+}
+
+impl Evaluation {
+    /// Nothing equipped in the slot.
+    fn empty(slot: WearSlots, character: &Character) -> Self {
+        let mut evaluation = Self {
+            ac: Some(0.0),
+            ev: Some(0.0),
+            sh: Some(0.0),
+            encumbrance: Some(0.0),
+            spell_failure: Some(0.0),
+            ..Self::default()
+        };
+
+        // Unarmed attacks: 3 damage, plus 1 per level of Unarmed Combat
+        if matches!(slot, WearSlots::Weapon) {
+            let damage = 3.0 + character.skill("Unarmed Combat");
+            evaluation.damage_per_turn = Some(average_damage(damage, 0, character, 0.0));
+        }
+
+        evaluation
+    }
+
+    fn new(item: &Item, name: Option<&ItemName>, character: &Character) -> Self {
+        let mut evaluation = Self::default();
+        let enchantment = name.and_then(|name| name.enchantment).unwrap_or(0);
+
+        match item {
+            Item::Armour(armour) => {
+                let base = armour.base_rating.map(f64::from);
+                let encumbrance = armour.encumbrance.map(f64::from).unwrap_or(0.0);
+                let enchantment = enchantment as f64;
+
+                match armour.armour_type {
+                    ArmourType::Body => {
+                        let skill = character.skill("Armour");
+                        let penalty = character.armour_penalty(encumbrance, "Armour", 450.0);
+                        evaluation.ac =
+                            base.map(|ac| ac * (440.0 + 20.0 * skill) / 440.0 + enchantment);
+                        evaluation.ev = Some(-penalty);
+                        evaluation.encumbrance = Some(encumbrance);
+                        evaluation.spell_failure = Some(19.0 * penalty);
+                    }
+                    ArmourType::Shield => {
+                        let penalty = character.armour_penalty(encumbrance, "Shields", 270.0);
+                        evaluation.sh = base.map(|sh| sh + enchantment);
+                        evaluation.ev = Some(-penalty);
+                        evaluation.encumbrance = Some(encumbrance);
+                        evaluation.spell_failure = Some(19.0 * penalty);
+                    }
+                    _ => {
+                        evaluation.ac = base.map(|ac| ac + enchantment);
+                        evaluation.encumbrance = Some(0.0);
+                        evaluation.spell_failure = Some(0.0);
+                    }
+                }
+            }
+            Item::Weapon(weapon) => {
+                if let (Some(damage), Some(delay)) = (weapon.base_damage, weapon.base_attack_delay)
+                {
+                    let skill = weapon_skill(&weapon.weapon_type);
+                    let skill = skill.map(|skill| character.skill(skill)).unwrap_or(0.0);
+                    evaluation.damage_per_turn = Some(
+                        average_damage(damage as f64, enchantment, character, skill)
+                            / attack_delay(delay as f64, skill),
+                    );
+                }
+            }
+            Item::Jewellery(jewellery) => {
+                let kind = name.and_then(|name| name.kind.as_deref());
+                let enchantment = enchantment as f64;
+                match kind {
+                    Some("protection") => evaluation.ac = Some(enchantment),
+                    Some("evasion") => evaluation.ev = Some(enchantment),
+                    _ => (),
+                }
+                if matches!(jewellery.jewellery_type, JewelleryType::Ring) || kind.is_some() {
+                    evaluation.ac.get_or_insert(0.0);
+                    evaluation.ev.get_or_insert(0.0);
+                }
+                for (resistance, level) in kind.map(kind_resistances).unwrap_or_default() {
+                    *evaluation.resistances.entry(resistance).or_default() += level;
+                }
+            }
+            _ => (),
+        }
+
+        if let Some(name) = name {
+            evaluation.add_properties(name);
+        }
+
+        evaluation
+    }
+
+    /// Resistances and bonuses of the ego and of the artefact properties (e.g.
+    /// "rF+", "AC+3").
+    fn add_properties(&mut self, name: &ItemName) {
+        let resistances = name
+            .ego
+            .as_deref()
+            .map(kind_resistances)
+            .unwrap_or_default();
+        for (resistance, level) in resistances {
+            *self.resistances.entry(resistance).or_default() += level;
+        }
+
+        for property in &name.properties {
+            if let Some(value) = property.strip_prefix("AC") {
+                if let Ok(value) = value.parse::<f64>() {
+                    *self.ac.get_or_insert(0.0) += value;
+                }
+            } else if let Some(value) = property.strip_prefix("EV") {
+                if let Ok(value) = value.parse::<f64>() {
+                    *self.ev.get_or_insert(0.0) += value;
+                }
+            } else if let Some((resistance, level)) = property_resistance(property) {
+                *self.resistances.entry(resistance).or_default() += level;
+            }
+        }
+    }
+}
+
+/// Skill used by a type of weapon.
+fn weapon_skill(weapon_type: &WeaponType) -> Option<&'static str> {
+    let skill = match weapon_type {
+        WeaponType::None => return None,
+        WeaponType::Maces => "Maces & Flails",
+        WeaponType::Polearms => "Polearms",
+        WeaponType::Short => "Short Blades",
+        WeaponType::Long => "Long Blades",
+        WeaponType::Axes => "Axes",
+        WeaponType::Slings => "Slings",
+        WeaponType::Staves => "Staves",
+        WeaponType::Bows => "Bows",
+        WeaponType::Ranged => "Ranged Weapons",
+    };
+
+    Some(skill)
+}
+
+/// Average damage of an attack, with the strength, the weapon and Fighting
+/// skills, and the enchantment.
+fn average_damage(base: f64, enchantment: i32, character: &Character, skill: f64) -> f64 {
+    let mut damage = (base + 1.0) / 2.0;
+    damage *= 1.0 + (character.str - 10.0) * 0.025;
+    damage *= 1.0 + skill / 50.0;
+    damage *= 1.0 + character.skill("Fighting") / 60.0;
+
+    (damage + enchantment as f64 / 2.0).max(0.0)
+}
+
+/// Attack delay in turns, reduced by 0.1 every two skill levels down to the
+/// minimum delay (half the base delay, and at least 0.7).
+fn attack_delay(base_delay: f64, skill: f64) -> f64 {
+    let min_delay = (base_delay / 2.0).max(7.0).min(base_delay);
+    (base_delay - (skill / 2.0).floor()).max(min_delay) / 10.0
+}
+
+/// Resistances of an armour ego or a jewellery type (e.g. "fire resistance",
+/// "protection from cold").
+fn kind_resistances(kind: &str) -> Vec<(Resistance, i32)> {
+    match kind {
+        "fire resistance" | "protection from fire" => vec![(Resistance::Fire, 1)],
+        "cold resistance" | "protection from cold" => vec![(Resistance::Cold, 1)],
+        "resistance" => vec![(Resistance::Fire, 1), (Resistance::Cold, 1)],
+        "poison resistance" => vec![(Resistance::Poison, 1)],
+        "positive energy" => vec![(Resistance::Negative, 1)],
+        "willpower" => vec![(Resistance::Will, 1)],
+        "see invisible" => vec![(Resistance::SeeInvisible, 1)],
+        "resist corrosion" => vec![(Resistance::Corrosion, 1)],
+        "fire" => vec![(Resistance::Fire, 1), (Resistance::Cold, -1)],
+        "ice" => vec![(Resistance::Cold, 1), (Resistance::Fire, -1)],
+        _ => vec![],
+    }
+}
+
+/// Resistance of an artefact property (e.g. "rF++", "rC-", "rPois").
+fn property_resistance(property: &str) -> Option<(Resistance, i32)> {
+    let name = property.trim_end_matches(['+', '-']);
+    let resistance = match name {
+        "rF" => Resistance::Fire,
+        "rC" => Resistance::Cold,
+        "rPois" => Resistance::Poison,
+        "rN" => Resistance::Negative,
+        "rElec" => Resistance::Electricity,
+        "rCorr" => Resistance::Corrosion,
+        "Will" => Resistance::Will,
+        "SInv" => Resistance::SeeInvisible,
+        _ => return None,
+    };
+
+    let signs = &property[name.len()..];
+    let level = match signs.len() {
+        0 => 1,
+        _ => signs.matches('+').count() as i32 - signs.matches('-').count() as i32,
+    };
+
+    Some((resistance, level))
+}
+
+/// Why a species can't use an item in an equipment slot, if so.
+fn restriction(species: &str, slot: &WearSlots, base_type: &str) -> Option<String> {
+    let hat = matches!(base_type, "hat" | "cap" | "helm")
+        || base_type.ends_with(" hat")
+        || base_type.ends_with(" cap");
+
+    let restricted = match (species, slot) {
+        ("Felid", WearSlots::Weapon | WearSlots::Shield) => true,
+        ("Felid", WearSlots::Body | WearSlots::Gloves | WearSlots::Boots | WearSlots::Cloak) => {
+            true
+        }
+        ("Octopode", WearSlots::Body | WearSlots::Gloves | WearSlots::Boots) => true,
+        ("Felid" | "Octopode" | "Minotaur" | "Ogre" | "Troll", WearSlots::Helmet) => !hat,
+        ("Draconian", WearSlots::Body) => !base_type.ends_with("robe"),
+        ("Naga" | "Armataur" | "Centaur", WearSlots::Boots) => !base_type.ends_with("barding"),
+        ("Ogre" | "Troll", WearSlots::Body) => {
+            !matches!(base_type, "robe" | "animal skin" | "leather armour")
+        }
+        _ => false,
+    };
+
+    restricted.then(|| format!("{species}s can't use a {base_type}"))
+}
+
+/// Equipment slot of an item, if it can be worn or wielded.
+fn wear_slot(item: &Item, name: Option<&ItemName>) -> Option<WearSlots> {
+    let base_type = name.map(|name| name.base_type.as_str()).unwrap_or("");
+
+    let slot = match item {
+        Item::Weapon(_) | Item::Staff(_) => WearSlots::Weapon,
+        Item::Armour(armour) => match armour.armour_type {
+            ArmourType::Body => WearSlots::Body,
+            ArmourType::Boots => WearSlots::Boots,
+            ArmourType::Cloak => WearSlots::Cloak,
+            ArmourType::Helmet => WearSlots::Helmet,
+            ArmourType::Shield => WearSlots::Shield,
+            ArmourType::Gloves => WearSlots::Gloves,
+            // Not described yet: from the name
+            ArmourType::None => armour_slot(base_type),
+        },
+        Item::Jewellery(_) if base_type.ends_with("amulet") => WearSlots::Amulet,
+        Item::Jewellery(_) => WearSlots::RingLeft,
+        _ => return None,
+    };
+
+    Some(slot)
+}
+
+/// Equipment slot of an armour, from its base type (e.g. "pair of boots").
+fn armour_slot(base_type: &str) -> WearSlots {
+    let last_word = base_type.rsplit(' ').next().unwrap_or(base_type);
+
+    match last_word {
+        "cloak" | "scarf" => WearSlots::Cloak,
+        "helmet" | "hat" | "cap" | "helm" => WearSlots::Helmet,
+        "gloves" | "gauntlets" => WearSlots::Gloves,
+        "boots" | "barding" => WearSlots::Boots,
+        "buckler" | "shield" | "orb" => WearSlots::Shield,
+        _ => WearSlots::Body,
+    }
+}
+
+fn difference(aspect: Aspect, current: Option<f64>, candidate: Option<f64>) -> Option<Difference> {
+    let (current, candidate) = (current?, candidate?);
+    ((current - candidate).abs() > 1e-6).then_some(Difference {
+        aspect,
+        current,
+        candidate,
+    })
+}
+
+impl CrawlData {
+    /// Compare an item of the inventory with the item equipped in the same
+    /// slot (the first empty ring slot, or the left ring, for rings), using the
+    /// skills, strength and species of the character. Returns [None] if the
+    /// item can't be worn or wielded.
+    ///
+    /// Aspects unknown for one of the items (e.g. not described yet) are not
+    /// compared.
+    ///
+    /// # Arguments
+    ///
+    /// * `item_index` - Index of the inventory slot (0 for `a`).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let comparison = data.compare_item(index).unwrap();
+    /// if comparison.is_upgrade() {
+    ///     webtile.write_key("W")?;
+    /// }
+    /// for con in comparison.cons {
+    ///     println!("{:?}: {} -> {}", con.aspect, con.current, con.candidate);
+    /// }
+    /// ```
+    pub fn compare_item(&self, item_index: usize) -> Option<Comparison> {
+        let item = self.inventory.items.get(item_index)?;
+        let name = self.item_name(item_index);
+        let mut slot = wear_slot(item, name)?;
+
+        let equipped = |slot: &WearSlots| {
+            let index = self.player.equipped[slot.clone() as usize];
+            usize::try_from(index).ok()
+        };
+        if matches!(slot, WearSlots::RingLeft)
+            && equipped(&WearSlots::RingLeft).is_some()
+            && equipped(&WearSlots::RingRight).is_none()
+        {
+            slot = WearSlots::RingRight;
+        }
+        let replaces = equipped(&slot).filter(|index| *index != item_index);
+
+        let character = Character {
+            skills: self
+                .skills
+                .skills
+                .iter()
+                .map(|(name, skill)| (name.as_str(), skill.level))
+                .collect(),
+            str: self.player.stats.str as f64,
+        };
+
+        let candidate = Evaluation::new(item, name, &character);
+        let current = match replaces {
+            Some(index) => Evaluation::new(
+                &self.inventory.items[index],
+                self.item_name(index),
+                &character,
+            ),
+            None => Evaluation::empty(slot.clone(), &character),
+        };
+
+        let base_type = name.map(|name| name.base_type.as_str()).unwrap_or("");
+        let restriction = restriction(self.player_species(), &slot, base_type);
+
+        let mut differences = [
+            (Aspect::Ac, current.ac, candidate.ac),
+            (Aspect::Ev, current.ev, candidate.ev),
+            (Aspect::Sh, current.sh, candidate.sh),
+            (
+                Aspect::Encumbrance,
+                current.encumbrance,
+                candidate.encumbrance,
+            ),
+            (
+                Aspect::SpellFailure,
+                current.spell_failure,
+                candidate.spell_failure,
+            ),
+            (
+                Aspect::DamagePerTurn,
+                current.damage_per_turn,
+                candidate.damage_per_turn,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(aspect, current, candidate)| difference(aspect, current, candidate))
+        .collect::<Vec<Difference>>();
+
+        let mut resistances = current
+            .resistances
+            .keys()
+            .chain(candidate.resistances.keys())
+            .copied()
+            .collect::<Vec<Resistance>>();
+        resistances.sort();
+        resistances.dedup();
+        differences.extend(resistances.into_iter().filter_map(|resistance| {
+            let level = |evaluation: &Evaluation| {
+                Some(
+                    evaluation
+                        .resistances
+                        .get(&resistance)
+                        .copied()
+                        .unwrap_or(0) as f64,
+                )
+            };
+            difference(
+                Aspect::Resistance(resistance),
+                level(&current),
+                level(&candidate),
+            )
+        }));
+
+        let (pros, cons) = differences.into_iter().partition(|difference| {
+            let lower_is_better = matches!(
+                difference.aspect,
+                Aspect::Encumbrance | Aspect::SpellFailure
+            );
+            (difference.candidate > difference.current) != lower_is_better
+        });
+
+        Some(Comparison {
+            item_index,
+            replaces,
+            restriction,
+            pros,
+            cons,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::skills::Skill;
+    use serde_json::json;
+
+    #[test]
+    fn test_compare_item() {
+        let mut data = CrawlData::init(7, "0.32");
+        data.update_player(&json!({"msg": "player", "species": "Human", "str": 12,
+            "equip": {"6": 0},
+            "inv": {
+                "0": {"base_type": 2, "name": "a +0 robe (worn)"},
+                "1": {"base_type": 2, "name": "a +2 scale mail of fire resistance"},
+                "2": {"base_type": 0, "name": "a +1 war axe"},
+                "3": {"base_type": 6, "name": "a ring of protection from cold"},
+            }
+        }))
+        .unwrap();
+        data.skills
+            .skills
+            .insert("Axes".to_owned(), Skill::new("a".to_owned(), 1, 4.0, 0));
+
+        let descriptions = [
+            json!({"title": "a - a +0 robe (worn)",
+                "body": "{body armour} Base armour rating: 2 Encumbrance rating: 0 "}),
+            json!({"title": "b - a +2 scale mail of fire resistance",
+                "body": "{body armour} Base armour rating: 6 Encumbrance rating: 10 "}),
+            json!({"title": "c - a +1 war axe",
+                "body": "{Axes} Base accuracy: +0 Base damage: 11 Base attack delay: 1.5 "}),
+        ];
+        for description in descriptions {
+            data.inventory_description(description).unwrap();
+        }
+
+        let mail = data.compare_item(1).unwrap();
+        assert_eq!(mail.replaces, Some(0));
+        assert!(!mail.is_upgrade());
+        let aspects = |differences: &[Difference]| {
+            differences
+                .iter()
+                .map(|difference| difference.aspect)
+                .collect::<Vec<Aspect>>()
+        };
+        assert_eq!(
+            aspects(&mail.pros),
+            [Aspect::Ac, Aspect::Resistance(Resistance::Fire)]
+        );
+        assert_eq!(
+            aspects(&mail.cons),
+            [Aspect::Ev, Aspect::Encumbrance, Aspect::SpellFailure]
+        );
+        assert_eq!(mail.pros[0].candidate, 8.0);
+
+        // Better than unarmed attacks
+        let axe = data.compare_item(2).unwrap();
+        assert_eq!(axe.replaces, None);
+        assert!(axe.is_upgrade());
+        assert_eq!(aspects(&axe.pros), [Aspect::DamagePerTurn]);
+
+        let ring = data.compare_item(3).unwrap();
+        assert_eq!(aspects(&ring.pros), [Aspect::Resistance(Resistance::Cold)]);
+
+        // Octopodes can't wear body armour
+        data.update_player(&json!({"msg": "player", "species": "Octopode"}))
+            .unwrap();
+        assert!(data.compare_item(1).unwrap().restriction.is_some());
+        assert!(data.compare_item(3).unwrap().is_upgrade());
+    }
+}
@@ -0,0 +1,534 @@
+use dcss_api::FormattedText;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Attack verbs of melee messages (first person, see `attack.cc` and the
+/// unarmed attacks), the third person is matched with an "s" or "es" suffix.
+const MELEE_VERBS: &str =
+    "hit|slash|pierce|skewer|impale|spit|stab|slice|carve|shred|eviscerate|chop\
+    |hack|bludgeon|smash|crush|pummel|batter|beat|thump|whack|bash|punch|kick\
+    |headbutt|bite|claw|peck|sting|gore|butt|trample|squeeze|constrict|touch|engulf\
+    |lash|slap|puncture|perforate|tentacle-slap|tail-slap|pound";
+
+/// Missiles and spells, whose messages look like melee attacks (e.g. "The dart
+/// hits you.", "The bolt of fire hits the goblin.").
+const MISSILES: [&str; 23] = [
+    "arrow",
+    "bolt",
+    "bullet",
+    "dart",
+    "javelin",
+    "needle",
+    "stone",
+    "rock",
+    "boomerang",
+    "net",
+    "shot",
+    "spear",
+    "blast",
+    "beam",
+    "ray",
+    "spray",
+    "splash",
+    "ball",
+    "orb",
+    "shard",
+    "spike",
+    "spit",
+    "missile",
+];
+
+/// Verbs of the messages of monsters shouting.
+const SHOUT_VERBS: &str = "shout|yell|roar|bellow|howl|scream|shriek|screech|hiss|growl|bark\
+    |snarl|bray|trumpet|croak|squeal|wail|moan|chant|cry|call";
+
+static MELEE_HIT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"^(You|.+?) ((?:{MELEE_VERBS})(?:e?s)?) (you|yourself|.+?)(?:(?: with | like | in | as | but |, ).*)?[.!]+$"
+    ))
+    .unwrap()
+});
+static MELEE_MISS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(You|.+?) (?:closely |barely |completely )?miss(?:es)? (you|.+?)[.!]+$").unwrap()
+});
+static PLAYER_KILL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^You (?:kill|destroy|slay|blow up|annihilate|dissolve) (.+?)[.!]+$").unwrap()
+});
+static KILL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(.+?) (?:is|are) (?:killed|destroyed|slain|blown up|annihilated)[.!]+$|^(.+?) dies[.!]+$")
+        .unwrap()
+});
+static PICKUP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([a-zA-Z]) - (.+?)( \(gained \d+\))?\.?$").unwrap());
+/// Slot of an item equipped (e.g. "a - +0 hand axe (weapon)" when wielding it).
+static EQUIPPED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\((?:weapon|offhand|worn|in hand|(?:left|right) hand|on [^)]+|around [^)]+)\)$")
+        .unwrap()
+});
+static IDENTIFIED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:It (?:was|is)|You (?:have )?identif(?:y|ied)) (.+?)[.!]$").unwrap()
+});
+static LEVEL_UP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:You have reached level|Welcome back to level) (\d+)!").unwrap()
+});
+static STAT_CHANGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^You feel (stronger|weaker|clever|cleverer|smarter|stupid|stupider|more agile|agile|clumsy|clumsier)[.!]$")
+        .unwrap()
+});
+static SHOUT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"^(.+?) (?:{SHOUT_VERBS})(?:e?s)?\b")).unwrap());
+
+/// Channel of a message of the log (`msg_channel_type` in the game).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageChannel {
+    Plain,
+    FriendAction,
+    Prompt,
+    God,
+    Duration,
+    Danger,
+    Warning,
+    Recovery,
+    Sound,
+    Talk,
+    TalkVisual,
+    IntrinsicGain,
+    Mutation,
+    MonsterSpell,
+    MonsterEnchant,
+    FriendSpell,
+    FriendEnchant,
+    MonsterDamage,
+    MonsterTarget,
+    Banishment,
+    Equipment,
+    Floor,
+    MultiturnAction,
+    Examine,
+    ExamineFilter,
+    Diagnostic,
+    Error,
+    Tutorial,
+    Orb,
+    TimedPortal,
+    HellEffect,
+    MonsterWarning,
+    Other(u64),
+}
+
+/// Character or monster taking part in an event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Actor {
+    Player,
+    /// Name of the monster, without article (e.g. "goblin", "Sigmund").
+    Monster(String),
+}
+
+/// Stat changed by a [EventKind::StatChange].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stat {
+    Strength,
+    Intelligence,
+    Dexterity,
+}
+
+/// What happened, parsed from the text and channel of a message of the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    /// Melee attack that hit (missiles and spells hitting are [EventKind::Other]).
+    MeleeHit {
+        attacker: Actor,
+        defender: Actor,
+        /// Verb of the attack (e.g. "hit", "bites"), hinting at the damage done.
+        verb: String,
+    },
+    MeleeMiss {
+        attacker: Actor,
+        defender: Actor,
+    },
+    /// A monster died, killed by the character if `killer` is set.
+    Kill {
+        killer: Option<Actor>,
+        victim: String,
+    },
+    /// Item picked up, with its inventory letter (not the inventory line shown
+    /// when equipping an item).
+    Pickup {
+        slot: char,
+        item: String,
+    },
+    /// Item identified (e.g. "a scroll of teleportation" after reading it).
+    Identified {
+        item: String,
+    },
+    /// Message from a god (the text is in [`GameEvent::text`]).
+    God,
+    /// Experience level reached.
+    LevelUp {
+        xl: u64,
+    },
+    StatChange {
+        stat: Stat,
+        increased: bool,
+    },
+    /// Monster shouting, or shout heard (without `speaker`).
+    Shout {
+        speaker: Option<String>,
+    },
+    /// Mutation gained or lost (a message of the mutation channel).
+    Mutation,
+    /// Any other message.
+    Other,
+}
+
+/// Event of the game, from a message of the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameEvent {
+    /// Player turn of the message.
+    pub turn: u64,
+    pub channel: MessageChannel,
+    pub kind: EventKind,
+    /// Text of the message, without colour tags.
+    pub text: String,
+    /// Text of the message, with its colours.
+    pub formatted: FormattedText,
+}
+
+impl MessageChannel {
+    /// Channel from the "channel" field of a message.
+    pub fn from_id(id: u64) -> Self {
+        match id {
+            0 => Self::Plain,
+            1 => Self::FriendAction,
+            2 => Self::Prompt,
+            3 => Self::God,
+            4 => Self::Duration,
+            5 => Self::Danger,
+            6 => Self::Warning,
+            7 => Self::Recovery,
+            8 => Self::Sound,
+            9 => Self::Talk,
+            10 => Self::TalkVisual,
+            11 => Self::IntrinsicGain,
+            12 => Self::Mutation,
+            13 => Self::MonsterSpell,
+            14 => Self::MonsterEnchant,
+            15 => Self::FriendSpell,
+            16 => Self::FriendEnchant,
+            17 => Self::MonsterDamage,
+            18 => Self::MonsterTarget,
+            19 => Self::Banishment,
+            20 => Self::Equipment,
+            21 => Self::Floor,
+            22 => Self::MultiturnAction,
+            23 => Self::Examine,
+            24 => Self::ExamineFilter,
+            25 => Self::Diagnostic,
+            26 => Self::Error,
+            27 => Self::Tutorial,
+            28 => Self::Orb,
+            29 => Self::TimedPortal,
+            30 => Self::HellEffect,
+            31 => Self::MonsterWarning,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl Actor {
+    fn from_text(text: &str) -> Self {
+        match text {
+            "You" | "you" | "yourself" => Self::Player,
+            monster => Self::Monster(strip_article(monster)),
+        }
+    }
+}
+
+impl GameEvent {
+    /// Parse a message of the log.
+    ///
+    /// # Arguments
+    ///
+    /// * `formatted` - The [FormattedText] of the message.
+    /// * `channel` - The [MessageChannel] of the message.
+    /// * `turn` - Player turn of the message.
+    pub(crate) fn parse(formatted: FormattedText, channel: MessageChannel, turn: u64) -> Self {
+        let text = formatted.plain();
+
+        Self {
+            turn,
+            channel,
+            kind: EventKind::parse(text.trim(), channel),
+            text,
+            formatted,
+        }
+    }
+}
+
+impl EventKind {
+    fn parse(text: &str, channel: MessageChannel) -> Self {
+        if channel == MessageChannel::God {
+            return Self::God;
+        }
+
+        if let Some(caps) = LEVEL_UP.captures(text) {
+            if let Ok(xl) = caps[1].parse::<u64>() {
+                return Self::LevelUp { xl };
+            }
+        }
+
+        if let Some(caps) = STAT_CHANGE.captures(text) {
+            let (stat, increased) = match &caps[1] {
+                "stronger" => (Stat::Strength, true),
+                "weaker" => (Stat::Strength, false),
+                "clever" | "cleverer" | "smarter" => (Stat::Intelligence, true),
+                "stupid" | "stupider" => (Stat::Intelligence, false),
+                "more agile" | "agile" => (Stat::Dexterity, true),
+                _ => (Stat::Dexterity, false),
+            };
+            return Self::StatChange { stat, increased };
+        }
+
+        if channel == MessageChannel::Mutation {
+            return Self::Mutation;
+        }
+
+        if let Some(caps) = PLAYER_KILL.captures(text) {
+            return Self::Kill {
+                killer: Some(Actor::Player),
+                victim: strip_article(&caps[1]),
+            };
+        }
+
+        if let Some(caps) = KILL.captures(text) {
+            let victim = caps
+                .get(1)
+                .or_else(|| caps.get(2))
+                .map_or("", |m| m.as_str());
+            return Self::Kill {
+                killer: None,
+                victim: strip_article(victim),
+            };
+        }
+
+        if let Some(caps) = MELEE_MISS.captures(text) {
+            return Self::MeleeMiss {
+                attacker: Actor::from_text(&caps[1]),
+                defender: Actor::from_text(&caps[2]),
+            };
+        }
+
+        if let Some(caps) = MELEE_HIT
+            .captures(text)
+            .filter(|caps| !is_missile(&caps[1]))
+        {
+            return Self::MeleeHit {
+                attacker: Actor::from_text(&caps[1]),
+                defender: Actor::from_text(&caps[3]),
+                verb: caps[2].to_owned(),
+            };
+        }
+
+        if matches!(channel, MessageChannel::Sound | MessageChannel::Talk) {
+            if text.starts_with("You hear ") {
+                return Self::Shout { speaker: None };
+            }
+            if let Some(caps) = SHOUT.captures(text) {
+                return Self::Shout {
+                    speaker: Some(strip_article(&caps[1])),
+                };
+            }
+        }
+
+        if let Some(caps) = IDENTIFIED.captures(text) {
+            return Self::Identified {
+                item: caps[1].to_owned(),
+            };
+        }
+
+        if channel == MessageChannel::Plain {
+            // Inventory lines of an item equipped (e.g. when wielding it) are not
+            // a pickup, unless they add to a stack
+            if let Some(caps) = PICKUP
+                .captures(text)
+                .filter(|caps| caps.get(3).is_some() || !EQUIPPED.is_match(&caps[2]))
+            {
+                return Self::Pickup {
+                    slot: caps[1].chars().next().unwrap_or_default(),
+                    item: caps[2].to_owned(),
+                };
+            }
+        }
+
+        Self::Other
+    }
+}
+
+/// If the subject of a message is a missile or a spell (e.g. "The bolt of
+/// fire"), by its last word before any "of".
+fn is_missile(subject: &str) -> bool {
+    let name = strip_article(subject);
+    let head = name.split(" of ").next().unwrap_or_default();
+
+    head.rsplit(' ')
+        .next()
+        .is_some_and(|word| MISSILES.contains(&word))
+}
+
+/// Name of a monster without its article (e.g. "The goblin" becomes "goblin").
+fn strip_article(name: &str) -> String {
+    ["The ", "the ", "A ", "a ", "An ", "an ", "Your ", "your "]
+        .iter()
+        .find_map(|article| name.strip_prefix(article))
+        .unwrap_or(name)
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::CrawlData;
+    use serde_json::json;
+
+    fn kind(text: &str, channel: MessageChannel) -> EventKind {
+        GameEvent::parse(FormattedText::parse(text), channel, 0).kind
+    }
+
+    #[test]
+    fn test_events() {
+        let plain = MessageChannel::Plain;
+
+        assert_eq!(
+            kind("You slash the orc wizard!", plain),
+            EventKind::MeleeHit {
+                attacker: Actor::Player,
+                defender: Actor::Monster("orc wizard".to_owned()),
+                verb: "slash".to_owned()
+            }
+        );
+        assert_eq!(
+            kind("The goblin hits you with a dagger.", plain),
+            EventKind::MeleeHit {
+                attacker: Actor::Monster("goblin".to_owned()),
+                defender: Actor::Player,
+                verb: "hits".to_owned()
+            }
+        );
+        assert_eq!(
+            kind("The adder closely misses you.", plain),
+            EventKind::MeleeMiss {
+                attacker: Actor::Monster("adder".to_owned()),
+                defender: Actor::Player
+            }
+        );
+        assert_eq!(
+            kind("You kill the goblin!", plain),
+            EventKind::Kill {
+                killer: Some(Actor::Player),
+                victim: "goblin".to_owned()
+            }
+        );
+        assert_eq!(
+            kind("The rat is killed!", plain),
+            EventKind::Kill {
+                killer: None,
+                victim: "rat".to_owned()
+            }
+        );
+        assert_eq!(
+            kind("b - 3 potions of curing (gained 1)", plain),
+            EventKind::Pickup {
+                slot: 'b',
+                item: "3 potions of curing".to_owned()
+            }
+        );
+        assert_eq!(
+            kind("It was a scroll of fog.", plain),
+            EventKind::Identified {
+                item: "a scroll of fog".to_owned()
+            }
+        );
+        assert_eq!(
+            kind("Trog roars: Kill them all!", MessageChannel::God),
+            EventKind::God
+        );
+        assert_eq!(
+            kind("You have reached level 5!", MessageChannel::IntrinsicGain),
+            EventKind::LevelUp { xl: 5 }
+        );
+        assert_eq!(
+            kind("You feel clumsy.", plain),
+            EventKind::StatChange {
+                stat: Stat::Dexterity,
+                increased: false
+            }
+        );
+        assert_eq!(
+            kind("The gnoll shouts!", MessageChannel::Sound),
+            EventKind::Shout {
+                speaker: Some("gnoll".to_owned())
+            }
+        );
+        assert_eq!(
+            kind("You feel a little pale.", MessageChannel::Mutation),
+            EventKind::Mutation
+        );
+        assert_eq!(
+            kind("You hit the goblin but do no damage.", plain),
+            EventKind::MeleeHit {
+                attacker: Actor::Player,
+                defender: Actor::Monster("goblin".to_owned()),
+                verb: "hit".to_owned()
+            }
+        );
+        assert_eq!(
+            kind("You feel a bit more experienced.", plain),
+            EventKind::Other
+        );
+        assert_eq!(kind("You open the door.", plain), EventKind::Other);
+    }
+
+    #[test]
+    fn test_events_from_msgs() {
+        let mut data = CrawlData::init(7, "0.32");
+        data.process_json(&json!({"msg": "msgs", "messages": [
+            {"text": "You feel clever.", "channel": 0},
+            {"text": "a - +0 hand axe (weapon)", "channel": 0},
+            {"text": "c - 15 darts (quivered) (gained 5)", "channel": 0},
+            {"text": "d - a +0 ring of protection (left hand)", "channel": 0},
+            {"text": "The dart hits you.", "channel": 0},
+            {"text": "The bolt of fire hits the goblin.", "channel": 0},
+            {"text": "The ball python bites you.", "channel": 0},
+        ]}))
+        .unwrap();
+
+        let mut kinds = vec![];
+        while let Some(event) = data.get_game_event() {
+            kinds.push(event.kind);
+        }
+
+        assert_eq!(
+            kinds,
+            vec![
+                EventKind::StatChange {
+                    stat: Stat::Intelligence,
+                    increased: true
+                },
+                EventKind::Other,
+                EventKind::Pickup {
+                    slot: 'c',
+                    item: "15 darts (quivered)".to_owned()
+                },
+                EventKind::Other,
+                EventKind::Other,
+                EventKind::Other,
+                EventKind::MeleeHit {
+                    attacker: Actor::Monster("ball python".to_owned()),
+                    defender: Actor::Player,
+                    verb: "bites".to_owned()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use crate::CrawlData;
+use std::time::{Duration, Instant};
+
+/// Game time taken by an action (see [`CrawlData::start_action`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionTime {
+    /// Key (or keys) sent for the action.
+    pub key: String,
+    /// Game time taken by the action, in aut (10 aut = 1 turn at normal speed).
+    pub time: u64,
+    /// Number of player turns taken by the action.
+    pub turns: u64,
+    /// Real time between the start and the end of the action.
+    pub real_time: Duration,
+}
+
+/// Action started with [`CrawlData::start_action`] and not yet ended.
+#[derive(Debug)]
+struct PendingAction {
+    key: String,
+    time: u64,
+    turn: u64,
+    start: Instant,
+}
+
+#[derive(Debug)]
+pub(crate) struct GameTime {
+    /// Player turns (the "turn" field of the "player" message).
+    pub(crate) turn: u64,
+    /// Game time in aut (the "time" field of the "player" message).
+    pub(crate) time: u64,
+    /// Real time of the first "player" message received.
+    start: Option<Instant>,
+    pending: Option<PendingAction>,
+    last_action: Option<ActionTime>,
+    /// Number of actions ended, and their total game time.
+    action_count: u64,
+    action_time: u64,
+    /// Number of consecutive actions that took no game time.
+    zero_time_streak: u32,
+}
+
+impl GameTime {
+    pub(crate) fn init() -> Self {
+        Self {
+            turn: 0,
+            time: 0,
+            start: None,
+            pending: None,
+            last_action: None,
+            action_count: 0,
+            action_time: 0,
+            zero_time_streak: 0,
+        }
+    }
+
+    pub(crate) fn update(&mut self, turn: Option<u64>, time: Option<u64>) {
+        if self.start.is_none() {
+            self.start = Some(Instant::now());
+        }
+
+        if let Some(turn) = turn {
+            self.turn = turn;
+        }
+
+        if let Some(time) = time {
+            self.time = time;
+        }
+    }
+
+    fn end_pending(&mut self) -> Option<ActionTime> {
+        let pending = self.pending.take()?;
+
+        let action = ActionTime {
+            key: pending.key,
+            time: self.time.saturating_sub(pending.time),
+            turns: self.turn.saturating_sub(pending.turn),
+            real_time: pending.start.elapsed(),
+        };
+
+        self.action_count += 1;
+        self.action_time += action.time;
+        if action.time == 0 {
+            self.zero_time_streak += 1;
+        } else {
+            self.zero_time_streak = 0;
+        }
+
+        self.last_action = Some(action.clone());
+
+        Some(action)
+    }
+}
+
+impl CrawlData {
+    /// Player turns since the start of the game.
+    pub fn game_turn(&self) -> u64 {
+        self.time.turn
+    }
+
+    /// Game time since the start of the game, in aut (10 aut = 1 turn at normal speed).
+    pub fn game_time(&self) -> u64 {
+        self.time.time
+    }
+
+    /// Real time since the first "player" message was processed.
+    pub fn real_time(&self) -> Duration {
+        self.time
+            .start
+            .map(|start| start.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// Mark the start of an action, right after sending its key(s) with
+    /// `dcss_api::Webtile::write_key` or `dcss_api::Webtile::act`. An action that
+    /// was started but not ended with [`CrawlData::end_action`] is ended first.
+    ///
+    /// Actions are not timed automatically: [CrawlData] only sees the messages
+    /// received, not the keys sent, so the caller marks both ends of each action.
+    /// The start has to be marked before the messages of the action are
+    /// processed, and the end after all of them (up to the `input_mode` 1 message
+    /// that ends the action) were processed, or the time taken is not counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A string slice of the key(s) sent.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Rest, the start being marked before the messages of the action are processed
+    /// webtile.write_key("5")?;
+    /// data.start_action("5");
+    /// webtile.read_until("input_mode", Some("mode"), Some(1))?;
+    ///
+    /// // Process every message received for the action, then mark its end
+    /// while let Some(message) = webtile.get_message() {
+    ///     data.process_json(&message)?;
+    /// }
+    /// let action = data.end_action().unwrap();
+    /// println!("Resting took {} aut", action.time);
+    /// ```
+    pub fn start_action(&mut self, key: &str) {
+        self.time.end_pending();
+
+        self.time.pending = Some(PendingAction {
+            key: key.to_owned(),
+            time: self.time.time,
+            turn: self.time.turn,
+            start: Instant::now(),
+        });
+    }
+
+    /// Mark the end of the action started with [`CrawlData::start_action`],
+    /// once its messages have been processed. Returns the time it took, or
+    /// [None] if no action was started.
+    pub fn end_action(&mut self) -> Option<ActionTime> {
+        self.time.end_pending()
+    }
+
+    /// Time taken by the last ended action.
+    pub fn last_action_time(&self) -> Option<&ActionTime> {
+        self.time.last_action.as_ref()
+    }
+
+    /// Number of actions ended (see [`CrawlData::end_action`]).
+    pub fn action_count(&self) -> u64 {
+        self.time.action_count
+    }
+
+    /// Average game time per action, in aut (0 if no action was ended).
+    pub fn average_action_time(&self) -> f64 {
+        if self.time.action_count == 0 {
+            return 0.0;
+        }
+
+        self.time.action_time as f64 / self.time.action_count as f64
+    }
+
+    /// Number of consecutive actions (up to the last ended one) that took no
+    /// game time.
+    pub fn zero_time_streak(&self) -> u32 {
+        self.time.zero_time_streak
+    }
+
+    /// If at least `threshold` consecutive actions took no game time, which
+    /// likely means the bot is stuck in a loop (e.g. moving into a wall).
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Number of consecutive zero-time actions.
+    pub fn in_zero_time_loop(&self, threshold: u32) -> bool {
+        self.time.zero_time_streak >= threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_action_time() {
+        let mut data = CrawlData::init(7, "0.32");
+        data.update_player(&json!({"msg": "player", "turn": 10, "time": 100}))
+            .unwrap();
+
+        data.start_action("5");
+        data.update_player(&json!({"msg": "player", "turn": 15, "time": 150}))
+            .unwrap();
+        let action = data.end_action().unwrap();
+        assert_eq!((action.time, action.turns), (50, 5));
+
+        // Moving into a wall takes no time
+        for _ in 0..3 {
+            data.start_action("key_dir_n");
+        }
+        data.end_action();
+        assert_eq!(data.action_count(), 4);
+        assert!(data.in_zero_time_loop(3));
+        assert_eq!(data.average_action_time(), 12.5);
+        assert_eq!(data.game_turn(), 15);
+        assert_eq!(data.game_time(), 150);
+    }
+}
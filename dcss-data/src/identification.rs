@@ -0,0 +1,517 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::events::{EventKind, GameEvent};
+use crate::inventory::Inventory;
+use crate::items::name::{ItemClass, ItemName};
+use crate::CrawlData;
+
+/// Approximate relative generation weights of the potions, with the version
+/// (minor number, e.g. 29 for 0.29) that stopped generating the type, if any.
+const POTION_WEIGHTS: [(&str, u32, Option<u32>); 17] = [
+    ("curing", 210, None),
+    ("heal wounds", 155, None),
+    ("haste", 70, None),
+    ("might", 70, None),
+    ("magic", 70, None),
+    ("berserk rage", 50, None),
+    ("resistance", 50, None),
+    ("invisibility", 40, None),
+    ("cancellation", 40, None),
+    ("brilliance", 35, None),
+    ("enlightenment", 35, None),
+    ("attraction", 30, None),
+    ("mutation", 30, None),
+    ("experience", 12, None),
+    ("degeneration", 25, Some(29)),
+    ("lignification", 20, Some(31)),
+    ("ambrosia", 15, Some(31)),
+];
+
+/// Approximate relative generation weights of the scrolls, with the version
+/// (minor number, e.g. 29 for 0.29) that stopped generating the type, if any.
+const SCROLL_WEIGHTS: [(&str, u32, Option<u32>); 20] = [
+    ("identify", 270, None),
+    ("teleportation", 170, None),
+    ("enchant armour", 90, None),
+    ("enchant weapon", 90, None),
+    ("fear", 50, None),
+    ("fog", 50, None),
+    ("blinking", 45, None),
+    ("revelation", 45, None),
+    ("noise", 45, None),
+    ("brand weapon", 30, None),
+    ("silence", 30, None),
+    ("summoning", 30, None),
+    ("vulnerability", 30, None),
+    ("immolation", 30, None),
+    ("poison", 30, None),
+    ("butterflies", 30, None),
+    ("amnesia", 25, None),
+    ("acquirement", 15, None),
+    ("holy word", 15, Some(29)),
+    ("torment", 15, Some(32)),
+];
+
+/// Start of the messages revealing the type of the item just used.
+const EFFECTS: [(ItemClass, &str, &str); 18] = [
+    (ItemClass::Potion, "You feel much better", "heal wounds"),
+    (ItemClass::Potion, "You feel better", "curing"),
+    (ItemClass::Potion, "You feel yourself speed up", "haste"),
+    (ItemClass::Potion, "You feel very mighty", "might"),
+    (ItemClass::Potion, "You feel clever", "brilliance"),
+    (
+        ItemClass::Potion,
+        "Magic courses through your body",
+        "magic",
+    ),
+    (ItemClass::Potion, "You feel protected", "resistance"),
+    (ItemClass::Potion, "A flood of memories", "experience"),
+    (
+        ItemClass::Potion,
+        "You fade into invisibility",
+        "invisibility",
+    ),
+    (
+        ItemClass::Potion,
+        "You feel magically purged",
+        "cancellation",
+    ),
+    (ItemClass::Potion, "You go berserk", "berserk rage"),
+    (ItemClass::Potion, "You feel extremely strange", "mutation"),
+    (
+        ItemClass::Scroll,
+        "You feel strangely unstable",
+        "teleportation",
+    ),
+    (ItemClass::Scroll, "The scroll dissolves into smoke", "fog"),
+    (ItemClass::Scroll, "You hear a loud clanging noise", "noise"),
+    (ItemClass::Scroll, "You assume a fearsome visage", "fear"),
+    (
+        ItemClass::Scroll,
+        "You feel aware of your surroundings",
+        "revelation",
+    ),
+    (
+        ItemClass::Scroll,
+        "The scroll explodes in your hands",
+        "immolation",
+    ),
+];
+
+/// Possible type of an unidentified potion or scroll.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// Type of the item (e.g. "heal wounds").
+    pub item_type: String,
+    /// Probability (0 to 1) that the item is of this type.
+    pub probability: f64,
+}
+
+/// What is known about the appearances (e.g. "bubbling", "XOZAFIB") of the
+/// unidentified potions and scrolls.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Identification {
+    /// Appearances seen during the game, by class.
+    seen: FxHashSet<(ItemClass, String)>,
+    /// Type of the appearances identified, or deduced.
+    identities: FxHashMap<(ItemClass, String), String>,
+    /// Types an appearance can't be (e.g. used without the expected effect).
+    ruled_out: FxHashMap<(ItemClass, String), FxHashSet<String>>,
+    /// Appearance of the item being used, until the next messages.
+    pending: Option<(ItemClass, String)>,
+    /// Minor number of the version of DCSS played (e.g. 32 for 0.32), [None]
+    /// if unknown (e.g. trunk), in which case every type can be generated.
+    version: Option<u32>,
+}
+
+impl Identification {
+    /// # Arguments
+    ///
+    /// * `version` - A string slice of the version of DCSS (e.g. "0.32", "0.32.1").
+    pub(crate) fn new(version: &str) -> Self {
+        Self {
+            version: version
+                .strip_prefix("0.")
+                .and_then(|minor| minor.split('.').next())
+                .and_then(|minor| minor.parse::<u32>().ok()),
+            ..Self::default()
+        }
+    }
+
+    /// If the type can be generated by the version played.
+    fn generated(&self, removed_in: Option<u32>) -> bool {
+        match (self.version, removed_in) {
+            (Some(version), Some(removed_in)) => version < removed_in,
+            _ => true,
+        }
+    }
+}
+
+/// Class and appearance of an unidentified potion or scroll (e.g. "bubbling"
+/// for "a bubbling potion", "XOZAFIB" for "a scroll labelled XOZAFIB").
+///
+/// # Arguments
+///
+/// * `name` - The parsed [ItemName].
+pub(crate) fn appearance(name: &ItemName) -> Option<(ItemClass, String)> {
+    if name.identified {
+        return None;
+    }
+
+    let appearance = match name.class? {
+        ItemClass::Potion => name.base_type.strip_suffix(" potion")?,
+        ItemClass::Scroll => name.base_type.split_once(" labelled ")?.1,
+        _ => return None,
+    };
+
+    Some((name.class?, appearance.to_owned()))
+}
+
+fn weights(class: ItemClass) -> &'static [(&'static str, u32, Option<u32>)] {
+    match class {
+        ItemClass::Potion => &POTION_WEIGHTS,
+        ItemClass::Scroll => &SCROLL_WEIGHTS,
+        _ => &[],
+    }
+}
+
+impl Inventory {
+    fn known_types(&self, class: ItemClass) -> Option<&FxHashMap<String, bool>> {
+        match class {
+            ItemClass::Potion => Some(&self.known_potion),
+            ItemClass::Scroll => Some(&self.known_scroll),
+            _ => None,
+        }
+    }
+
+    /// Types the appearance can still be, with their probability, most likely
+    /// first. Types already known are excluded, since their items are shown
+    /// with their name, as are the types the version played does not generate.
+    /// Uncommon types are kept, however unlikely.
+    pub(crate) fn candidates(&self, class: ItemClass, appearance: &str) -> Vec<Candidate> {
+        let key = (class, appearance.to_owned());
+
+        if let Some(item_type) = self.identification.identities.get(&key) {
+            return vec![Candidate {
+                item_type: item_type.clone(),
+                probability: 1.0,
+            }];
+        }
+
+        let Some(known) = self.known_types(class) else {
+            return vec![];
+        };
+        let ruled_out = self.identification.ruled_out.get(&key);
+
+        let remaining = weights(class)
+            .iter()
+            .filter(|(item_type, _, removed_in)| {
+                self.identification.generated(*removed_in)
+                    && !known.get(*item_type).copied().unwrap_or(false)
+                    && ruled_out.is_none_or(|types| !types.contains(*item_type))
+            })
+            .collect::<Vec<_>>();
+        let total = remaining.iter().map(|(_, weight, _)| weight).sum::<u32>() as f64;
+
+        let mut candidates = remaining
+            .iter()
+            .map(|(item_type, weight, _)| Candidate {
+                item_type: item_type.to_string(),
+                probability: *weight as f64 / total,
+            })
+            .collect::<Vec<Candidate>>();
+        candidates.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+
+        candidates
+    }
+
+    /// Remember the type of an appearance, and deduce the others by elimination.
+    pub(crate) fn identify_appearance(
+        &mut self,
+        class: ItemClass,
+        appearance: &str,
+        item_type: &str,
+    ) {
+        self.assign(class, appearance, item_type);
+        self.deduce();
+    }
+
+    fn assign(&mut self, class: ItemClass, appearance: &str, item_type: &str) {
+        let key = (class, appearance.to_owned());
+        self.identification.seen.insert(key.clone());
+        self.identification
+            .identities
+            .insert(key, item_type.to_owned());
+
+        match class {
+            ItemClass::Potion => self.known_potion.insert(item_type.to_owned(), true),
+            _ => self.known_scroll.insert(item_type.to_owned(), true),
+        };
+    }
+
+    /// Identify the appearances with a single type left, until nothing changes.
+    pub(crate) fn deduce(&mut self) {
+        loop {
+            let deduced = self
+                .identification
+                .seen
+                .iter()
+                .filter(|key| !self.identification.identities.contains_key(*key))
+                .find_map(|(class, appearance)| {
+                    match self.candidates(*class, appearance).as_slice() {
+                        [single] => Some((*class, appearance.clone(), single.item_type.clone())),
+                        _ => None,
+                    }
+                });
+
+            let Some((class, appearance, item_type)) = deduced else {
+                break;
+            };
+            self.assign(class, &appearance, &item_type);
+        }
+    }
+
+    /// Remember the appearance of an item in the inventory. If the item in the
+    /// slot had an appearance and now has a type, the appearance is identified.
+    pub(crate) fn observe_name(&mut self, previous: Option<&ItemName>, name: &ItemName) {
+        if let Some(key) = appearance(name) {
+            if self.identification.seen.insert(key) {
+                self.deduce();
+            }
+            return;
+        }
+
+        let Some((class, old)) = previous.and_then(appearance) else {
+            return;
+        };
+        if name.class == Some(class) {
+            if let Some(kind) = &name.kind {
+                self.identify_appearance(class, &old, kind);
+            }
+        }
+    }
+
+    /// Learn from the events of the log: "It was a potion of curing." after
+    /// using an item, or the effect of the item (e.g. "You feel much better").
+    pub(crate) fn observe_event(&mut self, event: &GameEvent) {
+        let Some((class, appearance)) = self.identification.pending.clone() else {
+            return;
+        };
+
+        let item_type = match &event.kind {
+            EventKind::Identified { item } => {
+                let name = ItemName::parse(item);
+                name.kind.filter(|_| name.class == Some(class))
+            }
+            _ => EFFECTS
+                .iter()
+                .find(|(effect_class, text, _)| {
+                    *effect_class == class && event.text.starts_with(text)
+                })
+                .map(|(_, _, item_type)| item_type.to_string()),
+        };
+
+        if let Some(item_type) = item_type {
+            self.identification.pending = None;
+            self.identify_appearance(class, &appearance, &item_type);
+        }
+    }
+
+    /// Stop waiting for the effect of the item used, once its messages are received.
+    pub(crate) fn end_of_messages(&mut self) {
+        self.identification.pending = None;
+    }
+}
+
+impl CrawlData {
+    /// Possible types of an unidentified potion or scroll in the inventory,
+    /// with their probability, most likely first. Identified items have a
+    /// single candidate, and other items none.
+    ///
+    /// The types are deduced by elimination of the known types, from the
+    /// items that got identified in the inventory and from the effects seen
+    /// after [`CrawlData::using_item`], weighted by how common each type is.
+    ///
+    /// # Arguments
+    ///
+    /// * `item_index` - Index of the inventory slot (0 for `a`).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Quaff-ID when the potion is likely to be a helpful one
+    /// let candidates = data.item_candidates(index);
+    /// let helpful: f64 = candidates
+    ///     .iter()
+    ///     .filter(|candidate| candidate.item_type != "mutation")
+    ///     .map(|candidate| candidate.probability)
+    ///     .sum();
+    /// if helpful > 0.9 {
+    ///     data.using_item(index);
+    ///     webtile.write_key("q")?;
+    /// }
+    /// ```
+    pub fn item_candidates(&self, item_index: usize) -> Vec<Candidate> {
+        let Some(name) = self.item_name(item_index) else {
+            return vec![];
+        };
+
+        match (appearance(name), name.class, &name.kind) {
+            (Some((class, appearance)), _, _) => self.inventory.candidates(class, &appearance),
+            (None, Some(ItemClass::Potion | ItemClass::Scroll), Some(kind)) => vec![Candidate {
+                item_type: kind.clone(),
+                probability: 1.0,
+            }],
+            _ => vec![],
+        }
+    }
+
+    /// Possible types of an appearance of potion or scroll (e.g. "bubbling",
+    /// "XOZAFIB"), with their probability, most likely first.
+    ///
+    /// # Arguments
+    ///
+    /// * `class` - [`ItemClass::Potion`] or [`ItemClass::Scroll`].
+    /// * `appearance` - A string slice of the appearance.
+    pub fn appearance_candidates(&self, class: ItemClass, appearance: &str) -> Vec<Candidate> {
+        self.inventory.candidates(class, appearance)
+    }
+
+    /// Type of an appearance of potion or scroll, if identified or deduced.
+    ///
+    /// # Arguments
+    ///
+    /// * `class` - [`ItemClass::Potion`] or [`ItemClass::Scroll`].
+    /// * `appearance` - A string slice of the appearance (e.g. "bubbling").
+    pub fn appearance_type(&self, class: ItemClass, appearance: &str) -> Option<&str> {
+        self.inventory
+            .identification
+            .identities
+            .get(&(class, appearance.to_owned()))
+            .map(String::as_str)
+    }
+
+    /// Tell that the unidentified potion or scroll in the slot is about to be
+    /// quaffed or read, so its effect in the next messages identifies it.
+    ///
+    /// # Arguments
+    ///
+    /// * `item_index` - Index of the inventory slot (0 for `a`).
+    pub fn using_item(&mut self, item_index: usize) {
+        self.inventory.identification.pending = self.item_name(item_index).and_then(appearance);
+    }
+
+    /// Tell that an appearance of potion or scroll is not of a type (e.g. used
+    /// without its effect), and deduce the others by elimination.
+    ///
+    /// # Arguments
+    ///
+    /// * `class` - [`ItemClass::Potion`] or [`ItemClass::Scroll`].
+    /// * `appearance` - A string slice of the appearance (e.g. "bubbling").
+    /// * `item_type` - A string slice of the type ruled out (e.g. "haste").
+    pub fn rule_out(&mut self, class: ItemClass, appearance: &str, item_type: &str) {
+        let key = (class, appearance.to_owned());
+        self.inventory.identification.seen.insert(key.clone());
+        self.inventory
+            .identification
+            .ruled_out
+            .entry(key)
+            .or_default()
+            .insert(item_type.to_owned());
+        self.inventory.deduce();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_identification() {
+        let mut data = CrawlData::init(7, "0.32");
+        data.update_player(&json!({"msg": "player", "inv": {
+            "0": {"base_type": 7, "name": "2 bubbling potions", "quantity": 2},
+            "1": {"base_type": 7, "name": "a murky potion", "quantity": 1},
+            "2": {"base_type": 5, "name": "a scroll labelled XOZAFIB", "quantity": 1},
+        }}))
+        .unwrap();
+
+        // Weighted by how common each type is
+        let candidates = data.item_candidates(0);
+        assert_eq!(candidates[0].item_type, "curing");
+        assert_eq!(candidates[1].item_type, "heal wounds");
+        let total: f64 = candidates.iter().map(|c| c.probability).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        // The potion gets its name in the inventory
+        data.update_player(&json!({"msg": "player", "inv": {
+            "0": {"name": "2 potions of curing"},
+        }}))
+        .unwrap();
+        assert_eq!(
+            data.appearance_type(ItemClass::Potion, "bubbling"),
+            Some("curing")
+        );
+        assert!(data.known_potion("curing"));
+        assert!(data
+            .item_candidates(1)
+            .iter()
+            .all(|candidate| candidate.item_type != "curing"));
+
+        // Effect of the potion
+        data.using_item(1);
+        data.process_json(&json!({"msg": "msgs", "messages": [
+            {"text": "You feel much better.", "channel": 0},
+        ]}))
+        .unwrap();
+        assert_eq!(
+            data.appearance_type(ItemClass::Potion, "murky"),
+            Some("heal wounds")
+        );
+
+        // Only one type left
+        for scroll in SCROLL_WEIGHTS.iter().skip(1) {
+            data.learn_scroll(scroll.0);
+        }
+        data.process_known_item_menu(json!([])).unwrap();
+        assert_eq!(
+            data.appearance_type(ItemClass::Scroll, "XOZAFIB"),
+            Some("identify")
+        );
+        assert_eq!(data.item_candidates(2)[0].probability, 1.0);
+    }
+
+    #[test]
+    fn test_identification_versions() {
+        let types = |version: &str| {
+            CrawlData::init(7, version)
+                .appearance_candidates(ItemClass::Potion, "bubbling")
+                .into_iter()
+                .map(|candidate| candidate.item_type)
+                .collect::<Vec<String>>()
+        };
+
+        // Types no longer generated are only ruled out for the versions without them
+        assert!(types("0.28.0").contains(&"degeneration".to_owned()));
+        assert!(types("0.30").contains(&"lignification".to_owned()));
+        assert!(!types("0.32").contains(&"lignification".to_owned()));
+        assert_eq!(types("git").len(), POTION_WEIGHTS.len());
+
+        // An uncommon type is still a candidate, so no deduction
+        let mut data = CrawlData::init(7, "0.28");
+        data.rule_out(ItemClass::Scroll, "XOZAFIB", "torment");
+        for scroll in SCROLL_WEIGHTS.iter().skip(1) {
+            if scroll.0 != "holy word" {
+                data.learn_scroll(scroll.0);
+            }
+        }
+        assert_eq!(data.appearance_type(ItemClass::Scroll, "XOZAFIB"), None);
+        assert_eq!(
+            data.appearance_candidates(ItemClass::Scroll, "XOZAFIB")
+                .len(),
+            2
+        );
+    }
+}
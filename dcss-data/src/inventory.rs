@@ -1,33 +1,47 @@
 use rustc_hash::FxHashMap;
 use serde_json::Value;
 
-use crate::common::{char_to_index, extract_param};
+use crate::common::{char_to_index, extract_param, get_str, get_u64, invalid_field};
+use crate::data_errors::DataError;
+use crate::identification::Identification;
+use crate::items::name::ItemName;
 use crate::items::Item;
 use crate::CrawlData;
 
 use crate::items::armours::Armour;
+use crate::items::books::Book;
+use crate::items::gems::Gem;
 use crate::items::jewellery::Jewellery;
+use crate::items::miscellany::Miscellany;
 use crate::items::missiles::Missile;
 use crate::items::potions::{type_of_potion, Potion};
+use crate::items::runes::Rune;
 use crate::items::scrolls::Scroll;
 use crate::items::staves::Staff;
+use crate::items::talismans::Talisman;
 use crate::items::wands::Wand;
 use crate::items::weapons::Weapon;
 
 #[derive(Debug, Clone)]
 pub(crate) struct Inventory {
     pub(crate) items: Vec<Item>,
+    /// Parsed names of the items, by inventory slot.
+    pub(crate) names: Vec<Option<ItemName>>,
     pub(crate) known_scroll: FxHashMap<String, bool>,
     pub(crate) known_potion: FxHashMap<String, bool>,
+    /// Appearances of the unidentified potions and scrolls.
+    pub(crate) identification: Identification,
     pub(crate) identifying: bool,
 }
 
 impl Inventory {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(version: &str) -> Self {
         let mut inv = Self {
             items: vec![Item::None; 52],
+            names: vec![None; 52],
             known_scroll: FxHashMap::default(),
             known_potion: FxHashMap::default(),
+            identification: Identification::new(version),
             identifying: false, // To not confuse it with the first read of scroll of identify;
         };
 
@@ -85,35 +99,70 @@ impl Inventory {
         inv
     }
 
-    pub(crate) fn update_inventory(&mut self, inventory: Value) {
-        for (i, item) in inventory.as_object().unwrap() {
-            let index: usize = i.parse().unwrap();
-
-            let item_type = item["base_type"].as_i64();
-            if let Some(it) = item_type {
-                self.init_item(index, it as i32);
-            } else if item.as_object().unwrap().contains_key("name") {
-                let name = item.as_object().unwrap()["name"].as_str().unwrap_or("");
-                let quantity = 1; // TODO:
-                self.update_item(index, name, quantity);
+    pub(crate) fn update_inventory(&mut self, inventory: Value) -> Result<(), DataError> {
+        let inventory = inventory
+            .as_object()
+            .ok_or_else(|| invalid_field("inv", "an object"))?;
+
+        for (i, item) in inventory {
+            let index = i
+                .parse::<usize>()
+                .ok()
+                .filter(|index| *index < self.items.len())
+                .ok_or_else(|| DataError::Parse {
+                    what: "inventory slot",
+                    text: i.to_owned(),
+                })?;
+
+            // The name of the previous item, if the item in the slot is the same
+            let mut previous = self.names[index].take();
+
+            if let Some(item_type) = item["base_type"].as_i64() {
+                self.init_item(index, item_type);
+                previous = None;
+            }
+
+            if item.get("name").is_some() && !self.items[index].is_none() {
+                let mut name = ItemName::parse(get_str(item, "name")?);
+                if let Some(quantity) = item["quantity"].as_u64() {
+                    name.quantity = quantity as u32;
+                }
+                self.update_item(index, &name);
+                self.observe_name(previous.as_ref(), &name);
+                previous = Some(name);
+            }
+
+            if !self.items[index].is_none() {
+                self.names[index] = previous;
             }
         }
+
+        Ok(())
     }
 
-    pub(crate) fn init_item(&mut self, index: usize, item_type: i32) {
+    pub(crate) fn init_item(&mut self, index: usize, item_type: i64) {
         self.items[index] = match item_type {
             0 => Item::Weapon(Weapon::new()),
             1 => Item::Missile(Missile::new()),
             2 => Item::Armour(Armour::new()),
             3 => Item::Wand(Wand::new()),
-            4 => unimplemented!(),
+            4 => Item::Food,
             5 => Item::Scroll(Scroll::new()),
             6 => Item::Jewellery(Jewellery::new()),
             7 => Item::Potion(Potion::new()),
-            8 => unimplemented!(),
+            8 => Item::Book(Book::new()),
             9 => Item::Staff(Staff::new()),
-            _ => Item::None,
-        }
+            10 => Item::Orb,
+            11 => Item::Miscellany(Miscellany::new()),
+            12 => Item::Corpse,
+            13 => Item::Gold,
+            15 => Item::Rune(Rune::new()),
+            16 => Item::Talisman(Talisman::new()),
+            17 => Item::Gem(Gem::new()),
+            // Empty slot (OBJ_UNASSIGNED)
+            100 => Item::None,
+            other => Item::Unknown(other),
+        };
     }
 
     pub(crate) fn _reexamine_item(&mut self, index: usize) {
@@ -123,75 +172,87 @@ impl Inventory {
             Item::Missile(item) => item.data_collected = false,
             Item::Armour(item) => item.data_collected = false,
             Item::Wand(item) => item.data_collected = false,
-            Item::_Unknown4 => unimplemented!(),
             Item::Scroll(item) => item.data_collected = false,
             Item::Jewellery(item) => item.data_collected = false,
             Item::Potion(item) => item.data_collected = false,
-            Item::_Unknown8 => unimplemented!(),
+            Item::Book(item) => item.data_collected = false,
             Item::Staff(item) => item.data_collected = false,
+            Item::Miscellany(item) => item.data_collected = false,
+            Item::Rune(item) => item.data_collected = false,
+            Item::Talisman(item) => item.data_collected = false,
+            Item::Gem(item) => item.data_collected = false,
+            Item::Food | Item::Orb | Item::Corpse | Item::Gold | Item::Unknown(_) => (),
         }
     }
 
-    pub(crate) fn update_item(&mut self, index: usize, name: &str, quantity: u64) {
+    pub(crate) fn update_item(&mut self, index: usize, name: &ItemName) {
         match &mut self.items[index] {
-            Item::None => (),
-            Item::Weapon(_) => (),
-            Item::Missile(_) => (),
-            Item::Armour(_) => (),
-            Item::Wand(_) => (),
-            Item::_Unknown4 => unimplemented!(),
-            Item::Scroll(item) => item.update_scroll_values(name, quantity),
-            Item::Jewellery(_) => (),
-            Item::Potion(item) => item.update_potion_values(name, quantity),
-            Item::_Unknown8 => unimplemented!(),
-            Item::Staff(_) => (),
+            Item::Scroll(item) => item.update_scroll_values(name),
+            Item::Potion(item) => item.update_potion_values(name),
+            // Other items are updated from their description
+            _ => (),
         }
     }
 
-    pub(crate) fn description(&mut self, description: Value) {
-        let key = &description["title"].to_string()[1..2];
-        match &mut self.items[char_to_index(key)] {
+    pub(crate) fn description(&mut self, description: Value) -> Result<(), DataError> {
+        let title = get_str(&description, "title")?;
+        let index = title
+            .get(0..1)
+            .and_then(char_to_index)
+            .ok_or_else(|| DataError::Parse {
+                what: "item letter",
+                text: title.to_owned(),
+            })?;
+
+        match &mut self.items[index] {
             Item::None => (),
             Item::Weapon(item) => item.update_weapon(description),
             Item::Missile(item) => item.data_collected = true,
             Item::Armour(item) => item.update_armour(description),
             Item::Wand(item) => item.data_collected = true,
-            Item::_Unknown4 => unimplemented!(),
-            Item::Scroll(item) => item.update_scroll(description),
-            Item::Jewellery(item) => item.update_jewellery(description),
-            Item::Potion(item) => item.update_potion(description),
-            Item::_Unknown8 => unimplemented!(),
+            Item::Scroll(item) => item.update_scroll(description)?,
+            Item::Jewellery(item) => item.update_jewellery(description)?,
+            Item::Potion(item) => item.update_potion(description)?,
+            Item::Book(item) => item.update_book(description),
             Item::Staff(item) => item.data_collected = true,
+            Item::Miscellany(item) => item.update_miscellany(description),
+            Item::Rune(item) => item.update_rune(description),
+            Item::Talisman(item) => item.update_talisman(description),
+            Item::Gem(item) => item.update_gem(description),
+            Item::Food | Item::Orb | Item::Corpse | Item::Gold | Item::Unknown(_) => (),
         }
+
+        Ok(())
     }
 
-    pub(crate) fn process_known_item_menu(&mut self, menu_item: Value) {
-        let table_array = menu_item.as_array().unwrap();
+    pub(crate) fn process_known_item_menu(&mut self, menu_item: Value) -> Result<(), DataError> {
+        let table_array = menu_item
+            .as_array()
+            .ok_or_else(|| invalid_field("items", "an array"))?;
 
         let mut item_type = "";
 
         for item in table_array {
-            let item_obj = item.as_object().unwrap();
-            let level = item_obj["level"].as_u64().unwrap();
+            let level = get_u64(item, "level")?;
+            let text = get_str(item, "text")?;
 
             if level == 1 && !item_type.is_empty() {
                 item_type = "";
             }
 
             // Focus on scrolls
-            if level == 1 && item_obj["text"].as_str().unwrap().contains("Scrolls") {
+            if level == 1 && text.contains("Scrolls") {
                 item_type = "scroll";
                 continue;
             }
 
             // Focus on potions
-            if level == 1 && item_obj["text"].as_str().unwrap().contains("Potions") {
+            if level == 1 && text.contains("Potions") {
                 item_type = "potion";
                 continue;
             }
 
             if item_type == "scroll" {
-                let text = item["text"].as_str().unwrap();
                 let scroll_param = extract_param(text, "scrolls of ", &vec!['(']);
                 let scroll_type;
                 if let Some(param) = scroll_param {
@@ -199,11 +260,10 @@ impl Inventory {
                 } else {
                     continue;
                 }
-                *self.known_scroll.get_mut(&scroll_type).unwrap() = true;
+                self.known_scroll.insert(scroll_type, true);
             }
 
             if item_type == "potion" {
-                let text = item["text"].as_str().unwrap();
                 let potion_param = extract_param(text, "potions of ", &vec!['(']);
                 let potion_type;
                 if let Some(param) = potion_param {
@@ -211,13 +271,26 @@ impl Inventory {
                 } else {
                     continue;
                 }
-                *self.known_potion.get_mut(&potion_type).unwrap() = true;
+                self.known_potion.insert(potion_type, true);
             }
         }
+
+        self.deduce();
+
+        Ok(())
     }
 }
 
 impl CrawlData {
+    /// Parsed name of the item in the inventory slot, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `item_index` - Index of the inventory slot (0 for `a`).
+    pub fn item_name(&self, item_index: usize) -> Option<&ItemName> {
+        self.inventory.names.get(item_index)?.as_ref()
+    }
+
     pub fn known_scroll(&self, scroll_type: &str) -> bool {
         self.inventory.known_scroll[scroll_type]
     }
@@ -229,15 +302,17 @@ impl CrawlData {
     /// Identify a specific scroll as "known"
     pub fn learn_scroll(&mut self, scroll_type: &str) {
         *self.inventory.known_scroll.get_mut(scroll_type).unwrap() = true;
+        self.inventory.deduce();
     }
 
     /// Identify a specific potion as "known"
     pub fn learn_potion(&mut self, potion_type: &str) {
         *self.inventory.known_potion.get_mut(potion_type).unwrap() = true;
+        self.inventory.deduce();
     }
 
     pub fn potion_index(&self, potion_type: &str) -> Option<usize> {
-        let potion_type_enum = type_of_potion(potion_type.to_owned());
+        let potion_type_enum = type_of_potion(potion_type)?;
 
         for index in 0..52 {
             if self.item_is_none(index) || !self.item_data_collected(index) {
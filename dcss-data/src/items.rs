@@ -1,20 +1,31 @@
 pub(crate) mod armours;
+pub(crate) mod books;
+pub(crate) mod gems;
 pub(crate) mod jewellery;
+pub(crate) mod miscellany;
 pub(crate) mod missiles;
+pub(crate) mod name;
 pub(crate) mod potions;
+pub(crate) mod runes;
 pub(crate) mod scrolls;
 pub(crate) mod staves;
+pub(crate) mod talismans;
 pub(crate) mod wands;
 pub(crate) mod weapons;
 
 use armours::{Armour, ArmourType};
+use books::Book;
+use gems::Gem;
 use jewellery::{AmuletType, Jewellery, RingType};
+use miscellany::Miscellany;
 use missiles::Missile;
 use potions::Potion;
 use potions::PotionType;
+use runes::Rune;
 use scrolls::Scroll;
 use scrolls::ScrollType;
 use staves::Staff;
+use talismans::Talisman;
 use wands::Wand;
 use weapons::Weapon;
 
@@ -25,12 +36,25 @@ pub(crate) enum Item {
     Missile(Missile),
     Armour(Armour),
     Wand(Wand),
-    _Unknown4, // Unknown item
+    /// Food (before 0.28), nothing to collect.
+    Food,
     Scroll(Scroll),
     Jewellery(Jewellery),
     Potion(Potion),
-    _Unknown8, // Unknown item
+    Book(Book),
     Staff(Staff),
+    Orb,
+    Miscellany(Miscellany),
+    Corpse,
+    Gold,
+    Rune(Rune),
+    /// Talismans (0.31 and later).
+    Talisman(Talisman),
+    /// Gems (0.32 and later).
+    Gem(Gem),
+    /// Class not known to this library (e.g. from a newer version of the game),
+    /// with its "base_type".
+    Unknown(i64),
 }
 
 #[derive(Clone, Debug)]
@@ -42,8 +66,8 @@ pub(crate) enum WearSlots {
     Boots,
     Shield,
     Body,
-    _RingLeft,
-    _RingRight,
+    RingLeft,
+    RingRight,
     Amulet,
     _Unknown4,
 }
@@ -60,21 +84,46 @@ impl Item {
             Self::Missile(item) => item.data_collected,
             Self::Armour(item) => item.data_collected,
             Self::Wand(item) => item.data_collected,
-            Self::_Unknown4 => unimplemented!(),
             Self::Scroll(item) => item.data_collected,
             Self::Jewellery(item) => item.data_collected,
             Self::Potion(item) => item.data_collected,
-            Self::_Unknown8 => unimplemented!(),
+            Self::Book(item) => item.data_collected,
             Self::Staff(item) => item.data_collected,
+            Self::Miscellany(item) => item.data_collected,
+            Self::Rune(item) => item.data_collected,
+            Self::Talisman(item) => item.data_collected,
+            Self::Gem(item) => item.data_collected,
+            // Nothing to collect
+            Self::Food | Self::Orb | Self::Corpse | Self::Gold | Self::Unknown(_) => true,
         }
     }
 
+    /// Rating used to choose which item to wear or wield, -100 if unknown or if
+    /// the item is not rated. Only weapons, armours and jewellery are rated:
+    /// the other classes (including talismans and evokers, which are evoked)
+    /// are never compared to an equipped item.
     pub(crate) fn rating(&self) -> i32 {
         match self {
             Self::Weapon(item) => item.rating,
             Self::Armour(item) => item.rating,
             Self::Jewellery(item) => item.rating,
-            _ => -100,
+            // Not worn nor wielded
+            Self::None
+            | Self::Missile(_)
+            | Self::Wand(_)
+            | Self::Food
+            | Self::Scroll(_)
+            | Self::Potion(_)
+            | Self::Book(_)
+            | Self::Staff(_)
+            | Self::Orb
+            | Self::Miscellany(_)
+            | Self::Corpse
+            | Self::Gold
+            | Self::Rune(_)
+            | Self::Talisman(_)
+            | Self::Gem(_)
+            | Self::Unknown(_) => -100,
         }
     }
 
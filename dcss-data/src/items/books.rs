@@ -0,0 +1,45 @@
+use serde_json::Value;
+
+#[derive(Clone, Debug)]
+pub(crate) struct Book {
+    pub(crate) data_collected: bool,
+    /// Spells of the book, with their level.
+    pub(crate) spells: Vec<(String, u64)>,
+    /// Skill trained by a manual (e.g. "Fighting").
+    pub(crate) manual_skill: Option<String>,
+}
+
+impl Book {
+    pub(crate) fn new() -> Self {
+        Self {
+            data_collected: false,
+            spells: vec![],
+            manual_skill: None,
+        }
+    }
+
+    pub(crate) fn update_book(&mut self, book_desc: Value) {
+        self.data_collected = true;
+
+        let title = book_desc["title"].as_str().unwrap_or("");
+        if let Some((_, skill)) = title.split_once("manual of ") {
+            self.manual_skill = Some(skill.trim_end_matches('.').trim().to_owned());
+        }
+
+        // Spells, from all the books of the spellset
+        self.spells = book_desc["spellset"]
+            .as_array()
+            .map(|books| {
+                books
+                    .iter()
+                    .filter_map(|book| book["spells"].as_array())
+                    .flatten()
+                    .filter_map(|spell| {
+                        let name = spell["title"].as_str()?.trim().to_owned();
+                        Some((name, spell["level"].as_u64().unwrap_or(0)))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+}
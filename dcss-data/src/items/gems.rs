@@ -0,0 +1,33 @@
+use crate::items::name::ItemName;
+use serde_json::Value;
+
+/// Gem of a branch (0.32 and later), found at the end of the branch.
+#[derive(Clone, Debug)]
+pub(crate) struct Gem {
+    pub(crate) data_collected: bool,
+    /// Name of the gem (e.g. "dark gem"), without article.
+    pub(crate) name: Option<String>,
+    /// If the gem was shattered (the time limit of the branch ran out).
+    pub(crate) shattered: bool,
+}
+
+impl Gem {
+    pub(crate) fn new() -> Self {
+        Self {
+            data_collected: false,
+            name: None,
+            shattered: false,
+        }
+    }
+
+    pub(crate) fn update_gem(&mut self, gem_desc: Value) {
+        self.data_collected = true;
+
+        let name = ItemName::parse(gem_desc["title"].as_str().unwrap_or(""));
+        self.shattered = name.base_type.starts_with("shattered ");
+        self.name = Some(match name.kind {
+            Some(kind) => format!("{} of {kind}", name.base_type),
+            None => name.base_type,
+        });
+    }
+}
@@ -1,3 +1,4 @@
+use crate::data_errors::DataError;
 use serde_json::Value;
 
 #[derive(Clone, Debug)]
@@ -62,7 +63,7 @@ impl Jewellery {
         }
     }
 
-    pub(crate) fn update_jewellery(&mut self, jewellery_desc: Value) {
+    pub(crate) fn update_jewellery(&mut self, jewellery_desc: Value) -> Result<(), DataError> {
         self.data_collected = true;
 
         let title = jewellery_desc["title"].to_string();
@@ -77,8 +78,13 @@ impl Jewellery {
             self.ring_type = ring_type(body);
             self.rating = self.ring_rating();
         } else {
-            unimplemented!("No jewellery type");
+            return Err(DataError::Parse {
+                what: "jewellery type",
+                text: title,
+            });
         }
+
+        Ok(())
     }
 
     pub(crate) fn amulet_rating(&self) -> i32 {
@@ -89,8 +95,7 @@ impl Jewellery {
             AmuletType::Reflect => 2,
             AmuletType::Acrobat => 1,
             AmuletType::RegenMP => 0,
-            AmuletType::Faith => -100,
-            _ => unimplemented!("Failed to identify the amulet type"),
+            AmuletType::Faith | AmuletType::Unknown => -100,
         }
     }
 
@@ -116,7 +121,7 @@ impl Jewellery {
             RingType::Flight => 17,
             RingType::CorrosionResistance => 18,
             RingType::WillPower => 19,
-            _ => unimplemented!("Failed to identify the ring type"),
+            RingType::Unknown => -100,
         }
     }
 }
@@ -136,7 +141,7 @@ pub(crate) fn amulet_type(amulet_desc: String) -> AmuletType {
         return AmuletType::Faith;
     }
 
-    unimplemented!("Failed to identify the amulet type");
+    AmuletType::Unknown
 }
 
 pub(crate) fn ring_type(ring_desc: String) -> RingType {
@@ -182,5 +187,5 @@ pub(crate) fn ring_type(ring_desc: String) -> RingType {
         return RingType::MPP9;
     }
 
-    unimplemented!("Failed to identify the ring type")
+    RingType::Unknown
 }
@@ -0,0 +1,30 @@
+use regex::Regex;
+use serde_json::Value;
+use std::sync::LazyLock;
+
+static CHARGES: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\((\d+)/(\d+)\)").unwrap());
+
+#[derive(Clone, Debug)]
+pub(crate) struct Miscellany {
+    pub(crate) data_collected: bool,
+    /// Charges left and maximum charges of evokers (e.g. "box of beasts (2/3)").
+    pub(crate) charges: Option<(u32, u32)>,
+}
+
+impl Miscellany {
+    pub(crate) fn new() -> Self {
+        Self {
+            data_collected: false,
+            charges: None,
+        }
+    }
+
+    pub(crate) fn update_miscellany(&mut self, misc_desc: Value) {
+        self.data_collected = true;
+
+        let title = misc_desc["title"].as_str().unwrap_or("");
+        self.charges = CHARGES
+            .captures(title)
+            .and_then(|caps| Some((caps[1].parse::<u32>().ok()?, caps[2].parse::<u32>().ok()?)));
+    }
+}
@@ -0,0 +1,616 @@
+use dcss_api::FormattedText;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Base types of the weapons.
+const WEAPONS: [&str; 47] = [
+    "executioner's axe",
+    "giant spiked club",
+    "triple crossbow",
+    "sacred scourge",
+    "eudemon blade",
+    "demon trident",
+    "double sword",
+    "triple sword",
+    "quarterstaff",
+    "short sword",
+    "hand cannon",
+    "eveningstar",
+    "morningstar",
+    "demon blade",
+    "great sword",
+    "quick blade",
+    "giant club",
+    "great mace",
+    "long sword",
+    "demon whip",
+    "dire flail",
+    "battleaxe",
+    "broad axe",
+    "shortbow",
+    "longbow",
+    "arbalest",
+    "bardiche",
+    "falchion",
+    "hand axe",
+    "lajatang",
+    "partisan",
+    "scimitar",
+    "trishula",
+    "war axe",
+    "trident",
+    "halberd",
+    "orcbow",
+    "dagger",
+    "rapier",
+    "glaive",
+    "scythe",
+    "spear",
+    "flail",
+    "sling",
+    "club",
+    "mace",
+    "whip",
+];
+
+/// Base types of the missiles.
+const MISSILES: [&str; 9] = [
+    "sling bullet",
+    "throwing net",
+    "large rock",
+    "boomerang",
+    "javelin",
+    "stone",
+    "arrow",
+    "bolt",
+    "dart",
+];
+
+/// Base types of the armours (the last word is enough for "dragon scales",
+/// "troll leather armour", etc.).
+const ARMOURS: [&str; 20] = [
+    "plate armour",
+    "leather armour",
+    "animal skin",
+    "chain mail",
+    "scale mail",
+    "ring mail",
+    "kite shield",
+    "tower shield",
+    "gauntlets",
+    "buckler",
+    "barding",
+    "helmet",
+    "scales",
+    "gloves",
+    "cloak",
+    "scarf",
+    "boots",
+    "robe",
+    "hat",
+    "orb",
+];
+
+/// Base types of the miscellaneous evokers (first words of the name).
+const MISCELLANEOUS: [&str; 11] = [
+    "lamp of fire",
+    "phial of floods",
+    "box of beasts",
+    "sack of spiders",
+    "fan of gales",
+    "horn of Geryon",
+    "crystal ball",
+    "lightning rod",
+    "condenser vane",
+    "tin of tremorstones",
+    "phantom mirror",
+];
+
+/// Properties of artefacts without a value (see `artefact_inscription` in the game).
+const FLAG_PROPERTIES: [&str; 26] = [
+    "SInv", "Fly", "Clar", "Harm", "Rampage", "Faith", "Spirit", "Acrobat", "Reflect", "Gourm",
+    "Archmagi", "Wiz", "Inv", "rMut", "rCorr", "rPois", "rElec", "^Drain", "^Contam", "^Fragile",
+    "*Slow", "*Rage", "*Corrode", "*Noise", "*Silence", "-Cast",
+];
+
+/// Weapon brands, as shown in the inscription of artefacts.
+const BRANDS: [&str; 18] = [
+    "flame",
+    "freeze",
+    "holy",
+    "elec",
+    "venom",
+    "protect",
+    "drain",
+    "speed",
+    "vamp",
+    "pain",
+    "antimagic",
+    "distort",
+    "chaos",
+    "penet",
+    "reap",
+    "spect",
+    "heavy",
+    "valour",
+];
+
+/// Property with a value (e.g. "rF++", "Str+3", "Will-").
+static VALUE_PROPERTY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[*^]?[A-Za-z]+(?:[+-]\d+|\++|-+)$").unwrap());
+static ENCHANTMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^([+-]\d+) ").unwrap());
+static QUANTITY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\d+) ").unwrap());
+static SLOT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^([a-zA-Z]) [-+#] ").unwrap());
+static RANDART_NAME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\s*"([^"]+)""#).unwrap());
+
+/// Class of an item (`object_class_type` in the game), from its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ItemClass {
+    Weapon,
+    Missile,
+    Armour,
+    Wand,
+    Scroll,
+    Jewellery,
+    Potion,
+    Book,
+    Staff,
+    Miscellaneous,
+    Talisman,
+    Gold,
+    Rune,
+    Orb,
+    /// Gems of the branches (0.32 and later).
+    Gem,
+    Corpse,
+    /// Food (before 0.28).
+    Food,
+}
+
+/// Artefact, with its name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Artefact {
+    /// Fixed artefact (e.g. "Staff of Olgreb").
+    Unrandom(String),
+    /// Random artefact, or [None] if the name is not shown.
+    Random(Option<String>),
+}
+
+/// Item name, as shown by the game (e.g. "a +2 broad axe of flaming {Hunt}"),
+/// split in its parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemName {
+    /// Inventory letter, if the name starts with it (e.g. "a - a +0 dagger").
+    pub slot: Option<char>,
+    pub quantity: u32,
+    /// Enchantment of weapons, armours and some jewellery (e.g. +2).
+    pub enchantment: Option<i32>,
+    /// Charges of wands.
+    pub charges: Option<u32>,
+    pub class: Option<ItemClass>,
+    /// Base type, in the singular (e.g. "broad axe", "potion", "ring"), with the
+    /// appearance of unidentified items (e.g. "bubbling potion").
+    pub base_type: String,
+    /// Subtype of consumables, jewellery, wands and staves (e.g. "heal wounds",
+    /// "protection from fire").
+    pub kind: Option<String>,
+    /// Ego of weapons and armours (e.g. "flaming", "fire resistance").
+    pub ego: Option<String>,
+    pub artefact: Option<Artefact>,
+    /// Properties of artefacts and brands (e.g. "rF+", "Str+3", "flame").
+    pub properties: Vec<String>,
+    /// Inscription of the player (e.g. "Hunt").
+    pub inscription: Option<String>,
+    /// Curse status, shown before 0.26.
+    pub cursed: Option<bool>,
+    /// If the item is worn or wielded.
+    pub equipped: bool,
+    /// If the type of item is known (e.g. "a scroll of fog" and not "a scroll
+    /// labelled XXX").
+    pub identified: bool,
+}
+
+impl ItemClass {
+    /// Class from the "base_type" field of the inventory items, or [None] if
+    /// not supported.
+    pub fn from_base_type(base_type: i64) -> Option<Self> {
+        let class = match base_type {
+            0 => Self::Weapon,
+            1 => Self::Missile,
+            2 => Self::Armour,
+            3 => Self::Wand,
+            4 => Self::Food,
+            5 => Self::Scroll,
+            6 => Self::Jewellery,
+            7 => Self::Potion,
+            8 => Self::Book,
+            9 => Self::Staff,
+            10 => Self::Orb,
+            11 => Self::Miscellaneous,
+            12 => Self::Corpse,
+            13 => Self::Gold,
+            15 => Self::Rune,
+            16 => Self::Talisman,
+            17 => Self::Gem,
+            _ => return None,
+        };
+
+        Some(class)
+    }
+
+    /// Class from the base type of an item name (e.g. "broad axe", "potion").
+    fn from_base_name(base: &str, full: &str) -> Option<Self> {
+        // Unidentified scrolls are "labelled" (e.g. "scroll labelled XOZAFIB")
+        let base = base.split(" labelled ").next().unwrap_or(base);
+        let last_word = base.rsplit(' ').next().unwrap_or(base);
+
+        let class = if MISCELLANEOUS.iter().any(|misc| full.starts_with(misc)) {
+            Self::Miscellaneous
+        } else if full.starts_with("Orb of Zot") {
+            Self::Orb
+        } else if base.ends_with("gold piece") || base.ends_with("gold pieces") {
+            Self::Gold
+        } else if MISSILES.iter().any(|missile| ends_with_word(base, missile)) {
+            Self::Missile
+        } else if WEAPONS.iter().any(|weapon| ends_with_word(base, weapon)) {
+            Self::Weapon
+        } else if ARMOURS.iter().any(|armour| ends_with_word(base, armour)) {
+            Self::Armour
+        } else if matches!(last_word, "potion" | "potions") {
+            Self::Potion
+        } else if matches!(last_word, "scroll" | "scrolls") {
+            Self::Scroll
+        } else if matches!(last_word, "ring" | "amulet") {
+            Self::Jewellery
+        } else if last_word == "wand" {
+            Self::Wand
+        } else if matches!(last_word, "staff" | "Staff") {
+            Self::Staff
+        } else if matches!(last_word, "book" | "manual" | "parchment" | "tome") {
+            Self::Book
+        } else if last_word == "talisman" {
+            Self::Talisman
+        } else if matches!(last_word, "rune" | "runes") {
+            Self::Rune
+        } else if matches!(last_word, "gem" | "gems") {
+            Self::Gem
+        } else if matches!(last_word, "corpse" | "corpses" | "skeleton" | "skeletons") {
+            Self::Corpse
+        } else {
+            return None;
+        };
+
+        Some(class)
+    }
+}
+
+impl ItemName {
+    /// Parse an item name, as shown in the inventory, on the floor or in the
+    /// messages. Colour tags are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - A string slice of the item name.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let name = ItemName::parse("3 potions of heal wounds");
+    /// assert_eq!(name.quantity, 3);
+    /// assert_eq!(name.kind.as_deref(), Some("heal wounds"));
+    /// ```
+    pub fn parse(name: &str) -> Self {
+        let mut rest = FormattedText::parse(name).plain().trim().to_owned();
+        let mut item = Self {
+            slot: None,
+            quantity: 1,
+            enchantment: None,
+            charges: None,
+            class: None,
+            base_type: String::new(),
+            kind: None,
+            ego: None,
+            artefact: None,
+            properties: vec![],
+            inscription: None,
+            cursed: None,
+            equipped: false,
+            identified: false,
+        };
+
+        if let Some(caps) = SLOT.captures(&rest) {
+            item.slot = caps[1].chars().next();
+            rest = rest[caps[0].len()..].to_owned();
+        }
+        rest = rest.trim_end_matches('.').to_owned();
+
+        // Suffixes: inscriptions and properties, charges and equipment slot
+        loop {
+            rest = rest.trim_end().to_owned();
+
+            if rest.ends_with('}') {
+                let Some(start) = rest.rfind('{') else {
+                    break;
+                };
+                item.inscription_parts(&rest[start + 1..rest.len() - 1]);
+                rest.truncate(start);
+            } else if rest.ends_with(')') {
+                let Some(start) = rest.rfind('(') else {
+                    break;
+                };
+                let inside = &rest[start + 1..rest.len() - 1];
+                match inside.parse::<u32>() {
+                    Ok(charges) => item.charges = Some(charges),
+                    Err(_) => item.equipped |= is_equip_slot(inside),
+                }
+                rest.truncate(start);
+            } else {
+                break;
+            }
+        }
+
+        // Prefixes: article or quantity, curse status, enchantment
+        let mut definite = false;
+        if let Some(stripped) = rest.strip_prefix("the ").or(rest.strip_prefix("The ")) {
+            definite = true;
+            rest = stripped.to_owned();
+        } else if let Some(stripped) = rest.strip_prefix("an ").or(rest.strip_prefix("a ")) {
+            rest = stripped.to_owned();
+        } else if let Some(caps) = QUANTITY.captures(&rest) {
+            item.quantity = caps[1].parse().unwrap_or(1);
+            rest = rest[caps[0].len()..].to_owned();
+        }
+
+        if let Some(stripped) = rest.strip_prefix("uncursed ") {
+            item.cursed = Some(false);
+            rest = stripped.to_owned();
+        } else if let Some(stripped) = rest.strip_prefix("cursed ") {
+            item.cursed = Some(true);
+            rest = stripped.to_owned();
+        }
+
+        if let Some(caps) = ENCHANTMENT.captures(&rest) {
+            item.enchantment = caps[1].parse().ok();
+            rest = rest[caps[0].len()..].to_owned();
+        }
+
+        // Name of random artefacts (e.g. `"Foobar"`)
+        if let Some(caps) = RANDART_NAME.captures(&rest) {
+            item.artefact = Some(Artefact::Random(Some(caps[1].to_owned())));
+            rest = rest.replacen(&caps[0], "", 1).trim().to_owned();
+        }
+
+        // Gloves and boots (e.g. "a +1 pair of boots of running"), already
+        // plural whatever the quantity
+        let pair = match rest
+            .strip_prefix("pair of ")
+            .or(rest.strip_prefix("pairs of "))
+        {
+            Some(stripped) => {
+                rest = stripped.to_owned();
+                true
+            }
+            None => false,
+        };
+
+        let (base, of) = match rest.split_once(" of ") {
+            Some((base, of)) if !MISCELLANEOUS.iter().any(|misc| rest.starts_with(misc)) => {
+                (base.to_owned(), Some(of.to_owned()))
+            }
+            _ => (rest.clone(), None),
+        };
+
+        item.class = ItemClass::from_base_name(&base, &rest);
+        item.base_type = if item.class == Some(ItemClass::Miscellaneous) {
+            rest.clone()
+        } else if pair {
+            base.clone()
+        } else {
+            singular(&base, item.quantity)
+        };
+
+        if let Some(of) = of {
+            // Any capitalised word (e.g. "the amulet of the Four Winds")
+            let capitalised = of
+                .split(' ')
+                .any(|word| word.starts_with(|c: char| c.is_uppercase()))
+                || base.starts_with(|c: char| c.is_uppercase());
+
+            // Runes ("of Zot"), the Orb and books (e.g. "book of Fire") are not
+            // artefacts
+            let artefact_class = matches!(
+                item.class,
+                Some(ItemClass::Weapon)
+                    | Some(ItemClass::Armour)
+                    | Some(ItemClass::Jewellery)
+                    | Some(ItemClass::Staff)
+                    | None
+            );
+
+            if !artefact_class {
+                item.kind = Some(of);
+            } else if capitalised && definite {
+                item.artefact = Some(Artefact::Unrandom(rest.clone()));
+            } else if capitalised {
+                item.artefact = Some(Artefact::Random(Some(format!("of {of}"))));
+            } else {
+                match item.class {
+                    Some(ItemClass::Weapon) | Some(ItemClass::Armour) => item.ego = Some(of),
+                    _ => item.kind = Some(of),
+                }
+            }
+        }
+
+        // Random artefacts without a name shown (e.g. not yet identified)
+        if item.artefact.is_none() && !item.properties.is_empty() && definite {
+            item.artefact = Some(Artefact::Random(None));
+        }
+
+        item.identified = match item.class {
+            Some(ItemClass::Weapon) | Some(ItemClass::Armour) => {
+                item.enchantment.is_some() || item.artefact.is_some()
+            }
+            Some(ItemClass::Scroll)
+            | Some(ItemClass::Potion)
+            | Some(ItemClass::Jewellery)
+            | Some(ItemClass::Wand)
+            | Some(ItemClass::Staff)
+            | Some(ItemClass::Book) => item.kind.is_some() || item.artefact.is_some(),
+            Some(_) => true,
+            None => false,
+        };
+
+        item
+    }
+
+    /// Sort the parts of a `{...}` suffix between artefact properties and the
+    /// inscription of the player.
+    fn inscription_parts(&mut self, inside: &str) {
+        let mut properties = vec![];
+        let mut inscription = vec![];
+
+        for part in inside.split(", ") {
+            let tokens = part.split_whitespace().collect::<Vec<&str>>();
+
+            if !tokens.is_empty() && tokens.iter().all(|token| is_property(token)) {
+                properties.extend(tokens.iter().map(|token| token.to_string()));
+            } else if !part.trim().is_empty() {
+                inscription.push(part.trim());
+            }
+        }
+
+        // Suffixes are parsed from the end
+        self.properties.splice(0..0, properties);
+
+        if !inscription.is_empty() {
+            let inscription = inscription.join(", ");
+            self.inscription = Some(match self.inscription.take() {
+                Some(previous) => format!("{inscription}, {previous}"),
+                None => inscription,
+            });
+        }
+    }
+}
+
+fn is_property(token: &str) -> bool {
+    FLAG_PROPERTIES.contains(&token) || BRANDS.contains(&token) || VALUE_PROPERTY.is_match(token)
+}
+
+fn is_equip_slot(text: &str) -> bool {
+    matches!(
+        text,
+        "weapon" | "worn" | "in hand" | "left hand" | "right hand" | "around neck" | "on tentacle"
+    ) || text.starts_with("left ")
+        || text.starts_with("right ")
+        || text.ends_with(" hand")
+}
+
+/// If the base ends with the word(s) (e.g. "runed broad axe" ends with "broad axe").
+fn ends_with_word(base: &str, word: &str) -> bool {
+    let singular = base.strip_suffix('s').unwrap_or(base);
+
+    [base, singular].iter().any(|base| {
+        base.strip_suffix(word)
+            .is_some_and(|start| start.is_empty() || start.ends_with(' '))
+    })
+}
+
+/// Singular of the base type of a stack of items (e.g. "potions" to "potion").
+fn singular(base: &str, quantity: u32) -> String {
+    if quantity <= 1 {
+        return base.to_owned();
+    }
+
+    if let Some((base, label)) = base.split_once(" labelled ") {
+        return format!("{} labelled {label}", singular(base, quantity));
+    }
+
+    base.strip_suffix("ies")
+        .map(|start| format!("{start}y"))
+        .or_else(|| base.strip_suffix('s').map(str::to_owned))
+        .unwrap_or_else(|| base.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_item_names() {
+        let axe = ItemName::parse("a +2 broad axe of flaming {Hunt}");
+        assert_eq!(axe.enchantment, Some(2));
+        assert_eq!(axe.class, Some(ItemClass::Weapon));
+        assert_eq!(axe.base_type, "broad axe");
+        assert_eq!(axe.ego.as_deref(), Some("flaming"));
+        assert_eq!(axe.inscription.as_deref(), Some("Hunt"));
+        assert!(axe.identified);
+
+        let potions = ItemName::parse("3 potions of heal wounds");
+        assert_eq!(potions.quantity, 3);
+        assert_eq!(potions.class, Some(ItemClass::Potion));
+        assert_eq!(potions.base_type, "potion");
+        assert_eq!(potions.kind.as_deref(), Some("heal wounds"));
+
+        let ring = ItemName::parse("an uncursed ring of protection from fire");
+        assert_eq!(ring.cursed, Some(false));
+        assert_eq!(ring.class, Some(ItemClass::Jewellery));
+        assert_eq!(ring.kind.as_deref(), Some("protection from fire"));
+
+        let staff = ItemName::parse("the +9 Staff of Olgreb");
+        assert_eq!(staff.enchantment, Some(9));
+        assert_eq!(
+            staff.artefact,
+            Some(Artefact::Unrandom("Staff of Olgreb".to_owned()))
+        );
+
+        let randart = ItemName::parse(
+            "b - the +3 quarterstaff \"Kizoff\" {freeze, rC+ Str+3, Ouch} (weapon)",
+        );
+        assert_eq!(randart.slot, Some('b'));
+        assert!(randart.equipped);
+        assert_eq!(randart.base_type, "quarterstaff");
+        assert_eq!(
+            randart.artefact,
+            Some(Artefact::Random(Some("Kizoff".to_owned())))
+        );
+        assert_eq!(randart.properties, vec!["freeze", "rC+", "Str+3"]);
+        assert_eq!(randart.inscription.as_deref(), Some("Ouch"));
+
+        let scroll = ItemName::parse("2 scrolls labelled XOZAFIB");
+        assert_eq!(scroll.class, Some(ItemClass::Scroll));
+        assert_eq!(scroll.base_type, "scroll labelled XOZAFIB");
+        assert!(!scroll.identified);
+
+        let wand = ItemName::parse("a wand of flame (7)");
+        assert_eq!(wand.charges, Some(7));
+        assert_eq!(wand.kind.as_deref(), Some("flame"));
+
+        let potion = ItemName::parse("a <lightgreen>bubbling potion</lightgreen>");
+        assert_eq!(potion.base_type, "bubbling potion");
+        assert!(!potion.identified);
+
+        let box_of_beasts = ItemName::parse("a box of beasts");
+        assert_eq!(box_of_beasts.class, Some(ItemClass::Miscellaneous));
+        assert_eq!(box_of_beasts.kind, None);
+
+        let boots = ItemName::parse("a +1 pair of boots of running");
+        assert_eq!(boots.class, Some(ItemClass::Armour));
+        assert_eq!(boots.base_type, "boots");
+        assert_eq!(boots.ego.as_deref(), Some("running"));
+        assert!(boots.identified);
+
+        let gloves = ItemName::parse("2 pairs of gloves");
+        assert_eq!(gloves.quantity, 2);
+        assert_eq!(gloves.class, Some(ItemClass::Armour));
+        assert_eq!(gloves.base_type, "gloves");
+
+        let amulet = ItemName::parse("the amulet of the Four Winds");
+        assert_eq!(amulet.class, Some(ItemClass::Jewellery));
+        assert_eq!(amulet.kind, None);
+        assert_eq!(
+            amulet.artefact,
+            Some(Artefact::Unrandom("amulet of the Four Winds".to_owned()))
+        );
+    }
+}
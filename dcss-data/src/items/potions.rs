@@ -1,4 +1,6 @@
-use crate::common::extract_param;
+use crate::common::{extract_param, get_str};
+use crate::data_errors::DataError;
+use crate::items::name::{ItemClass, ItemName};
 use serde_json::Value;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -39,54 +41,42 @@ impl Potion {
         }
     }
 
-    pub(crate) fn update_potion(&mut self, description: Value) {
+    pub(crate) fn update_potion(&mut self, description: Value) -> Result<(), DataError> {
         self.data_collected = true;
 
-        if description["body"]
-            .as_str()
-            .unwrap()
-            .contains(" identified ")
-        {
-            self.identified = true;
-        } else {
-            return;
+        if !get_str(&description, "body")?.contains(" identified ") {
+            return Ok(());
         }
+        self.identified = true;
 
-        if let Some(potion_type) = extract_param(
-            description["title"].as_str().unwrap(),
-            "potion of ",
-            &vec!['.', '{'],
-        ) {
-            self.potion_type = type_of_potion(potion_type.trim_end().to_owned());
-        } else {
-            self.potion_type = type_of_potion(
-                extract_param(
-                    description["title"].as_str().unwrap(),
-                    "potions of ",
-                    &vec!['.', '{'],
-                )
-                .unwrap()
-                .trim_end()
-                .to_owned(),
-            );
-        }
+        let title = get_str(&description, "title")?;
+        let potion_type = extract_param(title, "potion of ", &vec!['.', '{'])
+            .or_else(|| extract_param(title, "potions of ", &vec!['.', '{']))
+            .ok_or_else(|| DataError::Parse {
+                what: "potion type",
+                text: title.to_owned(),
+            })?;
+        self.potion_type = type_of_potion(potion_type.trim_end()).unwrap_or(PotionType::Unknown);
+
+        Ok(())
     }
 
-    pub(crate) fn update_potion_values(&mut self, name: &str, _quantity: u64) {
-        let mut potion_type = extract_param(name, "potion of ", &vec!['\n', '{']);
-        if potion_type.is_none() {
-            potion_type = extract_param(name, "potions of ", &vec!['.', '{', '\n']);
+    pub(crate) fn update_potion_values(&mut self, name: &ItemName) {
+        if name.class != Some(ItemClass::Potion) {
+            return;
         }
 
-        if let Some(pt) = potion_type {
+        if let Some(potion_type) = &name.kind {
             self.identified = true;
-            self.potion_type = type_of_potion(pt.trim_end().to_owned());
+            self.potion_type = type_of_potion(potion_type).unwrap_or(PotionType::Unknown);
         }
     }
 }
 
-pub(crate) fn type_of_potion(potion_type: String) -> PotionType {
-    match &potion_type[..] {
+/// Type of potion from its name (e.g. "heal wounds"), or [None] for
+/// a name unknown to this version of the crate.
+pub(crate) fn type_of_potion(potion_type: &str) -> Option<PotionType> {
+    let potion_type = match potion_type {
         "berserk rage" => PotionType::Berserk,
         "haste" => PotionType::Haste,
         "experience" => PotionType::Experience,
@@ -104,8 +94,8 @@ pub(crate) fn type_of_potion(potion_type: String) -> PotionType {
         "mutation" => PotionType::Mutation,
         "ambrosia" => PotionType::Ambrosia,
         "magic" => PotionType::Magic,
-        _ => {
-            unreachable!();
-        }
-    }
+        _ => return None,
+    };
+
+    Some(potion_type)
 }
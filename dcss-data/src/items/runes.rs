@@ -0,0 +1,25 @@
+use crate::items::name::ItemName;
+use serde_json::Value;
+
+#[derive(Clone, Debug)]
+pub(crate) struct Rune {
+    pub(crate) data_collected: bool,
+    /// Type of rune (e.g. "silver", "decaying").
+    pub(crate) rune_type: Option<String>,
+}
+
+impl Rune {
+    pub(crate) fn new() -> Self {
+        Self {
+            data_collected: false,
+            rune_type: None,
+        }
+    }
+
+    pub(crate) fn update_rune(&mut self, rune_desc: Value) {
+        self.data_collected = true;
+
+        let name = ItemName::parse(rune_desc["title"].as_str().unwrap_or(""));
+        self.rune_type = name.base_type.strip_suffix(" rune").map(str::to_owned);
+    }
+}
@@ -1,4 +1,6 @@
-use crate::common::extract_param;
+use crate::common::{extract_param, get_str};
+use crate::data_errors::DataError;
+use crate::items::name::{ItemClass, ItemName};
 use serde_json::Value;
 
 #[derive(Clone, Debug)]
@@ -42,54 +44,42 @@ impl Scroll {
         }
     }
 
-    pub(crate) fn update_scroll(&mut self, description: Value) {
+    pub(crate) fn update_scroll(&mut self, description: Value) -> Result<(), DataError> {
         self.data_collected = true;
 
-        if description["body"]
-            .as_str()
-            .unwrap()
-            .contains(" identified ")
-        {
-            self.identified = true;
-        } else {
-            return;
+        if !get_str(&description, "body")?.contains(" identified ") {
+            return Ok(());
         }
+        self.identified = true;
 
-        if let Some(scroll_type) = extract_param(
-            description["title"].as_str().unwrap(),
-            "scroll of ",
-            &vec!['.', '{'],
-        ) {
-            self.scroll_type = type_of_scroll(scroll_type.trim_end().to_owned());
-        } else {
-            self.scroll_type = type_of_scroll(
-                extract_param(
-                    description["title"].as_str().unwrap(),
-                    "scrolls of ",
-                    &vec!['.', '{'],
-                )
-                .unwrap()
-                .trim_end()
-                .to_owned(),
-            );
-        }
+        let title = get_str(&description, "title")?;
+        let scroll_type = extract_param(title, "scroll of ", &vec!['.', '{'])
+            .or_else(|| extract_param(title, "scrolls of ", &vec!['.', '{']))
+            .ok_or_else(|| DataError::Parse {
+                what: "scroll type",
+                text: title.to_owned(),
+            })?;
+        self.scroll_type = type_of_scroll(scroll_type.trim_end()).unwrap_or(ScrollType::Unknown);
+
+        Ok(())
     }
 
-    pub(crate) fn update_scroll_values(&mut self, name: &str, _quantity: u64) {
-        let mut scroll_type = extract_param(name, "scroll of ", &vec!['\n', '{']);
-        if scroll_type.is_none() {
-            scroll_type = extract_param(name, "scrolls of ", &vec!['.', '{']);
+    pub(crate) fn update_scroll_values(&mut self, name: &ItemName) {
+        if name.class != Some(ItemClass::Scroll) {
+            return;
         }
 
-        if let Some(pt) = scroll_type {
+        if let Some(scroll_type) = &name.kind {
             self.identified = true;
-            self.scroll_type = type_of_scroll(pt.trim_end().to_owned());
+            self.scroll_type = type_of_scroll(scroll_type).unwrap_or(ScrollType::Unknown);
         }
     }
 }
 
-pub(crate) fn type_of_scroll(scroll_type: String) -> ScrollType {
-    match &scroll_type[..] {
+/// Type of scroll from its name (e.g. "enchant armour"), or [None] for
+/// a name unknown to this version of the crate.
+pub(crate) fn type_of_scroll(scroll_type: &str) -> Option<ScrollType> {
+    let scroll_type = match scroll_type {
         "acquirement" => ScrollType::Acquirement,
         "amnesia" => ScrollType::Amnesia,
         "blinking" => ScrollType::Blinking,
@@ -110,8 +100,8 @@ pub(crate) fn type_of_scroll(scroll_type: String) -> ScrollType {
         "vulnerability" => ScrollType::Vulnerability,
         "poison" => ScrollType::Poison,
         "butterflies" => ScrollType::Butterflies,
-        _ => {
-            unreachable!();
-        }
-    }
+        _ => return None,
+    };
+
+    Some(scroll_type)
 }
@@ -0,0 +1,28 @@
+use crate::items::name::ItemName;
+use serde_json::Value;
+
+#[derive(Clone, Debug)]
+pub(crate) struct Talisman {
+    pub(crate) data_collected: bool,
+    /// Form given by the talisman (e.g. "protean", "death").
+    pub(crate) form: Option<String>,
+}
+
+impl Talisman {
+    pub(crate) fn new() -> Self {
+        Self {
+            data_collected: false,
+            form: None,
+        }
+    }
+
+    pub(crate) fn update_talisman(&mut self, talisman_desc: Value) {
+        self.data_collected = true;
+
+        // "talisman of death" or "protean talisman"
+        let name = ItemName::parse(talisman_desc["title"].as_str().unwrap_or(""));
+        self.form = name
+            .kind
+            .or_else(|| name.base_type.strip_suffix(" talisman").map(str::to_owned));
+    }
+}
@@ -2,20 +2,52 @@
 
 mod abilities;
 mod common;
+mod data_errors;
+mod dungeon;
+mod evaluation;
+mod events;
+mod game_time;
+mod identification;
 mod inventory;
 mod items;
 mod log;
 mod menus;
 mod monsters;
+mod morgue;
+mod movement;
+mod pathfinding;
 mod pickup;
 mod player;
 mod skills;
+mod status;
 mod tiles;
 
 pub use crate::common::Coord;
+pub use crate::data_errors::DataError;
+pub use crate::dungeon::LevelId;
+pub use crate::evaluation::{Aspect, Comparison, Difference, Resistance};
+pub use crate::events::{Actor, EventKind, GameEvent, MessageChannel, Stat};
+pub use crate::game_time::ActionTime;
+pub use crate::identification::Candidate;
+pub use crate::items::name::{Artefact, ItemClass, ItemName};
+pub use crate::monsters::description::{
+    AttackFlavour, MonsterAttack, MonsterClass, MonsterDescription, MonsterIntelligence,
+    MonsterSize, MonsterSpell, Threat,
+};
+pub use crate::monsters::view::{MonsterQuery, MonsterView};
+pub use crate::monsters::{Attitude, DamageLevel, ExamineRequest, MonsterFlag};
+pub use crate::morgue::{
+    CharacterDump, DumpItem, DumpKill, DumpNote, DumpSkill, DumpSpell, DumpStats, SkillTraining,
+};
+pub use crate::movement::MovementProfile;
+pub use crate::pathfinding::{DistanceMap, Goal};
+pub use crate::status::{PoisonLevel, Status, StatusDuration, StatusEffect, StatusKind};
+pub use dcss_api::{Colour, FormattedText, Span};
 
 use crate::abilities::Abilities;
-use crate::common::{pathfinding, structured_table};
+use crate::common::{get_array, get_str, get_u64, structured_table};
+use crate::dungeon::Dungeon;
+use crate::game_time::GameTime;
 use crate::inventory::Inventory;
 use crate::items::armours::ArmourType;
 use crate::items::jewellery::AmuletType;
@@ -24,11 +56,12 @@ use crate::items::{Item, WearSlots};
 use crate::log::Log;
 use crate::menus::Menus;
 use crate::monsters::Monsters;
+use crate::pathfinding::pathfinding;
 use crate::pickup::Pickup;
 use crate::player::Player;
 use crate::skills::Skills;
 use crate::tiles::Tiles;
-use std::error::Error;
+use rustc_hash::FxHashMap;
 
 use serde_json::Value;
 
@@ -57,10 +90,21 @@ pub struct CrawlData {
     inventory: Inventory,
     /// TO COMMENT
     log: Log,
+    /// Game turn and time, and time taken by each action.
+    time: GameTime,
+    /// Map, monsters and items of the levels visited, other than the current one.
+    dungeon: Dungeon,
+    /// Stats of each type of monster examined during the game, by name.
+    examined: FxHashMap<String, MonsterDescription>,
+    /// Number of messages received that could not be handled, by message type.
+    unhandled: FxHashMap<String, u64>,
+    /// Return an error for messages that can't be handled, instead of counting
+    /// them (see [`CrawlData::set_strict`]).
+    strict: bool,
 }
 
 impl CrawlData {
-    pub fn init(fov: u32, _version: &str) -> Self {
+    pub fn init(fov: u32, version: &str) -> Self {
         Self {
             fov,
             spectators: 0,
@@ -71,13 +115,33 @@ impl CrawlData {
             menus: Menus::init(),
             abilities: Abilities::new(),
             skills: Skills::new(),
-            inventory: Inventory::new(),
+            inventory: Inventory::new(version),
             log: Log::init(),
+            time: GameTime::init(),
+            dungeon: Dungeon::init(),
+            examined: FxHashMap::default(),
+            unhandled: FxHashMap::default(),
+            strict: false,
         }
     }
 
-    pub fn process_json(&mut self, message: &Value) -> Result<(), Box<dyn Error>> {
-        let msg = message["msg"].as_str().unwrap();
+    /// Process a message received from DCSS Webtiles. Messages of an unknown
+    /// type are ignored and counted (see [`CrawlData::unhandled_messages`]),
+    /// unless in strict mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - A [serde_json::Value] received by DCSS Webtiles.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// while let Some(message) = webtile.get_message() {
+    ///     data.process_json(&message)?;
+    /// }
+    /// ```
+    pub fn process_json(&mut self, message: &Value) -> Result<(), DataError> {
+        let msg = get_str(message, "msg")?;
 
         match msg {
             // Ignore
@@ -116,12 +180,14 @@ impl CrawlData {
 
             // Lobby
             "lobby_complete" => (),
+            "lobby_entry" => (),
+            "lobby_remove" => (),
             "login_success" => (),
             "game_started" => (),
-            "update_spectators" => self.spectators = message["count"].as_u64().unwrap(),
+            "update_spectators" => self.spectators = get_u64(message, "count")?,
 
             // Player
-            "player" => self.update_player(message),
+            "player" => self.update_player(message)?,
 
             // Dungeon
             "map" => self.update_map(message)?,
@@ -132,22 +198,63 @@ impl CrawlData {
             "close_menu" => self.remove_closed_menus(),
             "ui-push" => self.ui_type(message)?,
             "ui-pop" => self.remove_closed_menus(),
-            "txt" => self.process_skills(message),
+            "txt" => self.process_skills(message)?,
 
-            _ => {
-                unreachable!("NOT IMPLEMENTED: {:?}", message);
-            }
+            _ => self.unhandled(msg)?,
         };
         Ok(())
     }
 
+    /// Count a message that could not be handled, or return an error in strict mode.
+    fn unhandled(&mut self, msg: &str) -> Result<(), DataError> {
+        if self.strict {
+            return Err(DataError::UnknownMessage(msg.to_owned()));
+        }
+
+        *self.unhandled.entry(msg.to_owned()).or_default() += 1;
+
+        Ok(())
+    }
+
+    /// In strict mode, [`CrawlData::process_json`] returns a
+    /// [DataError::UnknownMessage] for a message that can't be handled, and a
+    /// [DataError::UnknownMapFeature] for a tile with an unknown map feature
+    /// (useful in tests). Otherwise (the default), the message is ignored and the
+    /// tile is made unwalkable, and both are counted (the map features as
+    /// `mf <number>`, see [`CrawlData::unhandled_messages`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `strict` - A bool on if strict mode is enabled.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Type of the messages that could not be handled, and how many of each
+    /// were received, sorted by type.
+    pub fn unhandled_messages(&self) -> Vec<(String, u64)> {
+        let mut unhandled = self
+            .unhandled
+            .iter()
+            .map(|(msg, count)| (msg.to_owned(), *count))
+            .collect::<Vec<(String, u64)>>();
+        unhandled.sort();
+
+        unhandled
+    }
+
+    /// Total number of messages that could not be handled.
+    pub fn unhandled_count(&self) -> u64 {
+        self.unhandled.values().sum()
+    }
+
     pub fn get_spectator_num(&self) -> u64 {
         self.spectators
     }
 
-    fn menus(&mut self, message: &Value) -> Result<(), Box<dyn Error>> {
+    fn menus(&mut self, message: &Value) -> Result<(), DataError> {
         if message["tag"] == "pickup" {
-            let table = structured_table(message["items"].clone());
+            let table = structured_table(&message["items"])?;
 
             if table.contains_key("Monsters") {
                 self.queue_select_monster();
@@ -162,22 +269,19 @@ impl CrawlData {
         self.remove_closed_menus();
 
         if message["tag"] == "ability" {
-            self.process_ability_menu(message["items"].clone());
+            self.process_ability_menu(message["items"].clone())?;
         }
 
         if message["tag"] == "inventory"
-            && message["title"]["text"]
-                .as_str()
-                .unwrap()
-                .contains("Recognised items.")
+            && get_str(&message["title"], "text")?.contains("Recognised items.")
         {
-            self.process_known_item_menu(message["items"].clone());
+            self.process_known_item_menu(message["items"].clone())?;
         }
 
         Ok(())
     }
 
-    fn update_menu(&mut self) -> Result<(), Box<dyn Error>> {
+    fn update_menu(&mut self) -> Result<(), DataError> {
         // Menu was opened or action performed (same thing for this bot)
         self.identify_menu_as_opened();
 
@@ -187,22 +291,32 @@ impl CrawlData {
         Ok(())
     }
 
-    fn ui_type(&mut self, message: &Value) -> Result<(), Box<dyn Error>> {
-        match message["type"].as_str().unwrap() {
+    fn ui_type(&mut self, message: &Value) -> Result<(), DataError> {
+        match get_str(message, "type")? {
             "seed-selection" => (),
             "newgame-choice" => (),
             "describe-item" => {
                 self.identify_menu_as_opened();
-                self.inventory_description(message.clone());
+                self.inventory_description(message.clone())?;
             }
             "describe-monster" => {
-                if self.monsters.examine_loc.is_none() {
-                    panic!("You must set up `ready_examine_monster` before examining the monster.");
+                self.identify_menu_as_opened();
+
+                // Monster examined without `ready_examine_monster` nor a title to
+                // match it with
+                let examine_loc = self.monsters.examine_loc.take();
+                let has_title = message["title"]
+                    .as_str()
+                    .is_some_and(|title| !title.trim().is_empty());
+                if examine_loc.is_none() && !has_title {
+                    if self.strict {
+                        return Err(DataError::UnexpectedMonsterDescription);
+                    }
+
+                    return self.unhandled("describe-monster");
                 }
 
-                self.identify_menu_as_opened();
-                self.monster_description(message.clone(), self.monsters.examine_loc.unwrap());
-                self.monsters.examine_loc = None;
+                self.monster_description(message.clone(), examine_loc)?;
             }
             _ => (),
         }
@@ -210,34 +324,55 @@ impl CrawlData {
         Ok(())
     }
 
-    fn process_log(&mut self, message: &Value) -> Result<(), Box<dyn Error>> {
+    fn process_log(&mut self, message: &Value) -> Result<(), DataError> {
         // If no messages
-        if !message.as_object().unwrap().contains_key("messages") {
+        if message.get("messages").is_none() {
             return Ok(());
         }
 
-        // Send messages to the VecDeque
-        for text_obj in message["messages"].as_array().unwrap() {
-            let text = text_obj["text"].as_str().unwrap();
+        // Send messages to the VecDeque, and the events parsed from them
+        for text_obj in get_array(message, "messages")? {
+            let text = get_str(text_obj, "text")?;
+            let channel = MessageChannel::from_id(text_obj["channel"].as_u64().unwrap_or(0));
+            // Messages of a previous turn (e.g. when reconnecting) have their turn
+            let turn = text_obj["turn"].as_u64().unwrap_or(self.time.turn);
 
-            if text.contains("You slide downwards.")
-                || text.contains("You climb downwards.")
-                || text.contains("You fall into a shaft")
-            {
-                self.new_floor();
+            let formatted = FormattedText::parse(text);
+
+            // Single item on the floor, under the character
+            if let Some(item) = formatted.plain().strip_prefix("You see here ") {
+                let pos = self.player_pos();
+                self.pickup.names.insert(pos, vec![ItemName::parse(item)]);
             }
 
-            self.log.log.push_back(text.to_owned())
+            let event = GameEvent::parse(formatted.clone(), channel, turn);
+            self.inventory.observe_event(&event);
+            self.log.events.push_back(event);
+            self.log.log.push_back(formatted)
         }
 
+        self.inventory.end_of_messages();
+
         Ok(())
     }
 
-    pub fn update_map(&mut self, message: &Value) -> Result<(), Box<dyn Error>> {
+    pub fn update_map(&mut self, message: &Value) -> Result<(), DataError> {
+        if message["clear"].as_bool() == Some(true) {
+            self.clear_level();
+        }
+
         let extra_data = self.tiles.update(&message["cells"])?;
 
+        for mf in extra_data.3 {
+            if self.strict {
+                return Err(DataError::UnknownMapFeature(mf));
+            }
+
+            *self.unhandled.entry(format!("mf {}", mf)).or_default() += 1;
+        }
+
         for mon in extra_data.0 {
-            self.monsters.update(mon.0, mon.1);
+            self.monsters.update(mon.0, mon.1)?;
         }
 
         for ground_item_coord in extra_data.1 {
@@ -252,52 +387,93 @@ impl CrawlData {
             self.monsters.invisible_removed(invis_rm_coord)
         }
 
+        self.monsters.apply_known(&self.examined);
+
+        self.dungeon.entered = false;
+
         Ok(())
     }
 
-    pub fn update_player(&mut self, message: &Value) {
-        let message_obj = message.as_object().unwrap();
+    pub fn update_player(&mut self, message: &Value) -> Result<(), DataError> {
+        // Before the position, which is relative to the level
+        self.update_level(message);
+
+        // Hit chances depend on the character when the monster was examined
+        if ["equip", "status", "xl", "str", "dex"]
+            .iter()
+            .any(|field| message.get(field).is_some())
+        {
+            self.monsters.expire_hit_chances();
+        }
 
-        if message_obj.contains_key("pos") {
-            self.player
-                .update_pos(message["pos"]["x"].clone(), message["pos"]["y"].clone());
+        if message.get("pos").is_some() {
+            self.player.update_pos(&message["pos"])?;
         };
 
-        if message_obj.contains_key("equip") {
-            self.player.update_equipped(message["equip"].clone())
+        if message.get("equip").is_some() {
+            self.player.update_equipped(&message["equip"])?;
         }
 
-        if message_obj.contains_key("status") {
-            self.player.update_status(message["status"].clone());
+        if message.get("status").is_some() {
+            self.player.update_status(&message["status"])?;
         }
 
-        if message_obj.contains_key("inv") {
-            self.inventory.update_inventory(message["inv"].clone());
+        if message.get("inv").is_some() {
+            self.inventory.update_inventory(message["inv"].clone())?;
         }
 
-        self.player.update_health(message);
+        self.player.update_health(message)?;
+
+        self.player.update_stats(message)?;
+
+        self.player.update_defense(message)?;
+
+        self.player.update_magic(message)?;
+
+        self.player.update_character(message)?;
+
+        self.player.update_religion(message)?;
+
+        self.player.update_misc(message)?;
 
-        self.player.update_stats(message);
+        self.time
+            .update(message["turn"].as_u64(), message["time"].as_u64());
 
-        self.player.update_defense(message);
+        Ok(())
     }
 
+    /// Text of the status lights of the character (see
+    /// [`CrawlData::player_statuses`] for the typed statuses).
     pub fn get_player_status(&self) -> Vec<String> {
-        self.player.status.clone()
+        self.player
+            .status
+            .iter()
+            .map(|effect| effect.text.clone())
+            .collect()
     }
 
-    pub fn index_of_equipped(&self, wear_type: &str) -> i32 {
-        match wear_type {
-            "Weapon" => self.player.equipped[WearSlots::Weapon as usize],
-            "Amulet" => self.player.equipped[WearSlots::Amulet as usize],
-            "Body" => self.player.equipped[WearSlots::Body as usize],
-            "Boots" => self.player.equipped[WearSlots::Boots as usize],
-            "Cloak" => self.player.equipped[WearSlots::Cloak as usize],
-            "Helmet" => self.player.equipped[WearSlots::Helmet as usize],
-            "Shield" => self.player.equipped[WearSlots::Shield as usize],
-            "Gloves" => self.player.equipped[WearSlots::Gloves as usize],
-            _ => unimplemented!("TODO"),
-        }
+    /// Inventory index of the item equipped in a slot (-1 if the slot is empty),
+    /// or [None] if the slot is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `wear_type` - A string slice of the slot (e.g. "Weapon", "RingLeft").
+    pub fn index_of_equipped(&self, wear_type: &str) -> Option<i32> {
+        let slot = match wear_type {
+            "Weapon" => WearSlots::Weapon,
+            "Amulet" => WearSlots::Amulet,
+            "Body" => WearSlots::Body,
+            "Boots" => WearSlots::Boots,
+            "Cloak" => WearSlots::Cloak,
+            "Helmet" => WearSlots::Helmet,
+            "Shield" => WearSlots::Shield,
+            "Gloves" => WearSlots::Gloves,
+            "RingLeft" => WearSlots::RingLeft,
+            "RingRight" => WearSlots::RingRight,
+            _ => return None,
+        };
+
+        Some(self.player.equipped[slot as usize])
     }
 
     pub fn item_type(&self, item_index: usize) -> String {
@@ -307,15 +483,26 @@ impl CrawlData {
             Item::Missile(_) => "Missile".to_owned(),
             Item::Armour(_) => "Armour".to_owned(),
             Item::Wand(_) => "Wand".to_owned(),
-            Item::_Unknown4 => unimplemented!(),
+            Item::Food => "Food".to_owned(),
             Item::Scroll(_) => "Scroll".to_owned(),
             Item::Jewellery(_) => "Jewellery".to_owned(),
             Item::Potion(_) => "Potion".to_owned(),
-            Item::_Unknown8 => unimplemented!(),
+            Item::Book(_) => "Book".to_owned(),
             Item::Staff(_) => "Staff".to_owned(),
+            Item::Orb => "Orb".to_owned(),
+            Item::Miscellany(_) => "Miscellany".to_owned(),
+            Item::Corpse => "Corpse".to_owned(),
+            Item::Gold => "Gold".to_owned(),
+            Item::Rune(_) => "Rune".to_owned(),
+            Item::Talisman(_) => "Talisman".to_owned(),
+            Item::Gem(_) => "Gem".to_owned(),
+            Item::Unknown(base_type) => format!("Unknown ({base_type})"),
         }
     }
 
+    /// Rating of a weapon, armour or jewellery, to choose which one to wear or
+    /// wield. Other items (e.g. talismans, evokers, books) are not rated, and
+    /// always have a rating of -100, as unknown items.
     pub fn item_rating(&self, item_index: usize) -> i32 {
         self.inventory.items[item_index].rating()
     }
@@ -381,20 +568,88 @@ impl CrawlData {
         }
     }
 
-    pub fn process_known_item_menu(&mut self, known_item_menu: Value) {
-        self.inventory.process_known_item_menu(known_item_menu);
+    /// Spells of a book (with their level), once described.
+    pub fn book_spells(&self, item_index: usize) -> Vec<(String, u64)> {
+        match self.inventory.items.get(item_index) {
+            Some(Item::Book(book)) => book.spells.clone(),
+            _ => vec![],
+        }
+    }
+
+    /// Skill trained by a manual, once described.
+    pub fn manual_skill(&self, item_index: usize) -> Option<String> {
+        match self.inventory.items.get(item_index) {
+            Some(Item::Book(book)) => book.manual_skill.clone(),
+            _ => None,
+        }
+    }
+
+    /// Charges left and maximum charges of an evoker (e.g. box of beasts), once
+    /// described.
+    pub fn evoker_charges(&self, item_index: usize) -> Option<(u32, u32)> {
+        match self.inventory.items.get(item_index) {
+            Some(Item::Miscellany(misc)) => misc.charges,
+            _ => None,
+        }
+    }
+
+    /// Form given by a talisman (e.g. "protean"), once described.
+    pub fn talisman_form(&self, item_index: usize) -> Option<String> {
+        match self.inventory.items.get(item_index) {
+            Some(Item::Talisman(talisman)) => talisman.form.clone(),
+            _ => None,
+        }
+    }
+
+    /// Type of a rune (e.g. "silver"), once described.
+    pub fn rune_type(&self, item_index: usize) -> Option<String> {
+        match self.inventory.items.get(item_index) {
+            Some(Item::Rune(rune)) => rune.rune_type.clone(),
+            _ => None,
+        }
     }
 
-    pub fn inventory_description(&mut self, description: Value) {
-        self.inventory.description(description);
+    /// Name of a gem, and if it is shattered, once described.
+    pub fn gem(&self, item_index: usize) -> Option<(String, bool)> {
+        match self.inventory.items.get(item_index) {
+            Some(Item::Gem(gem)) => Some((gem.name.clone()?, gem.shattered)),
+            _ => None,
+        }
     }
 
-    pub fn monster_description(&mut self, description: Value, pos: Coord) {
-        self.monsters.description(description, pos);
+    pub fn process_known_item_menu(&mut self, known_item_menu: Value) -> Result<(), DataError> {
+        self.inventory.process_known_item_menu(known_item_menu)
     }
 
-    pub fn process_ability_menu(&mut self, menu_items: Value) {
-        self.abilities.process_ability_menu(menu_items);
+    pub fn inventory_description(&mut self, description: Value) -> Result<(), DataError> {
+        self.inventory.description(description)
+    }
+
+    /// Process a "describe-monster" UI: the stats are given to the monsters of
+    /// the same type (by name), and remembered for the rest of the game (except
+    /// the hit chances, see [`MonsterDescription::intrinsic`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `description` - A [serde_json::Value] with the "describe-monster" message.
+    /// * `pos` - Position of the monster examined, if known (needed if the
+    ///   description has no title).
+    pub fn monster_description(
+        &mut self,
+        description: Value,
+        pos: Option<Coord>,
+    ) -> Result<(), DataError> {
+        let description = MonsterDescription::parse(&description)?;
+
+        if let Some(name) = self.monsters.description(&description, pos) {
+            self.examined.insert(name, description.intrinsic());
+        }
+
+        Ok(())
+    }
+
+    pub fn process_ability_menu(&mut self, menu_items: Value) -> Result<(), DataError> {
+        self.abilities.process_ability_menu(menu_items)
     }
 
     pub fn has_ability(&self, ability: &str) -> bool {
@@ -402,7 +657,10 @@ impl CrawlData {
     }
 
     pub fn has_status(&self, status: &str) -> bool {
-        self.player.status.contains(&String::from(status))
+        self.player
+            .status
+            .iter()
+            .any(|effect| effect.text == status)
     }
 
     pub fn key_of_ability(&self, ability: &str) -> String {
@@ -454,20 +712,50 @@ impl CrawlData {
         self.fov
     }
 
-    pub fn tile_explored(&mut self, x_pos: usize, y_pos: usize) -> bool {
-        self.tiles.tiles[x_pos][y_pos].explored
+    pub fn tile_explored(&mut self, x_pos: i32, y_pos: i32) -> bool {
+        self.tiles.get((x_pos, y_pos)).explored
+    }
+
+    pub fn tile_walkable(&mut self, x_pos: i32, y_pos: i32) -> bool {
+        let tile = self.tiles.get((x_pos, y_pos));
+        tile.walkable && !tile.blocked
+    }
+
+    pub fn tile_walkable_ignore_blocked(&mut self, x_pos: i32, y_pos: i32) -> bool {
+        self.tiles.get((x_pos, y_pos)).walkable
+    }
+
+    pub fn tile_mf(&mut self, x_pos: i32, y_pos: i32) -> usize {
+        self.tiles.get((x_pos, y_pos)).mf
+    }
+
+    /// Coordinates of every tile received for the current level, in no particular order.
+    pub fn known_tiles(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.tiles.iter().map(|(coord, _)| coord)
     }
 
-    pub fn tile_walkable(&mut self, x_pos: usize, y_pos: usize) -> bool {
-        self.tiles.tiles[x_pos][y_pos].walkable && !self.tiles.tiles[x_pos][y_pos].blocked
+    /// Coordinates of the explored tiles of the current level, in no particular order.
+    pub fn explored_tiles(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.tiles
+            .iter()
+            .filter(|(_, tile)| tile.explored)
+            .map(|(coord, _)| coord)
     }
 
-    pub fn tile_walkable_ignore_blocked(&mut self, x_pos: usize, y_pos: usize) -> bool {
-        self.tiles.tiles[x_pos][y_pos].walkable
+    /// Smallest and largest (x, y) of the tiles received for the current level,
+    /// or [None] if no tile was received.
+    pub fn map_bounds(&self) -> Option<(Coord, Coord)> {
+        self.tiles.bounds()
     }
 
-    pub fn tile_mf(&mut self, x_pos: usize, y_pos: usize) -> usize {
-        self.tiles.tiles[x_pos][y_pos].mf
+    /// Items seen on the floor at the position (e.g. from "You see here ..."
+    /// messages), on the current level.
+    pub fn floor_items(&self, coord: Coord) -> &[ItemName] {
+        self.pickup
+            .names
+            .get(&coord)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
     }
 
     pub fn unknown_item_curr_loc(&mut self) -> bool {
@@ -487,7 +775,8 @@ impl CrawlData {
 
     pub fn nearest_item_path(&mut self) -> Option<Vec<Coord>> {
         let pos = self.player_pos();
-        let nearest = self.pickup.nearest(&self.tiles.tiles, pos, self.fov);
+        let profile = self.movement_profile();
+        let nearest = self.pickup.nearest(&self.tiles, pos, self.fov, &profile);
 
         if !nearest.is_empty() {
             return Some(nearest);
@@ -496,11 +785,15 @@ impl CrawlData {
         None
     }
 
+    /// Position of the next monster to examine, see [`CrawlData::examine_queue`].
     pub fn examine_monsters(&mut self) -> Option<Coord> {
-        let pos = self.player_pos();
-        self.monsters.pos_unexamined_monster(pos, self.fov)
+        self.examine_queue().first().map(|request| request.pos)
     }
 
+    /// Forget the map, monsters and items of the current level.
+    #[deprecated(
+        note = "levels are switched from the \"player\" message, see `CrawlData::current_level`"
+    )]
     pub fn new_floor(&mut self) {
         self.tiles = Tiles::init();
         self.monsters = Monsters::init();
@@ -511,47 +804,228 @@ impl CrawlData {
     ///
     /// # Arguments
     ///
-    /// * `player_pos` - A [Coord] containing the player's position within
-    ///   the [Tiles].tiles vector.
-    pub fn path_to_unexplored(&mut self) -> Vec<Coord> {
-        pathfinding(
-            &self.tiles.tiles,
-            self.player.pos,
-            None,
-            None,
-            Some("unexplored"),
-            10_000,
-            false,
-        )
+    /// * `profile` - A [MovementProfile] with the cost of each terrain (see
+    ///   [`CrawlData::movement_profile`]).
+    pub fn path_to_unexplored(&mut self, profile: &MovementProfile) -> Vec<Coord> {
+        self.path_to(&Goal::Unexplored, profile)
     }
 
     /// Set path to an down stair in the [Tiles] object.
     ///
     /// # Arguments
     ///
-    /// * `player_pos` - A ([Coord]) containing the player's position within
-    ///   the [Tiles].tiles vector.
-    pub fn path_to_down_stairs(&mut self) -> Vec<Coord> {
-        pathfinding(
-            &self.tiles.tiles,
-            self.player.pos,
-            None,
-            Some(13),
-            None,
-            10_000,
-            false,
-        )
+    /// * `profile` - A [MovementProfile] with the cost of each terrain.
+    pub fn path_to_down_stairs(&mut self, profile: &MovementProfile) -> Vec<Coord> {
+        self.path_to(&Goal::Mf(13), profile)
     }
 
-    pub fn path_to_location(&mut self, cell_loc: Coord) -> Vec<Coord> {
-        pathfinding(
-            &self.tiles.tiles,
-            self.player.pos,
-            Some(cell_loc),
-            Some(13),
-            None,
-            10_000,
-            false,
-        )
+    /// Set path to a location in the [Tiles] object.
+    ///
+    /// # Arguments
+    ///
+    /// * `cell_loc` - A [Coord] of the location.
+    /// * `profile` - A [MovementProfile] with the cost of each terrain.
+    pub fn path_to_location(&mut self, cell_loc: Coord, profile: &MovementProfile) -> Vec<Coord> {
+        self.path_to(&Goal::Coord(cell_loc), profile)
+    }
+
+    /// Path from the character to the closest tile reaching the [Goal], or an
+    /// empty path if it can't be reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `goal` - A [Goal] (coordinates, map feature, unexplored or predicate).
+    /// * `profile` - A [MovementProfile] with the cost of each terrain.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Closest altar or branch stairs
+    /// let profile = data.movement_profile();
+    /// let path = data.path_to(&Goal::Predicate(&|_coord, mf| mf == 14 || mf == 15), &profile);
+    /// ```
+    pub fn path_to(&self, goal: &Goal, profile: &MovementProfile) -> Vec<Coord> {
+        pathfinding(&self.tiles, self.player.pos, goal, profile, 10_000, false)
+    }
+
+    /// Cost of the cheapest path (see [MovementProfile]) from every reachable
+    /// tile of the current level to the closest of the sources. Compute once and
+    /// reuse for several queries in the same turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `sources` - A slice of [Coord] at distance 0 (e.g. monsters).
+    /// * `profile` - A [MovementProfile] with the cost of each terrain (see
+    ///   [`CrawlData::movement_profile`]).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let profile = data.movement_profile();
+    /// let from_player = data.distance_map(&[data.player_pos()], &profile);
+    /// let cost = from_player.distance(item_coord);
+    /// ```
+    pub fn distance_map(&self, sources: &[Coord], profile: &MovementProfile) -> DistanceMap {
+        DistanceMap::new(&self.tiles, sources, profile, 10_000, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_unhandled_messages() {
+        let mut data = CrawlData::init(7, "0.32");
+
+        // Unknown messages are counted, by type
+        data.process_json(&json!({"msg": "watching_started"}))
+            .unwrap();
+        data.process_json(&json!({"msg": "watching_started"}))
+            .unwrap();
+        data.process_json(&json!({"msg": "ui-push", "type": "describe-monster", "body": ""}))
+            .unwrap();
+        assert_eq!(data.unhandled_count(), 3);
+        assert_eq!(
+            data.unhandled_messages(),
+            vec![
+                ("describe-monster".to_owned(), 1),
+                ("watching_started".to_owned(), 2)
+            ]
+        );
+
+        // Malformed messages return an error instead of panicking
+        assert!(matches!(
+            data.process_json(&json!({"msg": "player", "hp": "full"})),
+            Err(DataError::InvalidField { .. })
+        ));
+
+        // Unknown map features don't abort the rest of the map
+        data.process_json(&json!({"msg": "map", "cells": [
+            {"x": 0, "y": 0, "mf": 99}, {"mf": 1},
+        ]}))
+        .unwrap();
+        assert!(!data.tiles.get((0, 0)).walkable);
+        assert!(data.tiles.get((1, 0)).walkable);
+        assert!(data.unhandled_messages().contains(&("mf 99".to_owned(), 1)));
+
+        data.set_strict(true);
+        assert!(matches!(
+            data.process_json(&json!({"msg": "map", "cells": [{"x": 0, "y": 0, "mf": 99}]})),
+            Err(DataError::UnknownMapFeature(99))
+        ));
+        assert!(matches!(
+            data.process_json(&json!({"msg": "watching_started"})),
+            Err(DataError::UnknownMessage(msg)) if msg == "watching_started"
+        ));
+        assert!(matches!(
+            data.process_json(&json!({"msg": "ui-push", "type": "describe-monster", "body": ""})),
+            Err(DataError::UnexpectedMonsterDescription)
+        ));
+    }
+
+    #[test]
+    fn test_item_names() {
+        let mut data = CrawlData::init(7, "0.32");
+        data.process_json(&json!({"msg": "player", "pos": {"x": 0, "y": 0}, "inv": {
+            "0": {"base_type": 0, "name": "+0 hand axe (weapon)", "quantity": 1},
+            "1": {"base_type": 7, "name": "3 potions of curing", "quantity": 3},
+        }}))
+        .unwrap();
+
+        assert!(data.item_name(0).unwrap().equipped);
+        assert_eq!(data.item_name(1).unwrap().kind.as_deref(), Some("curing"));
+        assert!(data.item_is_identified(1));
+        assert!(data.item_name(2).is_none());
+
+        data.process_json(&json!({"msg": "msgs", "messages": [
+            {"text": "You see here a <white>+1 ring mail</white>.", "channel": 0}
+        ]}))
+        .unwrap();
+        assert_eq!(data.floor_items((0, 0))[0].base_type, "ring mail");
+        assert_eq!(data.floor_items((0, 0))[0].class, Some(ItemClass::Armour));
+    }
+
+    #[test]
+    fn test_item_classes() {
+        let mut data = CrawlData::init(7, "0.32");
+        data.process_json(&json!({"msg": "player", "inv": {
+            "0": {"base_type": 8, "name": "a book of Flames"},
+            "1": {"base_type": 11, "name": "a box of beasts (3/3)"},
+            "2": {"base_type": 16, "name": "a protean talisman"},
+            "3": {"base_type": 15, "name": "the silver rune of Zot"},
+            "4": {"base_type": 17, "name": "the Vaults gem"},
+            "5": {"base_type": 4, "name": "a bread ration"},
+            "6": {"base_type": 42, "name": "a strange thing"},
+        }}))
+        .unwrap();
+
+        let descriptions = [
+            json!({"title": "a - a book of Flames", "body": "", "spellset": [{"label": "",
+                "spells": [{"title": "Foxfire", "level": 1}, {"title": "Scorch", "level": 2}]}]}),
+            json!({"title": "b - a box of beasts (2/3)", "body": ""}),
+            json!({"title": "c - a protean talisman", "body": ""}),
+            json!({"title": "d - the silver rune of Zot", "body": ""}),
+            json!({"title": "e - the Vaults gem", "body": ""}),
+        ];
+        for description in descriptions {
+            data.inventory_description(description).unwrap();
+        }
+
+        assert_eq!(data.item_type(0), "Book");
+        assert_eq!(data.book_spells(0)[1], ("Scorch".to_owned(), 2));
+        assert_eq!(data.evoker_charges(1), Some((2, 3)));
+        assert_eq!(data.talisman_form(2).as_deref(), Some("protean"));
+        assert_eq!(data.rune_type(3).as_deref(), Some("silver"));
+        assert_eq!(data.gem(4), Some(("Vaults gem".to_owned(), false)));
+
+        // Out of the inventory
+        assert!(data.book_spells(99).is_empty());
+        assert_eq!(data.gem(99), None);
+        assert_eq!(data.item_type(5), "Food");
+        assert_eq!(data.item_type(6), "Unknown (42)");
+        assert!((0..7).all(|index| data.item_data_collected(index)));
+        assert_eq!(data.item_rating(2), -100);
+    }
+
+    #[test]
+    fn test_malformed_item_descriptions() {
+        let mut data = CrawlData::init(7, "0.32");
+        data.process_json(&json!({"msg": "player", "inv": {
+            "0": {"base_type": 6, "name": "a ring of the Mounted Mantis {Slay+7 rF-}"},
+            "1": {"base_type": 7, "name": "a murky potion"},
+        }}))
+        .unwrap();
+
+        // Unknown properties leave the jewellery unidentified
+        data.inventory_description(json!({"title": "a - a ring of the Mounted Mantis",
+            "body": "{Slay+7 rF-}"}))
+            .unwrap();
+        assert_eq!(data.item_rating(0), -100);
+
+        // Errors are returned instead of panicking
+        assert!(matches!(
+            data.inventory_description(json!({"title": "b - a murky potion"})),
+            Err(DataError::InvalidField { .. })
+        ));
+        assert!(matches!(
+            data.inventory_description(json!({"title": "b - a murky potion",
+                "body": "It was identified when you drank it."})),
+            Err(DataError::Parse { .. })
+        ));
+
+        // Types unknown to this version of the crate stay unknown
+        data.process_json(&json!({"msg": "player", "inv": {
+            "2": {"base_type": 7, "name": "2 potions of moonshine"},
+            "3": {"base_type": 5, "name": "a scroll of spoiler warnings"},
+        }}))
+        .unwrap();
+        assert!(data.item_is_identified(2));
+        assert_eq!(data.potion_index("moonshine"), None);
+
+        assert_eq!(data.index_of_equipped("Weapon"), Some(-1));
+        assert_eq!(data.index_of_equipped("Tail"), None);
     }
 }
@@ -1,26 +1,52 @@
 use std::collections::VecDeque;
 
+use crate::events::GameEvent;
 use crate::CrawlData;
+use dcss_api::FormattedText;
 
 #[derive(Debug)]
 pub(crate) struct Log {
-    pub(crate) log: VecDeque<String>,
+    pub(crate) log: VecDeque<FormattedText>,
+    /// Events parsed from the messages, not yet processed.
+    pub(crate) events: VecDeque<GameEvent>,
 }
 
 impl Log {
     pub(crate) fn init() -> Self {
         Self {
             log: VecDeque::new(),
+            events: VecDeque::new(),
         }
     }
 }
 
 impl CrawlData {
-    pub fn get_log_message(&mut self) -> Option<String> {
+    /// Oldest message of the log not yet processed, with its colours (use
+    /// [`FormattedText::plain`] for the text only).
+    pub fn get_log_message(&mut self) -> Option<FormattedText> {
         self.log.log.pop_front()
     }
 
     pub fn logs_to_process(&mut self) -> bool {
         !self.log.log.is_empty()
     }
+
+    /// Oldest [GameEvent] not yet processed, parsed from the messages of the log.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// while let Some(event) = data.get_game_event() {
+    ///     if let EventKind::LevelUp { xl } = event.kind {
+    ///         // Spend the new skill points
+    ///     }
+    /// }
+    /// ```
+    pub fn get_game_event(&mut self) -> Option<GameEvent> {
+        self.log.events.pop_front()
+    }
+
+    pub fn events_to_process(&self) -> bool {
+        !self.log.events.is_empty()
+    }
 }
@@ -1,9 +1,16 @@
+pub(crate) mod description;
+pub(crate) mod view;
+
 use std::cmp;
 
-use crate::common::{pathfinding, CoordVec};
-use crate::tiles::Tile;
+use crate::common::{get_array, get_i64, get_str, get_u64, invalid_field, CoordVec};
+use crate::data_errors::DataError;
+use crate::monsters::description::MonsterDescription;
+use crate::monsters::view::MonsterView;
+use crate::movement::MovementProfile;
+use crate::pathfinding::{pathfinding, Goal};
+use crate::tiles::Tiles;
 use crate::CrawlData;
-use regex::Regex;
 use rustc_hash::FxHashMap;
 use serde_json::Value;
 
@@ -15,29 +22,66 @@ pub(crate) struct Monsters {
     pub(crate) monsters: FxHashMap<u64, Monster>,
 }
 
-#[derive(Debug)]
+/// Attitude of a monster towards the character (the "att" field of the
+/// monster data).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attitude {
+    Hostile,
+    /// Neutral monsters attack anything near them, including the character.
+    Neutral,
+    /// Neutral monsters that won't attack the character (strict or good neutral,
+    /// e.g. from Zin or the Shining One).
+    Peaceful,
+    Friendly,
+}
+
+/// How damaged a monster looks (the "dam" field of the monster data).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DamageLevel {
+    Unharmed,
+    LightlyDamaged,
+    ModeratelyDamaged,
+    HeavilyDamaged,
+    SeverelyDamaged,
+    AlmostDead,
+}
+
+/// Status of a monster sent with the monster data (the "status" field).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonsterFlag {
+    Asleep,
+    Dormant,
+    Wandering,
+    Unaware,
+    Distracted,
+    Fleeing,
+    Summoned,
+    Other(String),
+}
+
+/// Monster to examine (with the describe UI, e.g. `x` then `v` at its position)
+/// to learn the stats of its type, see [`CrawlData::examine_queue`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExamineRequest {
+    pub pos: Coord,
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
 pub(crate) struct Monster {
     pub(crate) name: String,
+    pub(crate) plural: Option<String>,
+    /// Monster type (e.g. the type of "Sigmund"), and base type (e.g. the type of
+    /// "human" for Sigmund, or of "draconian" for a red draconian).
+    pub(crate) type_id: Option<u64>,
+    pub(crate) base_type: Option<u64>,
     pub(crate) threat: i32,
+    pub(crate) attitude: Attitude,
+    pub(crate) damage: DamageLevel,
+    pub(crate) flags: Vec<MonsterFlag>,
     pub(crate) pos: Option<Coord>,
-    pub(crate) examined: bool,
-    pub(crate) max_hp: Option<i32>,
-    pub(crate) will: Option<i32>,
-    pub(crate) ac: Option<i32>,
-    pub(crate) ev: Option<i32>,
-    pub(crate) fire: Option<i32>,
-    pub(crate) cold: Option<i32>,
-    pub(crate) poison: Option<i32>,
-    pub(crate) negative: Option<i32>,
-    pub(crate) electric: Option<i32>,
-    pub(crate) class: Option<i32>,
-    pub(crate) size: Option<i32>,
-    pub(crate) int: Option<i32>,
-    pub(crate) speed: Option<i32>,
-    pub(crate) regen: Option<i32>,
-    pub(crate) player_hit_monster_chance: Option<i32>,
-    pub(crate) monster_hit_player_chance: Option<i32>,
-    pub(crate) max_damage: Option<i32>,
+    /// Stats from the description, once the monster is examined.
+    pub(crate) description: Option<MonsterDescription>,
 }
 
 impl Monsters {
@@ -48,7 +92,7 @@ impl Monsters {
         }
     }
 
-    pub(crate) fn update(&mut self, mon_pos: Coord, monster: Value) {
+    pub(crate) fn update(&mut self, mon_pos: Coord, monster: Value) -> Result<(), DataError> {
         // If monster is "None", and monster still at that position in memory,
         // remove it from that location
         if monster.is_null() {
@@ -57,21 +101,23 @@ impl Monsters {
                     continue;
                 }
 
-                if mon.pos.unwrap() == mon_pos {
+                if mon.pos == Some(mon_pos) {
                     mon.update_pos(None);
                 }
             }
 
-            return;
+            return Ok(());
         }
 
-        let monster_obj = monster.as_object().unwrap();
+        let monster_obj = monster
+            .as_object()
+            .ok_or_else(|| invalid_field("mon", "an object"))?;
 
         if !monster_obj.contains_key("id") {
-            return;
+            return Ok(());
         }
 
-        let monster_id = monster["id"].as_u64().unwrap();
+        let monster_id = get_u64(&monster, "id")?;
 
         if self.monsters.contains_key(&monster_id) {
             // If the monster moved on top of another monster,
@@ -82,34 +128,24 @@ impl Monsters {
                     continue;
                 }
 
-                if mon_pos == mon.pos.unwrap() {
+                if mon.pos == Some(mon_pos) {
                     mon.update_pos(None)
                 }
             }
 
-            // Update the current monster's position to the new one
-            self.monsters
-                .get_mut(&monster_id)
-                .unwrap()
-                .update_pos(Some(mon_pos));
+            // Update the current monster's position to the new one, and the
+            // fields that changed
+            if let Some(mon) = self.monsters.get_mut(&monster_id) {
+                mon.update_pos(Some(mon_pos));
+                mon.update(&monster)?;
+            }
         } else if monster_obj.contains_key("name") {
             // If monster has name tag, likely a new monster - create new monster
-            let name = monster["name"].as_str().unwrap().to_string();
-            let mut threat = 0;
-            if monster_obj.contains_key("threat") {
-                threat = monster["threat"].as_i64().unwrap() as i32;
-            }
+            let name = get_str(&monster, "name")?.to_string();
+            let mut mon = Monster::new(name, 0, Some(mon_pos));
+            mon.update(&monster)?;
 
-            // Plant or friendly
-            if monster_obj.contains_key("typedata") {
-                let typedata_obj = monster_obj["typedata"].as_object().unwrap();
-                if typedata_obj.contains_key("no_exp") && typedata_obj["no_exp"].as_bool().unwrap()
-                {
-                    threat = -1;
-                }
-            }
-            self.monsters
-                .insert(monster_id, Monster::new(name, threat, Some(mon_pos)));
+            self.monsters.insert(monster_id, mon);
         } else {
             // Sometimes the monster ID updates (if they disappear and come back,
             // probably to not reveal if it's the same monster or a new one)
@@ -133,258 +169,124 @@ impl Monsters {
 
                 // If mon found in same position, copy it to new position
                 // and give old monster a None position
-                if mon.pos.unwrap() == mon_pos {
+                if mon.pos == Some(mon_pos) {
                     found = true;
-                    new_mon = Some((mon.name.clone(), mon.threat, mon.pos));
+                    new_mon = Some(mon.clone());
                     mon.update_pos(None);
                     break;
                 }
             }
 
-            // If a new monster is needed
-            if let Some(mon) = new_mon {
-                self.monsters
-                    .insert(monster_id, Monster::new(mon.0, mon.1, mon.2));
+            if !found {
+                // Assume that it's just the same as the last one (unsafe?)
+                let last_mon = self
+                    .monsters
+                    .keys()
+                    .max()
+                    .map(|last_mon_id| &self.monsters[last_mon_id])
+                    .ok_or(DataError::UnknownMonster)?;
+
+                new_mon = Some(last_mon.clone());
             }
 
-            if !self.monsters.is_empty() {
-                if !found {
-                    // Assume that it's just the same as the last one (unsafe?)
-                    let last_mon_id = self.monsters.keys().max().unwrap();
-
-                    self.monsters.insert(
-                        monster_id,
-                        Monster::new(
-                            self.monsters[last_mon_id].name.clone(),
-                            self.monsters[last_mon_id].threat,
-                            self.monsters[last_mon_id].pos,
-                        ),
-                    );
-                }
-            } else {
-                unreachable!(
-                    "No monster in list, no ID found for monster data, should never happen."
-                )
+            if let Some(mut mon) = new_mon {
+                mon.update(&monster)?;
+                self.monsters.insert(monster_id, mon);
             }
         }
-    }
 
-    pub(crate) fn description(&mut self, description: Value, pos: Coord) {
-        let mut desc_body = description["body"].as_str().unwrap().to_owned();
-        desc_body.push_str("\n\n");
-
-        // Max HP
-        let re: Regex = Regex::new(r"(?:Max HP: ~|Max HP: )\s*([^<\n]*)").unwrap();
-        let cap = re.captures(&desc_body).unwrap();
-        let max_hp = cap[1].trim().parse::<i32>().unwrap();
-
-        // Will
-        let re: Regex = Regex::new(r"Will:\s*([^<\n]*)").unwrap();
-        let cap = &re.captures(&desc_body).unwrap()[1];
-        let will = cap
-            .chars()
-            .filter(|c| *c == '+')
-            .count()
-            .try_into()
-            .unwrap();
-
-        // AC
-        let re: Regex = Regex::new(r"AC:\s*([^<\n]*)").unwrap();
-        let cap = &re.captures(&desc_body).unwrap()[1];
-        let ac = cap
-            .chars()
-            .filter(|c| *c == '+')
-            .count()
-            .try_into()
-            .unwrap();
-
-        // EV
-        let re: Regex = Regex::new(r"EV:\s*([^<\n]*)").unwrap();
-        let cap: &str = &re.captures(&desc_body).unwrap()[1];
-        let ev = cap
-            .chars()
-            .filter(|c| *c == '+')
-            .count()
-            .try_into()
-            .unwrap();
+        Ok(())
+    }
 
-        // Fire resistance
-        let re: Regex = Regex::new(r"rF:\s*([^<\n]*)").unwrap();
-        let fire = decode_resistance(re, &desc_body);
-
-        // Cold resistance
-        let re: Regex = Regex::new(r"rC:\s*([^<\n]*)").unwrap();
-        let cold = decode_resistance(re, &desc_body);
-
-        // Poison resistance
-        let re: Regex = Regex::new(r"rPois:\s*([^<\n]*)").unwrap();
-        let poison = decode_resistance(re, &desc_body);
-
-        // Negative resistance
-        let re: Regex = Regex::new(r"rNeg:\s*([^<\n]*)").unwrap();
-        let negative = decode_resistance(re, &desc_body);
-
-        // Electric resistance
-        let re: Regex = Regex::new(r"rElec:\s*([^<\n]*)").unwrap();
-        let electric = decode_resistance(re, &desc_body);
-
-        // Threat
-        let re: Regex = Regex::new(r"Threat:\s*([^<\n]*)").unwrap();
-        let cap: &str = &re.captures(&desc_body).unwrap()[1];
-        let threat = match cap.trim() {
-            "Minor" => 1,
-            "Low" => 2,
-            "High" => 4,
-            "Lethal" => 5,
-            _ => unimplemented!("Missing level"),
-        };
-
-        // Class
-        let re: Regex = Regex::new(r"Class:\s*([^<\n]*)").unwrap();
-        let cap: &str = &re.captures(&desc_body).unwrap()[1];
-        let class = match cap.trim() {
-            "Natural" => 1,
-            "Undead" => 2,
-            "Demonic" => 3,
-            "Nonliv." => 4,
-            "Plant" => 5,
-            _ => unimplemented!("Missing class"),
-        };
-
-        // Size
-        let re: Regex = Regex::new(r"Size:\s*([^<\n]*)").unwrap();
-        let cap: &str = &re.captures(&desc_body).unwrap()[1];
-        let size = match cap.trim() {
-            "Tiny" => 1,
-            "V. Small" => 2,
-            "Small" => 3,
-            "Medium" => 4,
-            "Large" => 5,
-            "Giant" => 6,
-            _ => unimplemented!("Missing size"),
-        };
-
-        // Intelligence
-        let re: Regex = Regex::new(r"Int:\s*([^<\n]*)").unwrap();
-        let cap: &str = &re.captures(&desc_body).unwrap()[1];
-        let int = match cap.trim() {
-            "Mindless" => 1,
-            "Animal" => 2,
-            "Human" => 3,
-            _ => unimplemented!("Missing intelligence"),
-        };
-
-        // Speed TODO: Swim speed
-        let re: Regex = Regex::new(r"Speed:\s*([^%]*)").unwrap();
-        let speed = if let Some(cap) = re.captures(&desc_body) {
-            cap[1].parse::<i32>().unwrap()
-        } else {
-            100
-        };
+    /// Give the stats of a description to the monster at `pos` (if any), and
+    /// the intrinsic stats (see [`MonsterDescription::intrinsic`]) to every
+    /// monster of the same type. Return the name of the type of monster described,
+    /// if known.
+    pub(crate) fn description(
+        &mut self,
+        description: &MonsterDescription,
+        pos: Option<Coord>,
+    ) -> Option<String> {
+        let name = description
+            .name
+            .as_deref()
+            .or_else(|| {
+                self.monsters
+                    .values()
+                    .find(|mon| pos.is_some() && mon.pos == pos)
+                    .map(|mon| mon.name.as_str())
+            })
+            .map(type_name);
 
-        // Regen
-        let re: Regex = Regex::new(r"Regen:\s*([^/]*)").unwrap();
-        let regen: i32 = if let Some(cap) = re.captures(&desc_body) {
-            cap[1].parse::<i32>().unwrap_or(0)
-        } else {
-            0
-        };
-
-        // Chance to hit monster
-        // TODO: Deal with multiple attack vectors)
-        // TODO: If info collected while sleeping, chance to his might be wrong
-        let re: Regex = Regex::new(r"You have about \s*([^%]*)").unwrap();
-        let player_hit_monster_chance: i32 = if let Some(cap) = re.captures(&desc_body) {
-            cap[1].parse::<i32>().unwrap_or(0)
-        } else {
-            0
-        };
+        let intrinsic = description.intrinsic();
 
-        // hance the monster hits the player
-        let re: Regex = Regex::new(r"(?:He|She|It|They) (?:has|have) about \s*([^%]*)").unwrap();
-        let monster_hit_player_chance: i32 = if let Some(cap) = re.captures(&desc_body) {
-            cap[1].parse::<i32>().unwrap_or(0)
-        } else {
-            0
-        };
-
-        // Attack table
-        let re: Regex = Regex::new(r"(?:Attacks|Attack) \s*([^\n]*)").unwrap();
-        let max_damage = if let Some(found) = re.find(&desc_body) {
-            let start_pos = found.end() + 1;
-            let re: Regex = Regex::new(r"\n\n").unwrap();
-            let end_pos = re.find(&desc_body[start_pos..]).unwrap().start();
-
-            // Capture the 2x (for example) and the max damage
-            let re_num_x = Regex::new(r"(\d+)x").unwrap();
-            let re_max_damage = Regex::new(r"\s(\d+)(\s|$)").unwrap();
-            desc_body[start_pos..][..end_pos]
-                .split('\n')
-                .map(|a| {
-                    // Multiply the #x by the max damage
-                    (if let Some(cap) = re_num_x.captures(a) {
-                        cap[1].parse::<i32>().unwrap()
-                    } else {
-                        1
-                    }) * (re_max_damage.captures(a).unwrap()[1]
-                        .parse::<i32>()
-                        .unwrap())
-                })
-                .max()
-                .unwrap()
-        } else {
-            0
-        };
+        for mon in self.monsters.values_mut() {
+            if pos.is_some() && mon.pos == pos {
+                mon.set_description(description);
+            } else if name.as_deref() == Some(&type_name(&mon.name)) {
+                mon.set_description(&intrinsic);
+            }
+        }
 
-        // TODO parse immunities and abilities (e.g. it is immune to acid, it can see invisible)
+        name
+    }
 
-        // TODO: Get spells
-        let _spellset = description["spellset"].as_array().unwrap();
+    /// Forget the hit chances of the monsters examined, when the character
+    /// changes (e.g. new equipment or status).
+    pub(crate) fn expire_hit_chances(&mut self) {
+        for description in self
+            .monsters
+            .values_mut()
+            .filter_map(|mon| mon.description.as_mut())
+        {
+            description.player_hit_chance = None;
+            description.monster_hit_chance = None;
+        }
+    }
 
+    /// Give the stats of the types of monsters already examined to the monsters
+    /// not examined yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `known` - Descriptions of the types of monsters examined, by name (see [type_name]).
+    pub(crate) fn apply_known(&mut self, known: &FxHashMap<String, MonsterDescription>) {
         for mon in self.monsters.values_mut() {
-            if mon.pos != Some(pos) {
+            if mon.description.is_some() {
                 continue;
             }
 
-            mon.examined = true;
-            mon.threat = threat;
-            mon.max_hp = Some(max_hp);
-            mon.will = Some(will);
-            mon.ac = Some(ac);
-            mon.ev = Some(ev);
-            mon.fire = Some(fire);
-            mon.cold = Some(cold);
-            mon.poison = Some(poison);
-            mon.negative = Some(negative);
-            mon.electric = Some(electric);
-            mon.class = Some(class.to_owned());
-            mon.size = Some(size);
-            mon.int = Some(int.to_owned());
-            mon.speed = Some(speed);
-            mon.regen = Some(regen);
-            mon.player_hit_monster_chance = Some(player_hit_monster_chance);
-            mon.monster_hit_player_chance = Some(monster_hit_player_chance);
-            mon.max_damage = Some(max_damage);
+            if let Some(description) = known.get(&type_name(&mon.name)) {
+                mon.set_description(description);
+            }
         }
     }
 
-    pub(crate) fn count_path(&mut self, tiles: &[Vec<Tile>], player_pos: Coord, fov: u32) -> u32 {
-        self.path_to_all_mons(tiles, player_pos, fov, true).len() as u32
+    pub(crate) fn count_path(
+        &mut self,
+        tiles: &Tiles,
+        player_pos: Coord,
+        fov: u32,
+        profile: &MovementProfile,
+    ) -> u32 {
+        self.path_to_all_mons(tiles, player_pos, fov, profile, true)
+            .len() as u32
     }
 
     fn path_to_all_mons(
         &self,
-        tiles: &[Vec<Tile>],
+        tiles: &Tiles,
         player_pos: Coord,
         fov: u32,
+        profile: &MovementProfile,
         ignore_blocked: bool, // Ignore blocking paths, better counts
     ) -> Vec<CoordVec> {
         let mut path_of_monsters = vec![];
 
         // How far is monster from char (max = fov)
         for mon in self.monsters.values() {
-            // Harmless or plants
-            if mon.threat < 0 {
+            // Harmless, plants or not hostile
+            if !mon.is_threat() {
                 continue;
             }
 
@@ -394,8 +296,8 @@ impl Monsters {
             }
 
             // Don't bother to do pathfinding if known more than fov
-            let abs_x = (player_pos.0 as i32 - mon.pos.unwrap().0 as i32).abs();
-            let abs_y = (player_pos.1 as i32 - mon.pos.unwrap().1 as i32).abs();
+            let abs_x = (player_pos.0 - mon.pos.unwrap().0).abs();
+            let abs_y = (player_pos.1 - mon.pos.unwrap().1).abs();
             if cmp::max(abs_x, abs_y) > fov as i32 {
                 continue;
             }
@@ -403,9 +305,8 @@ impl Monsters {
             let path = pathfinding(
                 tiles,
                 player_pos,
-                mon.pos,
-                None,
-                None,
+                &Goal::Coord(mon.pos.unwrap()),
+                profile,
                 fov as u64,
                 ignore_blocked,
             );
@@ -421,93 +322,67 @@ impl Monsters {
     /// Return monsters that are withing FOV, regardless of path (since some monster
     /// can block the path to other monsters)
     pub(crate) fn monsters_in_fov(&self, player_pos: Coord, fov: u32) -> Vec<&Monster> {
-        // TODO Deal with Plants (threat = -1)
-
         self.monsters
             .iter()
             .map(|mon| mon.1)
             .filter(|mon| mon.pos.is_some())
-            .filter(|mon| mon.threat >= 0)
+            .filter(|mon| mon.is_threat())
             .filter(|mon| {
                 cmp::max(
-                    (player_pos.0 as i32 - mon.pos.unwrap().0 as i32).abs(),
-                    (player_pos.1 as i32 - mon.pos.unwrap().1 as i32).abs(),
+                    (player_pos.0 - mon.pos.unwrap().0).abs(),
+                    (player_pos.1 - mon.pos.unwrap().1).abs(),
                 ) <= fov as i32
             })
             .collect::<Vec<&Monster>>()
     }
 
-    pub(crate) fn monster_in_battle(
-        &self,
-        player_pos: Coord,
-        fov: u32,
-    ) -> Vec<FxHashMap<&str, i32>> {
-        let monsters = self.monsters_in_fov(player_pos, fov);
+    /// One monster to examine for each type of hostile monster within the FOV
+    /// not examined yet (the nearest one), nearest first.
+    pub(crate) fn examine_queue(&self, player_pos: Coord, fov: u32) -> Vec<ExamineRequest> {
+        let mut queue: Vec<(i32, ExamineRequest)> = vec![];
 
-        monsters
-            .iter()
-            .filter(|mon| mon.name != "invisible")
-            .map(|mon| {
-                let mut hash = FxHashMap::default();
-
-                hash.insert("threat", mon.threat);
-                hash.insert("max_hp", mon.max_hp.unwrap());
-                hash.insert("will", mon.will.unwrap());
-                hash.insert("ac", mon.ac.unwrap());
-                hash.insert("ev", mon.ev.unwrap());
-                hash.insert("fire", mon.fire.unwrap());
-                hash.insert("cold", mon.cold.unwrap());
-                hash.insert("poison", mon.poison.unwrap());
-                hash.insert("negative", mon.negative.unwrap());
-                hash.insert("electric", mon.electric.unwrap());
-                hash.insert("class", mon.class.unwrap());
-                hash.insert("size", mon.size.unwrap());
-                hash.insert("int", mon.int.unwrap());
-                hash.insert("speed", mon.speed.unwrap());
-                hash.insert("regen", mon.regen.unwrap());
-                hash.insert(
-                    "player_hit_monster_chance",
-                    mon.player_hit_monster_chance.unwrap(),
-                );
-                hash.insert(
-                    "monster_hit_player_chance",
-                    mon.monster_hit_player_chance.unwrap(),
-                );
-                hash.insert("max_damage", mon.max_damage.unwrap());
-
-                hash
-            })
-            .collect()
-    }
+        for mon in self.monsters.values() {
+            let Some(pos) = mon.pos else {
+                continue;
+            };
 
-    pub(crate) fn pos_unexamined_monster(&self, player_pos: Coord, fov: u32) -> Option<Coord> {
-        let mons = self
-            .monsters
-            .iter()
-            .map(|mon| mon.1)
-            .filter(|mon| mon.pos.is_some())
-            .filter(|mon| mon.threat >= 0)
-            .filter(|mon| !mon.examined)
-            .filter(|mon| mon.name != "invisible")
-            .filter(|mon| {
-                cmp::max(
-                    (player_pos.0 as i32 - mon.pos.unwrap().0 as i32).abs(),
-                    (player_pos.1 as i32 - mon.pos.unwrap().1 as i32).abs(),
-                ) <= fov as i32
-            })
-            .collect::<Vec<&Monster>>();
+            if !mon.is_threat() || mon.description.is_some() || mon.name == "invisible" {
+                continue;
+            }
+
+            let distance = cmp::max((player_pos.0 - pos.0).abs(), (player_pos.1 - pos.1).abs());
+            if distance > fov as i32 {
+                continue;
+            }
 
-        if !mons.is_empty() {
-            return mons[0].pos;
+            let request = ExamineRequest {
+                pos,
+                name: mon.name.clone(),
+            };
+            match queue
+                .iter_mut()
+                .find(|(_, queued)| type_name(&queued.name) == type_name(&mon.name))
+            {
+                Some(queued) if queued.0 > distance => *queued = (distance, request),
+                Some(_) => (),
+                None => queue.push((distance, request)),
+            }
         }
 
-        None
+        queue.sort_by_key(|(distance, request)| (*distance, request.pos));
+        queue.into_iter().map(|(_, request)| request).collect()
     }
 
-    pub(crate) fn nearest(&mut self, tiles: &[Vec<Tile>], player_pos: Coord, fov: u32) -> CoordVec {
+    pub(crate) fn nearest(
+        &mut self,
+        tiles: &Tiles,
+        player_pos: Coord,
+        fov: u32,
+        profile: &MovementProfile,
+    ) -> CoordVec {
         let mut shortest_path = vec![];
 
-        for path in self.path_to_all_mons(tiles, player_pos, fov, false) {
+        for path in self.path_to_all_mons(tiles, player_pos, fov, profile, false) {
             if path.len() < shortest_path.len() || shortest_path.is_empty() {
                 shortest_path.clone_from(&path);
             }
@@ -535,42 +410,160 @@ impl Monster {
     fn new(name: String, threat: i32, pos: Option<Coord>) -> Self {
         Self {
             name,
+            plural: None,
+            type_id: None,
+            base_type: None,
             threat,
+            attitude: Attitude::Hostile,
+            damage: DamageLevel::Unharmed,
+            flags: vec![],
             pos,
-            examined: false,
-            max_hp: None,
-            will: None,
-            ac: None,
-            ev: None,
-            fire: None,
-            cold: None,
-            poison: None,
-            negative: None,
-            electric: None,
-            class: None,
-            size: None,
-            int: None,
-            speed: None,
-            regen: None,
-            player_hit_monster_chance: None,
-            monster_hit_player_chance: None,
-            max_damage: None,
+            description: None,
         }
     }
 
+    fn set_description(&mut self, description: &MonsterDescription) {
+        self.description = Some(description.clone());
+    }
+
     fn update_pos(&mut self, pos: Option<Coord>) {
         self.pos = pos;
     }
+
+    /// Update the monster with the fields of the monster data (from a "map"
+    /// cell). Only the fields that changed are sent by the game, the others
+    /// are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `monster` - A [serde_json::Value] with the "mon" object of a cell.
+    fn update(&mut self, monster: &Value) -> Result<(), DataError> {
+        if monster.get("name").is_some() {
+            self.name = get_str(monster, "name")?.to_owned();
+        }
+
+        if monster.get("plural").is_some() {
+            self.plural = Some(get_str(monster, "plural")?.to_owned());
+        }
+
+        if monster.get("type").is_some() {
+            self.type_id = Some(get_u64(monster, "type")?);
+        }
+
+        if monster.get("btype").is_some() {
+            self.base_type = Some(get_u64(monster, "btype")?);
+        }
+
+        // Threat from the map (0 to 3), the one of the description (1 to 5) is
+        // kept in the description
+        if monster.get("threat").is_some() {
+            self.threat = get_i64(monster, "threat")? as i32;
+        }
+
+        // Plant or friendly
+        if monster["typedata"]["no_exp"].as_bool() == Some(true) {
+            self.threat = -1;
+        }
+
+        if monster.get("att").is_some() {
+            self.attitude = match get_u64(monster, "att")? {
+                1 => Attitude::Neutral,
+                2 | 3 => Attitude::Peaceful,
+                4 => Attitude::Friendly,
+                _ => Attitude::Hostile,
+            };
+        }
+
+        if monster.get("dam").is_some() {
+            self.damage = match get_u64(monster, "dam")? {
+                0 => DamageLevel::Unharmed,
+                1 => DamageLevel::LightlyDamaged,
+                2 => DamageLevel::ModeratelyDamaged,
+                3 => DamageLevel::HeavilyDamaged,
+                4 => DamageLevel::SeverelyDamaged,
+                _ => DamageLevel::AlmostDead,
+            };
+        }
+
+        // Full list sent every time it changes
+        if monster.get("status").is_some() {
+            self.flags = get_array(monster, "status")?
+                .iter()
+                .filter_map(|flag| flag.as_str())
+                .map(|flag| match flag {
+                    "asleep" | "sleeping" => MonsterFlag::Asleep,
+                    "dormant" => MonsterFlag::Dormant,
+                    "wandering" => MonsterFlag::Wandering,
+                    "unaware" => MonsterFlag::Unaware,
+                    "distracted" => MonsterFlag::Distracted,
+                    "fleeing" => MonsterFlag::Fleeing,
+                    "summoned" => MonsterFlag::Summoned,
+                    other => MonsterFlag::Other(other.to_owned()),
+                })
+                .collect();
+        }
+
+        Ok(())
+    }
+
+    /// If the monster should be fought: hostile (or neutral, which attacks the
+    /// character too), and not harmless (e.g. plants).
+    pub(crate) fn is_threat(&self) -> bool {
+        self.threat >= 0 && matches!(self.attitude, Attitude::Hostile | Attitude::Neutral)
+    }
+}
+
+/// Name identifying a type of monster, as a key of the examined monsters: the
+/// name from the map or the title of the description, in lowercase and without
+/// article (e.g. "the Royal Jelly" becomes "royal jelly").
+pub(crate) fn type_name(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+
+    ["a ", "an ", "the "]
+        .iter()
+        .find_map(|article| name.strip_prefix(article))
+        .map(str::to_owned)
+        .unwrap_or(name)
 }
 
 impl CrawlData {
+    /// Position of the monster about to be examined. Optional: descriptions
+    /// are matched to the monsters by name, but the position is used if the
+    /// description has no title.
     pub fn ready_examine_monster(&mut self, coord: Coord) {
         self.monsters.examine_loc = Some(coord);
     }
 
+    /// Monsters to examine, one for each type of hostile monster in view that
+    /// was not examined during the game (nearest first). Stats are remembered
+    /// for the whole game, so each type only needs to be examined once.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if let Some(request) = data.examine_queue().first() {
+    ///     // Open the describe UI at request.pos
+    /// }
+    /// ```
+    pub fn examine_queue(&self) -> Vec<ExamineRequest> {
+        let pos = self.player_pos();
+        self.monsters.examine_queue(pos, self.fov)
+    }
+
+    /// Stats of a type of monster, if examined during the game.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - A string slice of the name of the monster (e.g. "goblin").
+    pub fn examined_stats(&self, name: &str) -> Option<&MonsterDescription> {
+        self.examined.get(&type_name(name))
+    }
+
     pub fn monster_count_path(&mut self) -> u32 {
         let pos: Coord = self.player_pos();
-        self.monsters.count_path(&self.tiles.tiles, pos, self.fov)
+        let profile = self.movement_profile();
+        self.monsters
+            .count_path(&self.tiles, pos, self.fov, &profile)
     }
 
     pub fn monster_count_fov(&mut self) -> u32 {
@@ -585,20 +578,19 @@ impl CrawlData {
 
     pub fn nearest_monster_path(&mut self) -> Vec<Coord> {
         let pos = self.player_pos();
-        self.monsters.nearest(&self.tiles.tiles, pos, self.fov)
+        let profile = self.movement_profile();
+        self.monsters.nearest(&self.tiles, pos, self.fov, &profile)
     }
 
-    pub fn get_battle_monster_info(&self) -> Vec<FxHashMap<&str, i32>> {
-        let pos = self.player_pos();
-        let fov = self.fov;
-
-        self.monsters.monster_in_battle(pos, fov)
+    /// Hostile monsters within the field of view, see [`CrawlData::query_monsters`]
+    /// for other selections.
+    pub fn get_battle_monster_info(&self) -> Vec<MonsterView> {
+        self.query_monsters().hostile().collect()
     }
 
-    pub fn get_attacking_monster_info(&self) -> Vec<FxHashMap<&str, i32>> {
-        let pos = self.player_pos();
-
-        self.monsters.monster_in_battle(pos, 1)
+    /// Hostile monsters next to the character.
+    pub fn get_attacking_monster_info(&self) -> Vec<MonsterView> {
+        self.query_monsters().hostile().within(1).collect()
     }
 
     pub fn get_monster_threat_vec(&mut self) -> Vec<i32> {
@@ -611,15 +603,133 @@ impl CrawlData {
     }
 }
 
-fn decode_resistance(re: Regex, text: &str) -> i32 {
-    if let Some(cap) = &re.captures(text) {
-        if cap[1].starts_with('∞') {
-            4
-        } else {
-            cap[1].chars().filter(|c| *c == '+').count() as i32
-                - cap[1].chars().filter(|c| *c == 'x').count() as i32
-        }
-    } else {
-        0
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::monsters::description::Threat;
+    use serde_json::json;
+
+    #[test]
+    fn test_monster_update() {
+        let mut monsters = Monsters::init();
+        monsters
+            .update(
+                (3, 4),
+                json!({"id": 7, "name": "orc wizard", "plural": "orc wizards", "type": 57,
+                    "btype": 57, "threat": 2, "att": 0, "status": ["asleep"]}),
+            )
+            .unwrap();
+
+        let mon = &monsters.monsters[&7];
+        assert_eq!(mon.plural.as_deref(), Some("orc wizards"));
+        assert_eq!(mon.attitude, Attitude::Hostile);
+        assert_eq!(mon.flags, vec![MonsterFlag::Asleep]);
+
+        // Partial update, only the changed fields are sent
+        monsters
+            .update((4, 4), json!({"id": 7, "dam": 2, "status": []}))
+            .unwrap();
+
+        let mon = &monsters.monsters[&7];
+        assert_eq!(mon.pos, Some((4, 4)));
+        assert_eq!(mon.name, "orc wizard");
+        assert_eq!(mon.threat, 2);
+        assert_eq!(mon.damage, DamageLevel::ModeratelyDamaged);
+        assert!(mon.flags.is_empty());
+
+        // Allies are not a threat
+        monsters
+            .update((5, 4), json!({"id": 8, "name": "hound", "att": 4}))
+            .unwrap();
+        assert!(!monsters.monsters[&8].is_threat());
+
+        // Neutral monsters attack the character, unlike strict and good neutrals
+        monsters
+            .update((6, 4), json!({"id": 9, "name": "jackal", "att": 1}))
+            .unwrap();
+        assert!(monsters.monsters[&9].is_threat());
+        monsters.update((6, 4), json!({"id": 9, "att": 3})).unwrap();
+        assert!(!monsters.monsters[&9].is_threat());
+        assert_eq!(monsters.monsters_in_fov((4, 4), 7).len(), 1);
+    }
+
+    #[test]
+    fn test_examine_queue() {
+        let mut data = CrawlData::init(7, "0.32");
+        data.update_player(&json!({"msg": "player", "pos": {"x": 0, "y": 0}}))
+            .unwrap();
+        data.update_map(&json!({"msg": "map", "cells": [
+            {"x": 1, "y": 0, "mf": 1, "mon": {"id": 1, "name": "goblin", "threat": 1}},
+            {"x": 3, "y": 0, "mf": 1, "mon": {"id": 2, "name": "goblin", "threat": 1}},
+            {"x": 2, "y": 2, "mf": 1, "mon": {"id": 3, "name": "orc wizard", "threat": 2}},
+        ]}))
+        .unwrap();
+
+        // One request by type of monster, nearest first
+        let queue = data.examine_queue();
+        assert_eq!(
+            queue,
+            vec![
+                ExamineRequest {
+                    pos: (1, 0),
+                    name: "goblin".to_owned()
+                },
+                ExamineRequest {
+                    pos: (2, 2),
+                    name: "orc wizard".to_owned()
+                },
+            ]
+        );
+
+        // Matched by name, without `ready_examine_monster`
+        data.process_json(&json!({"msg": "ui-push", "type": "describe-monster",
+            "title": "a goblin", "body": "Max HP: ~12\nThreat: Low\n"}))
+            .unwrap();
+        assert_eq!(data.examine_queue().len(), 1);
+        assert!(data.monsters.monsters[&2].description.is_some());
+        assert_eq!(data.examined_stats("Goblin").unwrap().max_hp, Some(12));
+
+        // Goblins seen later (e.g. on another level) are already examined, with the
+        // threat from the description
+        data.update_player(&json!({"msg": "player", "place": "Dungeon", "depth": 1}))
+            .unwrap();
+        data.update_player(&json!({"msg": "player", "place": "Lair", "depth": 1}))
+            .unwrap();
+        data.update_map(&json!({"msg": "map", "cells": [
+            {"x": 1, "y": 1, "mf": 1, "mon": {"id": 4, "name": "goblin", "threat": 1}},
+        ]}))
+        .unwrap();
+        assert!(data.examine_queue().is_empty());
+        assert_eq!(data.monsters.monsters[&4].threat, 1);
+        assert_eq!(
+            data.monsters.monsters[&4]
+                .description
+                .as_ref()
+                .unwrap()
+                .threat,
+            Some(Threat::Low)
+        );
+
+        // Hit chances are only kept for the monster examined, until the character
+        // changes
+        data.monster_description(
+            json!({"title": "", "body": "Max HP: ~12\nThreat: Low\n\
+                You have about 80% to hit it. It has about 40% to hit you."}),
+            Some((1, 1)),
+        )
+        .unwrap();
+        let description = data.monsters.monsters[&4].description.as_ref().unwrap();
+        assert_eq!(description.player_hit_chance, Some(80));
+        assert_eq!(
+            data.examined_stats("goblin").unwrap().player_hit_chance,
+            None
+        );
+
+        data.update_player(&json!({"msg": "player", "xl": 2}))
+            .unwrap();
+        let description = data.monsters.monsters[&4].description.as_ref().unwrap();
+        assert_eq!(description.player_hit_chance, None);
+        assert_eq!(description.max_hp, Some(12));
     }
 }
@@ -0,0 +1,636 @@
+use crate::common::get_str;
+use crate::data_errors::DataError;
+use dcss_api::FormattedText;
+use regex::Regex;
+use serde_json::Value;
+use std::sync::LazyLock;
+
+/// Value of a stat, up to the next column (two spaces, or the label of another
+/// stat) or line.
+macro_rules! stat_regex {
+    ($label:literal) => {
+        LazyLock::new(|| {
+            Regex::new(concat!(
+                $label,
+                r":\s*(.*?)(?:\s{2,}|\s+(?:Max HP|Will|AC|EV|r[A-Z][a-z]*|Threat|Class|Size|Int|Speed|Regen):|\n|$)"
+            ))
+            .unwrap()
+        })
+    };
+}
+
+static MAX_HP: LazyLock<Regex> = stat_regex!(r"Max HP");
+static WILL: LazyLock<Regex> = stat_regex!(r"Will");
+static AC: LazyLock<Regex> = stat_regex!(r"\bAC");
+static EV: LazyLock<Regex> = stat_regex!(r"\bEV");
+static FIRE: LazyLock<Regex> = stat_regex!(r"\brF");
+static COLD: LazyLock<Regex> = stat_regex!(r"\brC");
+static POISON: LazyLock<Regex> = stat_regex!(r"\brPois");
+static NEGATIVE: LazyLock<Regex> = stat_regex!(r"\brNeg");
+static ELECTRIC: LazyLock<Regex> = stat_regex!(r"\brElec");
+static THREAT: LazyLock<Regex> = stat_regex!(r"Threat");
+static CLASS: LazyLock<Regex> = stat_regex!(r"Class");
+static SIZE: LazyLock<Regex> = stat_regex!(r"Size");
+static INT: LazyLock<Regex> = stat_regex!(r"\bInt");
+static SPEED: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"Speed:\s*(\d+)%").unwrap());
+static SWIM_SPEED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)swim(?:ming)?(?: speed)?:?\s*(\d+)%").unwrap());
+static REGEN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"Regen:\s*(\d+)").unwrap());
+static PLAYER_HIT_CHANCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"You have about\s*(\d+)%").unwrap());
+static MONSTER_HIT_CHANCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:He|She|It|They) (?:has|have) about\s*(\d+)%").unwrap());
+static ATTACK_TABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*Attacks?\s+[^\n]*\bDamage\b[^\n]*$").unwrap());
+static ATTACK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:(\d+)x\s+)?([^\d(]*?)\s*(?:\(x(\d+)\))?\s+(\d+)(.*)$").unwrap()
+});
+static ATTACK_FLAVOUR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\(([^)]*[a-z][^)]*)\)").unwrap());
+static IMMUNITIES: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:is|are) immune to ([^.\n]*)").unwrap());
+
+/// Threat of a monster, relative to the character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Threat {
+    Minor,
+    Low,
+    Moderate,
+    High,
+    Lethal,
+    Other(String),
+}
+
+/// Class (holiness) of a monster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonsterClass {
+    Natural,
+    Undead,
+    Demonic,
+    Nonliving,
+    Plant,
+    Holy,
+    Other(String),
+}
+
+/// Size of a monster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonsterSize {
+    Tiny,
+    VerySmall,
+    Small,
+    Medium,
+    Large,
+    Giant,
+    Other(String),
+}
+
+/// Intelligence of a monster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonsterIntelligence {
+    Mindless,
+    Animal,
+    Human,
+    Other(String),
+}
+
+/// Special effect of a monster attack, in addition to its damage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttackFlavour {
+    Poison,
+    StrongPoison,
+    Drain,
+    Vampiric,
+    Paralyse,
+    Confuse,
+    Slow,
+    Sleep,
+    Weakness,
+    Fire,
+    Cold,
+    Electricity,
+    Acid,
+    Corrode,
+    Pain,
+    Steal,
+    Trample,
+    Engulf,
+    Other(String),
+}
+
+/// Attack of a monster, from the attack table of its description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonsterAttack {
+    /// Type of attack (e.g. "hit", "bite").
+    pub name: String,
+    /// Number of times the attack is made each turn.
+    pub count: i32,
+    pub max_damage: i32,
+    pub flavour: Option<AttackFlavour>,
+}
+
+/// Spell of a monster, from the spellset of its description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonsterSpell {
+    pub name: String,
+    /// Range, if shown.
+    pub range: Option<u32>,
+    /// Damage (e.g. "3d10"), if shown.
+    pub damage: Option<String>,
+}
+
+/// Stats of a monster, from its description (the "describe-monster" UI).
+/// Stats not shown by the game version are [None] (or empty).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonsterDescription {
+    /// Name of the monster, from the title of the description.
+    pub name: Option<String>,
+    pub threat: Option<Threat>,
+    pub max_hp: Option<i32>,
+    /// Number of pips of willpower (6 if immune).
+    pub will: Option<i32>,
+    /// Number of pips of AC and EV.
+    pub ac: Option<i32>,
+    pub ev: Option<i32>,
+    /// Resistances, from -1 (vulnerable) to 3, or 4 if immune.
+    pub fire: i32,
+    pub cold: i32,
+    pub poison: i32,
+    pub negative: i32,
+    pub electric: i32,
+    pub class: Option<MonsterClass>,
+    pub size: Option<MonsterSize>,
+    pub int: Option<MonsterIntelligence>,
+    /// Speed, in percent of the normal speed.
+    pub speed: i32,
+    /// Speed in water, in percent, if the monster can swim.
+    pub swim_speed: Option<i32>,
+    pub regen: i32,
+    /// Chance, in percent, of the character hitting the monster in melee. Only
+    /// kept for the monster examined, until the character changes (see
+    /// [`MonsterDescription::intrinsic`]).
+    pub player_hit_chance: Option<i32>,
+    /// Chance, in percent, of the monster hitting the character in melee. Only
+    /// kept as [`MonsterDescription::player_hit_chance`].
+    pub monster_hit_chance: Option<i32>,
+    pub attacks: Vec<MonsterAttack>,
+    pub spells: Vec<MonsterSpell>,
+    /// What the monster is immune to (e.g. "poison", "acid").
+    pub immunities: Vec<String>,
+    pub see_invisible: bool,
+    pub flies: bool,
+}
+
+impl Threat {
+    fn from_text(text: &str) -> Self {
+        match text {
+            "Minor" => Self::Minor,
+            "Low" => Self::Low,
+            "Moderate" => Self::Moderate,
+            "High" => Self::High,
+            "Lethal" => Self::Lethal,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+
+    /// Threat from 1 (minor) to 5 (lethal), or [None] if unknown.
+    pub fn level(&self) -> Option<i32> {
+        match self {
+            Self::Minor => Some(1),
+            Self::Low => Some(2),
+            Self::Moderate => Some(3),
+            Self::High => Some(4),
+            Self::Lethal => Some(5),
+            Self::Other(_) => None,
+        }
+    }
+}
+
+impl MonsterClass {
+    fn from_text(text: &str) -> Self {
+        match text {
+            "Natural" => Self::Natural,
+            "Undead" => Self::Undead,
+            "Demonic" => Self::Demonic,
+            "Nonliv." | "Nonliving" => Self::Nonliving,
+            "Plant" => Self::Plant,
+            "Holy" => Self::Holy,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+}
+
+impl MonsterSize {
+    fn from_text(text: &str) -> Self {
+        match text {
+            "Tiny" => Self::Tiny,
+            "V. Small" | "Very Small" => Self::VerySmall,
+            "Small" => Self::Small,
+            "Medium" => Self::Medium,
+            "Large" => Self::Large,
+            "Giant" => Self::Giant,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+}
+
+impl MonsterIntelligence {
+    fn from_text(text: &str) -> Self {
+        match text {
+            "Mindless" => Self::Mindless,
+            "Animal" => Self::Animal,
+            "Human" => Self::Human,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+}
+
+impl AttackFlavour {
+    /// Identify the flavour from its description in the attack table (e.g.
+    /// "poison", "drain"), or [AttackFlavour::Other].
+    pub fn from_text(text: &str) -> Self {
+        let text = text.trim().to_lowercase();
+
+        match text.as_str() {
+            t if t.contains("strong poison") => Self::StrongPoison,
+            t if t.contains("poison") => Self::Poison,
+            t if t.contains("vampiric") || t.contains("drain health") => Self::Vampiric,
+            t if t.contains("drain") => Self::Drain,
+            t if t.contains("paraly") => Self::Paralyse,
+            t if t.contains("confus") => Self::Confuse,
+            t if t.contains("slow") => Self::Slow,
+            t if t.contains("sleep") => Self::Sleep,
+            t if t.contains("weak") => Self::Weakness,
+            t if t.contains("fire") || t.contains("burn") => Self::Fire,
+            t if t.contains("cold") || t.contains("freez") => Self::Cold,
+            t if t.contains("elec") => Self::Electricity,
+            t if t.contains("acid") => Self::Acid,
+            t if t.contains("corro") => Self::Corrode,
+            t if t.contains("pain") => Self::Pain,
+            t if t.contains("steal") => Self::Steal,
+            t if t.contains("trampl") => Self::Trample,
+            t if t.contains("engulf") => Self::Engulf,
+            _ => Self::Other(text),
+        }
+    }
+}
+
+impl MonsterDescription {
+    /// Parse the "describe-monster" UI message. Only a missing body or max HP
+    /// is an error, other stats are optional (they differ between versions).
+    ///
+    /// # Arguments
+    ///
+    /// * `description` - A [serde_json::Value] of the "ui-push" message.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let description = MonsterDescription::parse(&message)?;
+    /// let dangerous = description.max_damage() > data.player_hp();
+    /// ```
+    pub fn parse(description: &Value) -> Result<Self, DataError> {
+        // Without the colour tags (e.g. of the resistances or the threat)
+        let mut body = FormattedText::parse(get_str(description, "body")?).plain();
+        body.push_str("\n\n");
+
+        let max_hp_text = capture(&MAX_HP, &body).ok_or_else(|| DataError::Parse {
+            what: "max HP",
+            text: body.clone(),
+        })?;
+        let max_hp = max_hp_text
+            .trim_start_matches('~')
+            .trim_start_matches("about ")
+            .trim()
+            .parse::<i32>()
+            .map_err(|_| DataError::Parse {
+                what: "max HP",
+                text: max_hp_text.to_owned(),
+            })?;
+
+        let attacks = match ATTACK_TABLE.find(&body) {
+            Some(found) => {
+                let table = &body[found.end()..];
+                let end_pos = table.find("\n\n").unwrap_or(table.len());
+
+                table[..end_pos].lines().filter_map(parse_attack).collect()
+            }
+            None => vec![],
+        };
+
+        // Spells, from all the books of the spellset
+        let spells = description["spellset"]
+            .as_array()
+            .map(|books| {
+                books
+                    .iter()
+                    .filter_map(|book| book["spells"].as_array())
+                    .flatten()
+                    .filter_map(parse_spell)
+                    .collect::<Vec<MonsterSpell>>()
+            })
+            .unwrap_or_default();
+
+        // Immunities (e.g. "It is immune to poison and acid.")
+        let immunities = IMMUNITIES
+            .captures_iter(&body)
+            .flat_map(|cap| {
+                cap[1]
+                    .replace(" and ", ", ")
+                    .split(", ")
+                    .map(|immunity| immunity.trim().to_owned())
+                    .filter(|immunity| !immunity.is_empty())
+                    .collect::<Vec<String>>()
+            })
+            .collect();
+
+        Ok(Self {
+            name: description["title"]
+                .as_str()
+                .map(|title| title.trim().to_owned()),
+            threat: capture(&THREAT, &body).map(Threat::from_text),
+            max_hp: Some(max_hp),
+            will: capture(&WILL, &body).map(|will| decode_pips(will, 6)),
+            ac: capture(&AC, &body).map(|ac| decode_pips(ac, 6)),
+            ev: capture(&EV, &body).map(|ev| decode_pips(ev, 6)),
+            fire: decode_resistance(capture(&FIRE, &body)),
+            cold: decode_resistance(capture(&COLD, &body)),
+            poison: decode_resistance(capture(&POISON, &body)),
+            negative: decode_resistance(capture(&NEGATIVE, &body)),
+            electric: decode_resistance(capture(&ELECTRIC, &body)),
+            class: capture(&CLASS, &body).map(MonsterClass::from_text),
+            size: capture(&SIZE, &body).map(MonsterSize::from_text),
+            int: capture(&INT, &body).map(MonsterIntelligence::from_text),
+            speed: capture_number(&SPEED, &body).unwrap_or(100),
+            swim_speed: capture_number(&SWIM_SPEED, &body),
+            regen: capture_number(&REGEN, &body).unwrap_or(0),
+            player_hit_chance: capture_number(&PLAYER_HIT_CHANCE, &body),
+            monster_hit_chance: capture_number(&MONSTER_HIT_CHANCE, &body),
+            attacks,
+            spells,
+            immunities,
+            see_invisible: body.contains("can see invisible"),
+            flies: body.contains("can fly") || body.contains("is flying"),
+        })
+    }
+
+    /// Highest damage the monster can do in one turn of melee (number of
+    /// attacks times their max damage, for the strongest attack).
+    pub fn max_damage(&self) -> i32 {
+        self.attacks
+            .iter()
+            .map(|attack| attack.count * attack.max_damage)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The stats of the type of monster only, without the hit chances, which
+    /// depend on the character when examined (skills, equipment, status).
+    pub fn intrinsic(&self) -> Self {
+        Self {
+            player_hit_chance: None,
+            monster_hit_chance: None,
+            ..self.clone()
+        }
+    }
+}
+
+/// Trimmed text captured by the first group of the regex.
+fn capture<'a>(re: &Regex, text: &'a str) -> Option<&'a str> {
+    re.captures(text)
+        .and_then(|cap| cap.get(1))
+        .map(|found| found.as_str().trim())
+        .filter(|found| !found.is_empty())
+}
+
+fn capture_number(re: &Regex, text: &str) -> Option<i32> {
+    capture(re, text).and_then(|number| number.parse::<i32>().ok())
+}
+
+/// Number of "+" in a stat (e.g. "++..." is 2), or `immune` for "∞".
+fn decode_pips(text: &str, immune: i32) -> i32 {
+    if text.starts_with('∞') {
+        return immune;
+    }
+
+    text.chars().filter(|c| *c == '+').count() as i32
+}
+
+/// Resistance level: "+" count minus "x" count, 4 if immune ("∞"), 0 if not shown.
+fn decode_resistance(text: Option<&str>) -> i32 {
+    match text {
+        Some(text) if text.starts_with('∞') => 4,
+        Some(text) => decode_pips(text, 4) - text.chars().filter(|c| *c == 'x').count() as i32,
+        None => 0,
+    }
+}
+
+/// Parse a line of the attack table (e.g. "2x bite   8 (poison)"), or [None] if
+/// it isn't an attack.
+fn parse_attack(line: &str) -> Option<MonsterAttack> {
+    let cap = ATTACK.captures(line)?;
+
+    let name = cap[2].trim().trim_end_matches(':').to_owned();
+    if name.is_empty() {
+        return None;
+    }
+
+    let count = cap
+        .get(1)
+        .or(cap.get(3))
+        .and_then(|count| count.as_str().parse::<i32>().ok())
+        .unwrap_or(1);
+    let max_damage = cap[4].parse::<i32>().ok()?;
+
+    // Flavour in parentheses after the damage, e.g. "(drain)"
+    let flavour = ATTACK_FLAVOUR
+        .captures(&cap[5])
+        .map(|flavour| AttackFlavour::from_text(&flavour[1]));
+
+    Some(MonsterAttack {
+        name,
+        count,
+        max_damage,
+        flavour,
+    })
+}
+
+/// Parse a spell of the spellset (e.g. `{"title": "Slow", "range_string": "6"}`).
+fn parse_spell(spell: &Value) -> Option<MonsterSpell> {
+    let name = spell["title"].as_str()?.trim().to_owned();
+
+    let range = spell["range_string"].as_str().and_then(|range| {
+        range
+            .trim_matches(|c: char| !c.is_ascii_digit())
+            .parse::<u32>()
+            .ok()
+    });
+    let damage = spell["effect"]
+        .as_str()
+        .map(|damage| damage.trim().trim_matches(|c| c == '(' || c == ')'))
+        .filter(|damage| !damage.is_empty())
+        .map(str::to_owned);
+
+    Some(MonsterSpell {
+        name,
+        range,
+        damage,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    fn fixture(name: &str) -> MonsterDescription {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/describe-monster")
+            .join(name);
+        let message = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+
+        MonsterDescription::parse(&message).unwrap()
+    }
+
+    #[test]
+    fn test_all_fixtures_parse() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/describe-monster");
+
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|extension| extension != "json") {
+                continue;
+            }
+
+            let message: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+
+            let description = MonsterDescription::parse(&message)
+                .unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+
+            assert!(description.max_hp.is_some(), "{}", path.display());
+            assert!(
+                !matches!(description.threat, Some(Threat::Other(_))),
+                "{}",
+                path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn test_goblin_0_29() {
+        let goblin = fixture("0.29-goblin.json");
+
+        assert_eq!(goblin.name.as_deref(), Some("goblin"));
+        assert_eq!(goblin.threat, Some(Threat::Minor));
+        assert_eq!(goblin.max_hp, Some(5));
+        assert_eq!(goblin.class, Some(MonsterClass::Natural));
+        assert_eq!(goblin.size, Some(MonsterSize::Small));
+        assert_eq!(goblin.max_damage(), 4);
+    }
+
+    #[test]
+    fn test_orc_wizard_0_30() {
+        let wizard = fixture("0.30-orc-wizard.json");
+
+        let spells = wizard
+            .spells
+            .iter()
+            .map(|spell| spell.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(spells, vec!["Magic Dart", "Slow", "Invisibility"]);
+        assert_eq!(wizard.spells[0].damage.as_deref(), Some("3d4"));
+        assert_eq!(wizard.int, Some(MonsterIntelligence::Human));
+    }
+
+    #[test]
+    fn test_adder_0_31() {
+        let adder = fixture("0.31-adder.json");
+
+        assert_eq!(adder.attacks[0].flavour, Some(AttackFlavour::Poison));
+        assert_eq!(adder.poison, 1);
+        assert_eq!(adder.swim_speed, None);
+    }
+
+    #[test]
+    fn test_wraith_0_32() {
+        let wraith = fixture("0.32-wraith.json");
+
+        assert_eq!(wraith.class, Some(MonsterClass::Undead));
+        assert_eq!(wraith.attacks[0].flavour, Some(AttackFlavour::Drain));
+        assert_eq!(wraith.negative, 3);
+        assert_eq!(wraith.poison, 4);
+        assert!(wraith.see_invisible);
+        assert!(wraith.flies);
+        assert_eq!(wraith.immunities, vec!["poison"]);
+    }
+
+    #[test]
+    fn test_electric_eel_0_32() {
+        let eel = fixture("0.32-electric-eel.json");
+
+        assert_eq!(eel.swim_speed, Some(150));
+        assert_eq!(eel.electric, 4);
+        assert_eq!(eel.int, Some(MonsterIntelligence::Animal));
+    }
+
+    #[test]
+    fn test_colour_tags_and_single_spaces() {
+        let description = serde_json::json!({
+            "title": "adder",
+            "body": "<lightgrey>A small snake.</lightgrey>\n\n\
+                Max HP: ~12 Will: + AC: <white>+....</white> EV: ++...\n\
+                rPois: <green>+</green> rF: . rC: <red>x</red>\n\
+                Threat: <lightred>High</lightred> Class: Natural Size: Small Int: Animal\n\n\
+                Attack Max Damage\n\
+                <red>bite</red> 8 (poison)\n"
+        });
+        let description = MonsterDescription::parse(&description).unwrap();
+
+        assert_eq!(description.max_hp, Some(12));
+        assert_eq!(description.will, Some(1));
+        assert_eq!(description.ac, Some(1));
+        assert_eq!(description.ev, Some(2));
+        assert_eq!(description.poison, 1);
+        assert_eq!(description.cold, -1);
+        assert_eq!(description.threat, Some(Threat::High));
+        assert_eq!(description.class, Some(MonsterClass::Natural));
+        assert_eq!(description.size, Some(MonsterSize::Small));
+        assert_eq!(description.int, Some(MonsterIntelligence::Animal));
+        assert_eq!(description.attacks[0].name, "bite");
+        assert_eq!(description.attacks[0].max_damage, 8);
+        assert_eq!(description.attacks[0].flavour, Some(AttackFlavour::Poison));
+    }
+
+    #[test]
+    fn test_unknown_values() {
+        let description = serde_json::json!({
+            "title": "strange thing",
+            "body": "Max HP: ~30\nThreat: Extreme\nClass: Fae\nSize: Colossal\nInt: Clever\n"
+        });
+        let description = MonsterDescription::parse(&description).unwrap();
+
+        assert_eq!(
+            description.threat,
+            Some(Threat::Other("Extreme".to_owned()))
+        );
+        assert_eq!(
+            description.class,
+            Some(MonsterClass::Other("Fae".to_owned()))
+        );
+        assert_eq!(
+            description.size,
+            Some(MonsterSize::Other("Colossal".to_owned()))
+        );
+        assert_eq!(
+            description.int,
+            Some(MonsterIntelligence::Other("Clever".to_owned()))
+        );
+        assert_eq!(description.ac, None);
+
+        // No max HP
+        let description = serde_json::json!({"title": "thing", "body": "Threat: Low\n"});
+        assert!(MonsterDescription::parse(&description).is_err());
+    }
+}
@@ -0,0 +1,315 @@
+use crate::common::Coord;
+use crate::monsters::description::{
+    MonsterAttack, MonsterClass, MonsterIntelligence, MonsterSize, MonsterSpell, Threat,
+};
+use crate::monsters::{Attitude, DamageLevel, Monster, MonsterFlag};
+use crate::pathfinding::{pathfinding, Goal};
+use crate::CrawlData;
+use std::cmp::{self, Reverse};
+
+/// Monster on the current level, as seen by the character. Stats from the
+/// description are [None] until the monster is examined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonsterView {
+    pub id: u64,
+    pub name: String,
+    pub pos: Coord,
+    /// Distance from the character, in tiles (diagonals count as one).
+    pub distance: i32,
+    /// Number of moves to reach the monster, if requested with
+    /// [`MonsterQuery::with_path`].
+    pub path_length: Option<usize>,
+    /// Threat from the map (0 to 3). Harmless monsters (e.g. plants) have a
+    /// threat of -1.
+    pub threat: i32,
+    /// Threat from the description, once examined (see [`Threat::level`] for its
+    /// scale, 1 to 5).
+    pub description_threat: Option<Threat>,
+    pub attitude: Attitude,
+    pub damage: DamageLevel,
+    pub flags: Vec<MonsterFlag>,
+    pub examined: bool,
+    pub max_hp: Option<i32>,
+    pub will: Option<i32>,
+    pub ac: Option<i32>,
+    pub ev: Option<i32>,
+    pub fire: Option<i32>,
+    pub cold: Option<i32>,
+    pub poison: Option<i32>,
+    pub negative: Option<i32>,
+    pub electric: Option<i32>,
+    pub class: Option<MonsterClass>,
+    pub size: Option<MonsterSize>,
+    pub int: Option<MonsterIntelligence>,
+    pub speed: Option<i32>,
+    pub regen: Option<i32>,
+    pub player_hit_chance: Option<i32>,
+    pub monster_hit_chance: Option<i32>,
+    pub max_damage: Option<i32>,
+    pub attacks: Vec<MonsterAttack>,
+    pub spells: Vec<MonsterSpell>,
+}
+
+/// Order of the monsters returned by a [MonsterQuery].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Order {
+    Unordered,
+    Nearest,
+    Threat,
+}
+
+/// Builder to select monsters on the current level, created with
+/// [`CrawlData::query_monsters`]. By default, every monster with a known
+/// position within the field of view is selected.
+///
+/// # Example
+///
+/// ```ignore
+/// // Hostile monsters within 3 tiles that can be reached, most dangerous first
+/// let targets = data
+///     .query_monsters()
+///     .hostile()
+///     .within(3)
+///     .with_path()
+///     .ranked_by_threat()
+///     .collect();
+/// ```
+#[derive(Debug, Clone)]
+pub struct MonsterQuery<'a> {
+    data: &'a CrawlData,
+    hostile: bool,
+    max_distance: u32,
+    with_path: bool,
+    examined: Option<bool>,
+    order: Order,
+}
+
+impl MonsterView {
+    fn new(id: u64, mon: &Monster, pos: Coord, player_pos: Coord) -> Self {
+        let desc = mon.description.as_ref();
+
+        Self {
+            id,
+            name: mon.name.clone(),
+            pos,
+            distance: cmp::max((player_pos.0 - pos.0).abs(), (player_pos.1 - pos.1).abs()),
+            path_length: None,
+            threat: mon.threat,
+            description_threat: desc.and_then(|desc| desc.threat.clone()),
+            attitude: mon.attitude,
+            damage: mon.damage,
+            flags: mon.flags.clone(),
+            examined: desc.is_some(),
+            max_hp: desc.and_then(|desc| desc.max_hp),
+            will: desc.and_then(|desc| desc.will),
+            ac: desc.and_then(|desc| desc.ac),
+            ev: desc.and_then(|desc| desc.ev),
+            fire: desc.map(|desc| desc.fire),
+            cold: desc.map(|desc| desc.cold),
+            poison: desc.map(|desc| desc.poison),
+            negative: desc.map(|desc| desc.negative),
+            electric: desc.map(|desc| desc.electric),
+            class: desc.and_then(|desc| desc.class.clone()),
+            size: desc.and_then(|desc| desc.size.clone()),
+            int: desc.and_then(|desc| desc.int.clone()),
+            speed: desc.map(|desc| desc.speed),
+            regen: desc.map(|desc| desc.regen),
+            player_hit_chance: desc.and_then(|desc| desc.player_hit_chance),
+            monster_hit_chance: desc.and_then(|desc| desc.monster_hit_chance),
+            max_damage: desc.map(|desc| desc.max_damage()),
+            attacks: desc.map(|desc| desc.attacks.clone()).unwrap_or_default(),
+            spells: desc.map(|desc| desc.spells.clone()).unwrap_or_default(),
+        }
+    }
+}
+
+impl<'a> MonsterQuery<'a> {
+    pub(crate) fn new(data: &'a CrawlData) -> Self {
+        Self {
+            data,
+            hostile: false,
+            max_distance: data.fov,
+            with_path: false,
+            examined: None,
+            order: Order::Unordered,
+        }
+    }
+
+    /// Only monsters that should be fought: hostile or neutral, and not harmless
+    /// (e.g. plants).
+    pub fn hostile(mut self) -> Self {
+        self.hostile = true;
+        self
+    }
+
+    /// Only monsters within `distance` tiles of the character (diagonals count as one).
+    ///
+    /// # Arguments
+    ///
+    /// * `distance` - Max distance, in tiles.
+    pub fn within(mut self, distance: u32) -> Self {
+        self.max_distance = distance;
+        self
+    }
+
+    /// Only monsters the character can walk to (through other monsters), in
+    /// less moves than the max distance. Sets [`MonsterView::path_length`].
+    pub fn with_path(mut self) -> Self {
+        self.with_path = true;
+        self
+    }
+
+    /// Only monsters already examined (with stats from their description).
+    pub fn examined(mut self) -> Self {
+        self.examined = Some(true);
+        self
+    }
+
+    /// Only monsters not examined yet.
+    pub fn unexamined(mut self) -> Self {
+        self.examined = Some(false);
+        self
+    }
+
+    /// Closest monsters first.
+    pub fn nearest_first(mut self) -> Self {
+        self.order = Order::Nearest;
+        self
+    }
+
+    /// Most threatening monsters first, by the threat from the map (known for
+    /// every monster), then from the description (for examined monsters), then
+    /// closest first.
+    pub fn ranked_by_threat(mut self) -> Self {
+        self.order = Order::Threat;
+        self
+    }
+
+    /// Run the query.
+    pub fn collect(self) -> Vec<MonsterView> {
+        let player_pos = self.data.player_pos();
+        let profile = self.data.movement_profile();
+
+        let mut views = self
+            .data
+            .monsters
+            .monsters
+            .iter()
+            .filter(|(_, mon)| mon.name != "invisible")
+            .filter(|(_, mon)| !self.hostile || mon.is_threat())
+            .filter(|(_, mon)| {
+                self.examined
+                    .is_none_or(|examined| examined == mon.description.is_some())
+            })
+            .filter_map(|(id, mon)| Some(MonsterView::new(*id, mon, mon.pos?, player_pos)))
+            .filter(|view| view.distance <= self.max_distance as i32)
+            .filter_map(|mut view| {
+                if self.with_path {
+                    let path = pathfinding(
+                        &self.data.tiles,
+                        player_pos,
+                        &Goal::Coord(view.pos),
+                        &profile,
+                        self.max_distance as u64,
+                        true,
+                    );
+                    if path.is_empty() {
+                        return None;
+                    }
+                    view.path_length = Some(path.len());
+                }
+
+                Some(view)
+            })
+            .collect::<Vec<MonsterView>>();
+
+        match self.order {
+            Order::Unordered => (),
+            Order::Nearest => views.sort_by_key(|view| (view.distance, view.id)),
+            Order::Threat => views.sort_by_key(|view| {
+                let description_threat = view
+                    .description_threat
+                    .as_ref()
+                    .and_then(Threat::level)
+                    .unwrap_or(0);
+
+                (
+                    Reverse(view.threat),
+                    Reverse(description_threat),
+                    view.distance,
+                    view.id,
+                )
+            }),
+        }
+
+        views
+    }
+
+    /// Number of monsters selected by the query.
+    pub fn count(self) -> usize {
+        self.collect().len()
+    }
+}
+
+impl CrawlData {
+    /// Start a [MonsterQuery] on the monsters of the current level.
+    pub fn query_monsters(&self) -> MonsterQuery<'_> {
+        MonsterQuery::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_monster_query() {
+        let mut data = CrawlData::init(7, "0.32");
+        data.update_player(&json!({"msg": "player", "pos": {"x": 0, "y": 0}}))
+            .unwrap();
+        data.update_map(&json!({"msg": "map", "cells": [
+            {"x": 0, "y": 0, "mf": 1}, {"mf": 1},
+            {"mf": 1, "mon": {"id": 1, "name": "goblin", "threat": 1}},
+            {"mf": 2},
+            {"mf": 1, "mon": {"id": 2, "name": "ogre", "threat": 3}},
+            {"x": 1, "y": 1, "mf": 1, "mon": {"id": 3, "name": "rat", "threat": 1, "att": 4}},
+        ]}))
+        .unwrap();
+
+        assert_eq!(data.query_monsters().count(), 3);
+        assert_eq!(data.query_monsters().hostile().count(), 2);
+
+        let ranked = data.query_monsters().hostile().ranked_by_threat().collect();
+        assert_eq!(ranked[0].name, "ogre");
+        assert_eq!(ranked[0].max_hp, None);
+
+        // The wall blocks the path to the ogre
+        let reachable = data.query_monsters().hostile().with_path().collect();
+        assert_eq!(reachable.len(), 1);
+        assert_eq!(reachable[0].name, "goblin");
+        assert_eq!(reachable[0].path_length, Some(2));
+
+        let nearest = data.query_monsters().within(1).nearest_first().collect();
+        assert_eq!(nearest[0].name, "rat");
+
+        // An orc further away, examined: same threat on the map as the goblin,
+        // but higher in its description
+        data.update_map(&json!({"msg": "map", "cells": [
+            {"x": 0, "y": 3, "mf": 1, "mon": {"id": 4, "name": "orc", "threat": 1}},
+        ]}))
+        .unwrap();
+        data.monster_description(
+            json!({"title": "orc", "body": "Max HP: ~12\nThreat: Moderate\n"}),
+            Some((0, 3)),
+        )
+        .unwrap();
+
+        let ranked = data.query_monsters().hostile().ranked_by_threat().collect();
+        let ids = ranked.iter().map(|view| view.id).collect::<Vec<u64>>();
+        assert_eq!(ids, vec![2, 4, 1]);
+        assert_eq!(ranked[1].threat, 1);
+        assert_eq!(ranked[1].description_threat, Some(Threat::Moderate));
+    }
+}
@@ -0,0 +1,428 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Integer value following a label of the stats table.
+macro_rules! stat_regex {
+    ($label:literal, $value:literal) => {
+        LazyLock::new(|| Regex::new(concat!($label, r":[ \t]*(", $value, ")")).unwrap())
+    };
+}
+
+static VERSION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"version\s+([^\s]+)").unwrap());
+static HEADER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^(\S+) the (.+?) \((.+?)\)\s+Turns: (\d+), Time: (\S+)").unwrap()
+});
+static HEALTH: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"Health:[ \t]*(-?\d+)/(\d+)").unwrap());
+static MAGIC: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"Magic:[ \t]*(-?\d+)/(\d+)").unwrap());
+static AC: LazyLock<Regex> = stat_regex!(r"AC", r"-?\d+");
+static EV: LazyLock<Regex> = stat_regex!(r"EV", r"-?\d+");
+static SH: LazyLock<Regex> = stat_regex!(r"SH", r"-?\d+");
+static STR: LazyLock<Regex> = stat_regex!(r"Str", r"-?\d+");
+static INT: LazyLock<Regex> = stat_regex!(r"Int", r"-?\d+");
+static DEX: LazyLock<Regex> = stat_regex!(r"Dex", r"-?\d+");
+static XL: LazyLock<Regex> = stat_regex!(r"XL", r"\d+");
+static GOLD: LazyLock<Regex> = stat_regex!(r"Gold", r"\d+");
+static GOD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"God:[ \t]*([^\[\n]*)").unwrap());
+static PLACE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^(You are (?:on|in) .*)$").unwrap());
+static SKILL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(\S)\s+Level\s+([\d.]+)\s+(.+?)\s*$").unwrap());
+static INVENTORY_ITEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s+(\w) - (.+?)\s*$").unwrap());
+static SPELL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\w) - (.+?)\s{2,}(\S+)\s+(.*)$").unwrap());
+static KILL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:(\d+) |An? |The )?(.+?) \((.+)\)\s*$").unwrap());
+
+/// Data parsed from a character dump (or morgue file), as written by DCSS with
+/// the `#` command (see `dcss_api::Webtile::character_dump`).
+#[derive(Debug, Clone, Default)]
+pub struct CharacterDump {
+    /// Version of DCSS (e.g. `0.32.1`).
+    pub version: Option<String>,
+    /// Name, title, species, background, etc. of the character.
+    pub stats: DumpStats,
+    /// Skills, in the order of the dump.
+    pub skills: Vec<DumpSkill>,
+    /// Inventory items, in the order of the dump.
+    pub inventory: Vec<DumpItem>,
+    /// Memorised spells.
+    pub spells: Vec<DumpSpell>,
+    /// Lines of the "Innate Abilities, Weirdness & Mutations" section.
+    pub mutations: Vec<String>,
+    /// Notes (turn, place, note).
+    pub notes: Vec<DumpNote>,
+    /// Vanquished creatures.
+    pub kills: Vec<DumpKill>,
+}
+
+/// Character information from the top of the dump.
+#[derive(Debug, Clone, Default)]
+pub struct DumpStats {
+    pub name: Option<String>,
+    pub title: Option<String>,
+    /// Species and background (e.g. `Minotaur Berserker`).
+    pub species_background: Option<String>,
+    pub turns: Option<u64>,
+    /// Real time played (e.g. `00:05:12`).
+    pub time: Option<String>,
+    pub hp: Option<i32>,
+    pub hp_max: Option<i32>,
+    pub mp: Option<i32>,
+    pub mp_max: Option<i32>,
+    pub ac: Option<i32>,
+    pub ev: Option<i32>,
+    pub sh: Option<i32>,
+    pub str: Option<i32>,
+    pub int: Option<i32>,
+    pub dex: Option<i32>,
+    pub xl: Option<u32>,
+    pub gold: Option<u32>,
+    /// [None] if the character does not worship a god.
+    pub god: Option<String>,
+    /// Location of the character (e.g. `You are on level 3 of the Dungeon.`).
+    pub place: Option<String>,
+}
+
+/// Training state of a skill, from the marker in front of the skill.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillTraining {
+    /// `+`
+    Enabled,
+    /// `-`
+    Disabled,
+    /// `*`
+    Focused,
+    /// `O` (maximum level)
+    Maxed,
+    /// Any other marker
+    Other(char),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DumpSkill {
+    pub name: String,
+    pub level: f64,
+    pub training: SkillTraining,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpItem {
+    /// Inventory letter.
+    pub slot: char,
+    /// Full name of the item (e.g. `a +0 mace (weapon)`).
+    pub name: String,
+    /// Category of the item (e.g. `Hand Weapons`).
+    pub category: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpSpell {
+    /// Spell letter.
+    pub slot: char,
+    pub name: String,
+    /// Schools of the spell (e.g. `Conj/Fire`).
+    pub schools: String,
+    /// Failure rate, in percent.
+    pub failure: Option<u32>,
+    pub level: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpNote {
+    pub turn: u64,
+    pub place: String,
+    pub note: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpKill {
+    /// Name of the creature (plural if more than one was killed).
+    pub name: String,
+    pub count: u32,
+    /// Place(s) where the creatures were killed (e.g. `D:1`).
+    pub place: String,
+}
+
+impl CharacterDump {
+    /// Parse the text of a character dump. Missing sections are left empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `dump` - A string slice of the full character dump.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let dump = CharacterDump::parse(&webtile.character_dump("/morgue/%n/%n.txt")?);
+    /// ```
+    pub fn parse(dump: &str) -> Self {
+        let lines = dump.lines().collect::<Vec<&str>>();
+
+        Self {
+            version: parse_version(dump),
+            stats: parse_stats(dump),
+            skills: parse_skills(&lines),
+            inventory: parse_inventory(&lines),
+            spells: parse_spells(&lines),
+            mutations: parse_mutations(&lines),
+            notes: parse_notes(&lines),
+            kills: parse_kills(&lines),
+        }
+    }
+}
+
+/// Lines following the first line for which `is_header` is true (empty if there
+/// is no such line).
+fn section<'a>(lines: &[&'a str], is_header: impl Fn(&str) -> bool) -> Vec<&'a str> {
+    match lines.iter().position(|line| is_header(line)) {
+        Some(index) => lines[index + 1..].to_vec(),
+        None => vec![],
+    }
+}
+
+fn parse_version(dump: &str) -> Option<String> {
+    VERSION.captures(dump).map(|cap| cap[1].to_owned())
+}
+
+fn capture_i32(re: &Regex, text: &str) -> Option<i32> {
+    re.captures(text).and_then(|cap| cap[1].parse::<i32>().ok())
+}
+
+fn parse_stats(dump: &str) -> DumpStats {
+    let mut stats = DumpStats::default();
+
+    if let Some(cap) = HEADER.captures(dump) {
+        stats.name = Some(cap[1].to_owned());
+        stats.title = Some(cap[2].to_owned());
+        stats.species_background = Some(cap[3].to_owned());
+        stats.turns = cap[4].parse::<u64>().ok();
+        stats.time = Some(cap[5].to_owned());
+    }
+
+    if let Some(cap) = HEALTH.captures(dump) {
+        stats.hp = cap[1].parse::<i32>().ok();
+        stats.hp_max = cap[2].parse::<i32>().ok();
+    }
+
+    if let Some(cap) = MAGIC.captures(dump) {
+        stats.mp = cap[1].parse::<i32>().ok();
+        stats.mp_max = cap[2].parse::<i32>().ok();
+    }
+
+    stats.ac = capture_i32(&AC, dump);
+    stats.ev = capture_i32(&EV, dump);
+    stats.sh = capture_i32(&SH, dump);
+    stats.str = capture_i32(&STR, dump);
+    stats.int = capture_i32(&INT, dump);
+    stats.dex = capture_i32(&DEX, dump);
+    stats.xl = capture_i32(&XL, dump).map(|xl| xl as u32);
+    stats.gold = capture_i32(&GOLD, dump).map(|gold| gold as u32);
+
+    stats.god = GOD
+        .captures(dump)
+        .map(|cap| cap[1].trim().to_owned())
+        .filter(|god| !god.is_empty());
+
+    stats.place = PLACE.captures(dump).map(|cap| cap[1].trim().to_owned());
+
+    stats
+}
+
+fn parse_skills(lines: &[&str]) -> Vec<DumpSkill> {
+    section(lines, |line| line.trim() == "Skills:")
+        .into_iter()
+        .map_while(|line| SKILL.captures(line))
+        .map(|cap| DumpSkill {
+            name: cap[3].to_owned(),
+            level: cap[2].parse::<f64>().unwrap_or(0.0),
+            training: match &cap[1] {
+                "+" => SkillTraining::Enabled,
+                "-" => SkillTraining::Disabled,
+                "*" => SkillTraining::Focused,
+                "O" => SkillTraining::Maxed,
+                marker => SkillTraining::Other(marker.chars().next().unwrap_or(' ')),
+            },
+        })
+        .collect()
+}
+
+fn parse_inventory(lines: &[&str]) -> Vec<DumpItem> {
+    let mut inventory = vec![];
+    let mut category = String::new();
+
+    for line in section(lines, |line| line.trim() == "Inventory:") {
+        if line.trim().is_empty() {
+            if inventory.is_empty() {
+                continue;
+            }
+            break;
+        }
+
+        match INVENTORY_ITEM.captures(line) {
+            Some(cap) => inventory.push(DumpItem {
+                slot: cap[1].chars().next().unwrap(),
+                name: cap[2].to_owned(),
+                category: category.clone(),
+            }),
+            None if !line.starts_with(' ') => category = line.trim().to_owned(),
+            None => break,
+        }
+    }
+
+    inventory
+}
+
+fn parse_spells(lines: &[&str]) -> Vec<DumpSpell> {
+    section(lines, |line| {
+        line.starts_with("You know the following spells")
+    })
+    .into_iter()
+    .skip_while(|line| !line.trim_start().starts_with("Your Spells"))
+    .skip(1)
+    .map_while(|line| SPELL.captures(line))
+    .map(|cap| {
+        let columns = cap[4].split_whitespace().collect::<Vec<&str>>();
+
+        DumpSpell {
+            slot: cap[1].chars().next().unwrap(),
+            name: cap[2].to_owned(),
+            schools: cap[3].to_owned(),
+            failure: columns
+                .iter()
+                .rev()
+                .find(|column| column.ends_with('%'))
+                .and_then(|column| column.trim_end_matches('%').parse::<u32>().ok()),
+            level: columns.last().and_then(|column| column.parse::<u32>().ok()),
+        }
+    })
+    .collect()
+}
+
+fn parse_mutations(lines: &[&str]) -> Vec<String> {
+    section(lines, |line| {
+        line.starts_with("Innate Abilities, Weirdness & Mutations")
+    })
+    .into_iter()
+    .skip_while(|line| line.trim().is_empty())
+    .take_while(|line| !line.trim().is_empty())
+    .map(|line| line.trim().to_owned())
+    .collect()
+}
+
+fn parse_notes(lines: &[&str]) -> Vec<DumpNote> {
+    let mut notes: Vec<DumpNote> = vec![];
+
+    for line in section(lines, |line| line.trim() == "Notes")
+        .into_iter()
+        .skip_while(|line| !line.starts_with("---"))
+        .skip(1)
+    {
+        let columns = line.splitn(3, '|').map(str::trim).collect::<Vec<&str>>();
+        if columns.len() != 3 {
+            break;
+        }
+
+        match columns[0].parse::<u64>() {
+            Ok(turn) => notes.push(DumpNote {
+                turn,
+                place: columns[1].to_owned(),
+                note: columns[2].to_owned(),
+            }),
+            // Long notes continue on the next line, without a turn
+            Err(_) => {
+                if let Some(note) = notes.last_mut() {
+                    note.note.push(' ');
+                    note.note.push_str(columns[2]);
+                }
+            }
+        }
+    }
+
+    notes
+}
+
+fn parse_kills(lines: &[&str]) -> Vec<DumpKill> {
+    section(lines, |line| line.starts_with("Vanquished Creatures"))
+        .into_iter()
+        .map_while(|line| KILL.captures(line))
+        .map(|cap| DumpKill {
+            name: cap[2].to_owned(),
+            count: cap
+                .get(1)
+                .and_then(|count| count.as_str().parse::<u32>().ok())
+                .unwrap_or(1),
+            place: cap[3].to_owned(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_dump() {
+        let dump = CharacterDump::parse(include_str!("../tests/fixtures/dump-0.32.txt"));
+
+        assert_eq!(dump.version, Some("0.32.1".to_owned()));
+
+        let stats = &dump.stats;
+        assert_eq!(stats.name, Some("Username".to_owned()));
+        assert_eq!(stats.title, Some("Skirmisher".to_owned()));
+        assert_eq!(
+            stats.species_background,
+            Some("Minotaur Berserker".to_owned())
+        );
+        assert_eq!(stats.turns, Some(1234));
+        assert_eq!((stats.hp, stats.hp_max), (Some(35), Some(38)));
+        assert_eq!((stats.mp, stats.mp_max), (Some(2), Some(3)));
+        assert_eq!((stats.ac, stats.ev, stats.sh), (Some(5), Some(8), Some(0)));
+        assert_eq!(
+            (stats.str, stats.int, stats.dex),
+            (Some(22), Some(5), Some(11))
+        );
+        assert_eq!((stats.xl, stats.gold), (Some(4), Some(54)));
+        assert_eq!(stats.god, Some("Trog".to_owned()));
+
+        assert_eq!(dump.inventory.len(), 4);
+        assert_eq!(dump.inventory[3].slot, 'd');
+        assert_eq!(dump.inventory[3].name, "a murky potion");
+        assert_eq!(dump.inventory[3].category, "Potions");
+
+        assert_eq!(dump.skills.len(), 5);
+        assert_eq!(dump.skills[1].name, "Maces & Flails");
+        assert_eq!(dump.skills[3].level, 2.3);
+        assert_eq!(dump.skills[3].training, SkillTraining::Focused);
+
+        assert_eq!(dump.spells.len(), 1);
+        assert_eq!(dump.spells[0].name, "Magic Dart");
+        assert_eq!(dump.spells[0].schools, "Conj");
+        assert_eq!(dump.spells[0].failure, Some(7));
+        assert_eq!(dump.spells[0].level, Some(1));
+
+        assert_eq!(dump.mutations.len(), 2);
+
+        assert_eq!(dump.notes.len(), 3);
+        assert_eq!(dump.notes[2].turn, 345);
+        assert_eq!(dump.notes[2].place, "D:2");
+
+        assert_eq!(dump.kills.len(), 3);
+        assert_eq!(dump.kills[0].count, 2);
+        assert_eq!(dump.kills[0].name, "rats");
+        assert_eq!(dump.kills[1].name, "ball python");
+        assert_eq!(dump.kills[1].count, 1);
+    }
+
+    #[test]
+    fn test_parse_dump_without_god() {
+        let dump = CharacterDump::parse(
+            &include_str!("../tests/fixtures/dump-0.32.txt").replace("Trog [**....]", ""),
+        );
+
+        assert_eq!(dump.stats.god, None);
+        assert_eq!(dump.stats.dex, Some(11));
+    }
+}
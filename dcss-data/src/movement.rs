@@ -0,0 +1,190 @@
+use crate::status::Status;
+use crate::tiles::Tile;
+use crate::CrawlData;
+
+/// Cost of moving onto each type of terrain, used by the pathfinding. A cost of
+/// [None] makes the terrain impassable. Costs are relative to a floor tile (1).
+///
+/// # Example
+///
+/// ```ignore
+/// // Flying character, that avoids traps and clouds at almost any cost
+/// let profile = MovementProfile::walking().with_flight();
+/// let profile = MovementProfile { trap: Some(50), cloud: Some(50), ..profile };
+/// let path = data.path_to_down_stairs(&profile);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MovementProfile {
+    /// Floor, doors, stairs, items and other walkable features.
+    pub floor: u64,
+    /// Shallow water (MF 16).
+    pub shallow_water: Option<u64>,
+    /// Deep water (MF 22).
+    pub deep_water: Option<u64>,
+    /// Lava (MF 17).
+    pub lava: Option<u64>,
+    /// Traps (MF 18).
+    pub trap: Option<u64>,
+    /// Added to the cost of a tile with a cloud.
+    pub cloud: Option<u64>,
+}
+
+impl Default for MovementProfile {
+    fn default() -> Self {
+        Self::walking()
+    }
+}
+
+impl MovementProfile {
+    /// Character without any special movement: deep water and lava are impassable,
+    /// shallow water is slower, and traps and clouds are avoided.
+    pub fn walking() -> Self {
+        Self {
+            floor: 1,
+            shallow_water: Some(2),
+            deep_water: None,
+            lava: None,
+            trap: Some(10),
+            cloud: Some(10),
+        }
+    }
+
+    /// Flying over water and lava (e.g. flight status, or a species that flies).
+    pub fn with_flight(self) -> Self {
+        Self {
+            shallow_water: Some(self.floor),
+            deep_water: Some(self.floor),
+            lava: Some(self.floor),
+            ..self
+        }
+    }
+
+    /// Swimming through water at normal speed (e.g. Merfolk, Octopode).
+    pub fn with_swimming(self) -> Self {
+        Self {
+            shallow_water: Some(self.floor),
+            deep_water: Some(self.floor),
+            ..self
+        }
+    }
+
+    /// Moving through shallow water at normal speed, deep water staying impassable
+    /// (e.g. Barachi).
+    pub fn with_amphibious(self) -> Self {
+        Self {
+            shallow_water: Some(self.floor),
+            ..self
+        }
+    }
+
+    /// Clarity protects from the confusing clouds, making clouds less of a threat.
+    pub fn with_clarity(self) -> Self {
+        Self {
+            cloud: self.cloud.map(|cloud| cloud.min(3)),
+            ..self
+        }
+    }
+
+    /// Cost of moving onto the tile, or [None] if it is impassable (including
+    /// unexplored tiles).
+    pub(crate) fn cost(&self, tile: &Tile) -> Option<u64> {
+        let terrain = match tile.mf {
+            16 => self.shallow_water,
+            17 => self.lava,
+            18 => self.trap,
+            22 => self.deep_water,
+            _ if tile.walkable => Some(self.floor),
+            _ => None,
+        }?;
+
+        if tile.cloud {
+            return self.cloud.map(|cloud| terrain + cloud);
+        }
+
+        Some(terrain)
+    }
+}
+
+impl CrawlData {
+    /// [MovementProfile] of the character, based on its species (e.g. Merfolk swim)
+    /// and status (e.g. flying, clarity).
+    pub fn movement_profile(&self) -> MovementProfile {
+        let mut profile = match self.player_species() {
+            "Merfolk" | "Octopode" => MovementProfile::walking().with_swimming(),
+            "Barachi" => MovementProfile::walking().with_amphibious(),
+            _ => MovementProfile::walking(),
+        };
+
+        if self.has_status_effect(&Status::Flying) {
+            profile = profile.with_flight();
+        }
+
+        if self.has_status_effect(&Status::Clarity) {
+            profile = profile.with_clarity();
+        }
+
+        profile
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_movement_cost() {
+        let mut tile = Tile::new();
+        tile.mf = 22;
+
+        assert_eq!(MovementProfile::walking().cost(&tile), None);
+        assert_eq!(
+            MovementProfile::walking().with_swimming().cost(&tile),
+            Some(1)
+        );
+        assert_eq!(
+            MovementProfile::walking().with_amphibious().cost(&tile),
+            None
+        );
+
+        tile.mf = 16;
+        assert_eq!(
+            MovementProfile::walking().with_amphibious().cost(&tile),
+            Some(1)
+        );
+
+        tile.mf = 1;
+        tile.walkable = true;
+        tile.cloud = true;
+        assert_eq!(MovementProfile::walking().cost(&tile), Some(11));
+        assert_eq!(
+            MovementProfile::walking().with_clarity().cost(&tile),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn test_movement_profile() {
+        let mut data = CrawlData::init(7, "0.32");
+        data.update_player(&json!({"msg": "player", "species": "Barachi"}))
+            .unwrap();
+        assert_eq!(
+            data.movement_profile(),
+            MovementProfile::walking().with_amphibious()
+        );
+
+        data.update_player(&json!({"msg": "player", "species": "Merfolk", "status": [
+            {"light": "Fly", "text": "flying", "col": 1},
+            {"light": "Clear", "col": 1},
+        ]}))
+        .unwrap();
+        assert_eq!(
+            data.movement_profile(),
+            MovementProfile::walking()
+                .with_swimming()
+                .with_flight()
+                .with_clarity()
+        );
+    }
+}
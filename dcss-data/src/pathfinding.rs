@@ -0,0 +1,741 @@
+use crate::common::Coord;
+use crate::movement::MovementProfile;
+use crate::tiles::{Tile, Tiles};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// The 8 directions a character can move in.
+const DIRECTIONS: [Coord; 8] = [
+    (-1, 0),
+    (1, 0),
+    (0, -1),
+    (0, 1),
+    (-1, -1),
+    (-1, 1),
+    (1, -1),
+    (1, 1),
+];
+
+/// Goal of a path search.
+pub enum Goal<'a> {
+    /// A specific tile.
+    Coord(Coord),
+    /// Any tile with this map feature (e.g. 13 for down stairs).
+    Mf(usize),
+    /// Any tile not yet explored.
+    Unexplored,
+    /// Any tile for which the function (called with the coordinates and the map
+    /// feature of the tile) returns `true`.
+    Predicate(&'a dyn Fn(Coord, usize) -> bool),
+}
+
+impl Goal<'_> {
+    fn reached(&self, coord: Coord, tile: &Tile) -> bool {
+        match self {
+            Goal::Coord(goal) => *goal == coord,
+            Goal::Mf(mf) => tile.mf == *mf,
+            Goal::Unexplored => !tile.explored,
+            Goal::Predicate(predicate) => predicate(coord, tile.mf),
+        }
+    }
+
+    /// Estimated number of moves to the goal (maximum absolute distance, since
+    /// diagonal moves cost the same), only known for [Goal::Coord].
+    fn heuristic(&self, coord: Coord) -> u64 {
+        match self {
+            Goal::Coord(goal) => {
+                let x = (coord.0 - goal.0).unsigned_abs() as u64;
+                let y = (coord.1 - goal.1).unsigned_abs() as u64;
+                x.max(y)
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// Rectangle of the map, large enough to hold every tile a search can reach
+/// (the known tiles, with a border of 1 for the unknown tiles around them).
+#[derive(Debug, Clone, Copy)]
+struct Area {
+    min: Coord,
+    width: usize,
+    height: usize,
+}
+
+impl Area {
+    fn new(tiles: &Tiles, extra: &[Coord]) -> Self {
+        let (mut min, mut max) = tiles.bounds().unwrap_or((extra[0], extra[0]));
+        for coord in extra {
+            min = (min.0.min(coord.0), min.1.min(coord.1));
+            max = (max.0.max(coord.0), max.1.max(coord.1));
+        }
+
+        Self {
+            min: (min.0 - 1, min.1 - 1),
+            width: (max.0 - min.0 + 3) as usize,
+            height: (max.1 - min.1 + 3) as usize,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn index(&self, coord: Coord) -> Option<usize> {
+        let x = coord.0 - self.min.0;
+        let y = coord.1 - self.min.1;
+
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+
+        Some(x as usize * self.height + y as usize)
+    }
+
+    fn coord(&self, index: usize) -> Coord {
+        (
+            self.min.0 + (index / self.height) as i32,
+            self.min.1 + (index % self.height) as i32,
+        )
+    }
+}
+
+/// Set of coordinates in an [Area], stored as a bitset.
+struct CoordSet {
+    area: Area,
+    bits: Vec<u64>,
+}
+
+impl CoordSet {
+    fn new(area: Area) -> Self {
+        Self {
+            area,
+            bits: vec![0; area.len().div_ceil(64)],
+        }
+    }
+
+    /// Add the coordinates to the set, returns `false` if they already were in
+    /// the set (or are outside of the area).
+    fn insert(&mut self, coord: Coord) -> bool {
+        let Some(index) = self.area.index(coord) else {
+            return false;
+        };
+
+        let (word, bit) = (index / 64, 1 << (index % 64));
+        let inserted = self.bits[word] & bit == 0;
+        self.bits[word] |= bit;
+
+        inserted
+    }
+}
+
+/// Cost of moving onto the tile according to the [MovementProfile], or [None]
+/// if it can't be walked through.
+fn step_cost(tile: &Tile, profile: &MovementProfile, ignore_blocked: bool) -> Option<u64> {
+    if tile.blocked && !ignore_blocked {
+        return None;
+    }
+
+    profile.cost(tile)
+}
+
+/// Computes the cheapest path to a goal using the A* algorithm (with a binary heap)
+/// and return the path (vector of (x, y) coordinates, without the starting location).
+/// Returns an empty path if the goal can't be reached.
+///
+/// # Arguments
+///
+/// * `tiles` - the [Tiles] of the level.
+/// * `start_location` - (x, y) location to start the algorithm.
+/// * `goal` - the [Goal] to reach. The goal tile does not need to be walkable
+///   (e.g. a monster).
+/// * `profile` - the [MovementProfile] with the cost of each terrain.
+/// * `max_path` - maximum path steps (made to speed up when looking dozens of time per action).
+/// * `ignore_blocked` - walk through temporarily blocked tiles (e.g. monsters).
+pub(crate) fn pathfinding(
+    tiles: &Tiles,
+    start_location: Coord,
+    goal: &Goal,
+    profile: &MovementProfile,
+    max_path: u64,
+    ignore_blocked: bool,
+) -> Vec<Coord> {
+    let mut extra = vec![start_location];
+    if let Goal::Coord(coord) = goal {
+        extra.push(*coord);
+    }
+    let area = Area::new(tiles, &extra);
+
+    // Cheapest move cost, so that the heuristic never overestimates
+    let min_cost = [
+        profile.shallow_water,
+        profile.deep_water,
+        profile.lava,
+        profile.trap,
+    ]
+    .into_iter()
+    .flatten()
+    .fold(profile.floor, u64::min);
+
+    // Cost and moves done to get to each tile, and the tile it was reached from
+    let mut costs = vec![u64::MAX; area.len()];
+    let mut moves_done = vec![0; area.len()];
+    let mut parents: Vec<Option<usize>> = vec![None; area.len()];
+    let mut closed = CoordSet::new(area);
+
+    // Ordered by cost, then by order of insertion (to make ordering deterministic)
+    let mut open = BinaryHeap::new();
+    let mut key_break = 0u64;
+
+    let start = area.index(start_location).unwrap();
+    costs[start] = 0;
+    open.push(Reverse((
+        goal.heuristic(start_location) * min_cost,
+        key_break,
+        start,
+    )));
+
+    let mut end = None;
+    while let Some(Reverse((_, _, index))) = open.pop() {
+        let location = area.coord(index);
+        if !closed.insert(location) {
+            continue;
+        }
+
+        let tile = tiles.get(location);
+        if index != start && goal.reached(location, &tile) {
+            end = Some(index);
+            break;
+        }
+
+        // Goals don't need to be walkable, but can't be walked through
+        if index != start && step_cost(&tile, profile, ignore_blocked).is_none() {
+            continue;
+        }
+
+        // Limit how many moves the algorithm does
+        if moves_done[index] > max_path {
+            continue;
+        }
+
+        for direction in DIRECTIONS {
+            let next = (location.0 + direction.0, location.1 + direction.1);
+            let Some(next_index) = area.index(next) else {
+                continue;
+            };
+
+            let next_tile = tiles.get(next);
+            let Some(cost) = step_cost(&next_tile, profile, ignore_blocked)
+                .or_else(|| goal.reached(next, &next_tile).then_some(profile.floor))
+            else {
+                continue;
+            };
+
+            let next_cost = costs[index] + cost;
+            if next_cost < costs[next_index] {
+                costs[next_index] = next_cost;
+                moves_done[next_index] = moves_done[index] + 1;
+                parents[next_index] = Some(index);
+
+                key_break += 1;
+                open.push(Reverse((
+                    next_cost + goal.heuristic(next) * min_cost,
+                    key_break,
+                    next_index,
+                )));
+            }
+        }
+    }
+
+    // Unravel the parents - will give the cheapest path
+    let mut path = vec![];
+    let mut current = end;
+    while let Some(index) = current {
+        if index == start {
+            break;
+        }
+        path.push(area.coord(index));
+        current = parents[index];
+    }
+    path.reverse();
+
+    path
+}
+
+/// Cost (see [MovementProfile]) of the cheapest path from every reachable tile
+/// to the closest of a set of sources, computed once (with [`DistanceMap::new`])
+/// and reused for any number of queries (e.g. distance to the closest monster
+/// from every tile).
+#[derive(Debug, Clone)]
+pub struct DistanceMap {
+    area: Area,
+    distances: Vec<u64>,
+    /// Tile each tile was reached from, towards the closest source.
+    parents: Vec<Option<usize>>,
+}
+
+impl DistanceMap {
+    /// Compute the distance map (Dijkstra's algorithm, from all sources at once).
+    /// Sources do not need to be walkable. The cost of a path is the sum of the
+    /// cost of the tiles moved onto, the source being the start of the path.
+    ///
+    /// # Arguments
+    ///
+    /// * `tiles` - the [Tiles] of the level.
+    /// * `sources` - the tiles at distance 0.
+    /// * `profile` - the [MovementProfile] with the cost of each terrain.
+    /// * `max_distance` - tiles further than this are considered unreachable.
+    /// * `ignore_blocked` - walk through temporarily blocked tiles (e.g. monsters).
+    pub(crate) fn new(
+        tiles: &Tiles,
+        sources: &[Coord],
+        profile: &MovementProfile,
+        max_distance: u64,
+        ignore_blocked: bool,
+    ) -> Self {
+        let area = Area::new(
+            tiles,
+            if sources.is_empty() {
+                &[(0, 0)]
+            } else {
+                sources
+            },
+        );
+        let mut distances = vec![u64::MAX; area.len()];
+        let mut parents: Vec<Option<usize>> = vec![None; area.len()];
+        let mut closed = CoordSet::new(area);
+
+        // Ordered by distance, then by index (to make ordering deterministic)
+        let mut open = BinaryHeap::new();
+
+        for source in sources {
+            let index = area.index(*source).unwrap();
+            if distances[index] != 0 {
+                distances[index] = 0;
+                open.push(Reverse((0, index)));
+            }
+        }
+
+        while let Some(Reverse((distance, index))) = open.pop() {
+            let location = area.coord(index);
+            if !closed.insert(location) {
+                continue;
+            }
+
+            for direction in DIRECTIONS {
+                let next = (location.0 + direction.0, location.1 + direction.1);
+                let Some(next_index) = area.index(next) else {
+                    continue;
+                };
+
+                let Some(cost) = step_cost(&tiles.get(next), profile, ignore_blocked) else {
+                    continue;
+                };
+
+                let next_distance = distance + cost;
+                if next_distance <= max_distance && next_distance < distances[next_index] {
+                    distances[next_index] = next_distance;
+                    parents[next_index] = Some(index);
+                    open.push(Reverse((next_distance, next_index)));
+                }
+            }
+        }
+
+        Self {
+            area,
+            distances,
+            parents,
+        }
+    }
+
+    /// Distance from the tile to the closest source, or [None] if unreachable.
+    pub fn distance(&self, coord: Coord) -> Option<u64> {
+        self.area
+            .index(coord)
+            .map(|index| self.distances[index])
+            .filter(|distance| *distance != u64::MAX)
+    }
+
+    /// Path from the tile to the closest source (without the starting tile, and
+    /// ending on the source), or an empty path if unreachable.
+    pub fn path_from(&self, coord: Coord) -> Vec<Coord> {
+        let mut path = vec![];
+
+        if self.distance(coord).is_none() {
+            return path;
+        }
+
+        let mut current = self.area.index(coord).and_then(|index| self.parents[index]);
+        while let Some(index) = current {
+            path.push(self.area.coord(index));
+            current = self.parents[index];
+        }
+
+        path
+    }
+
+    /// Reachable tiles (and their distance), in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, u64)> + '_ {
+        self.distances
+            .iter()
+            .enumerate()
+            .filter(|(_, distance)| **distance != u64::MAX)
+            .map(|(index, distance)| (self.area.coord(index), *distance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json::json;
+    use std::time::Instant;
+
+    /// Open level of `width` x `height` floor tiles, surrounded by walls.
+    fn open_level(width: i32, height: i32) -> Tiles {
+        let mut tiles = Tiles::init();
+
+        let mut cells = vec![];
+        for y in -1..=height {
+            cells.push(json!({"x": -1, "y": y, "mf": 2}));
+            for x in 0..=width {
+                let border = y == -1 || y == height || x == width;
+                cells.push(json!({"mf": if border { 2 } else { 1 }}));
+            }
+        }
+        tiles.update(&json!(cells)).unwrap();
+
+        tiles
+    }
+
+    #[test]
+    fn test_pathfinding() {
+        let mut tiles = open_level(10, 10);
+        let walking = MovementProfile::walking();
+
+        // Straight diagonal
+        let path = pathfinding(&tiles, (0, 0), &Goal::Coord((5, 5)), &walking, 100, false);
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.last(), Some(&(5, 5)));
+
+        // Map feature and predicate
+        tiles.get_mut((8, 2)).mf = 13;
+        let path = pathfinding(&tiles, (0, 0), &Goal::Mf(13), &walking, 100, false);
+        assert_eq!(path.len(), 8);
+        assert_eq!(path.last(), Some(&(8, 2)));
+        let predicate = |coord: Coord, _mf: usize| coord.0 == 3;
+        let path = pathfinding(
+            &tiles,
+            (0, 0),
+            &Goal::Predicate(&predicate),
+            &walking,
+            100,
+            false,
+        );
+        assert_eq!(path.len(), 3);
+
+        // Unexplored (outside of the walls) is unreachable
+        let path = pathfinding(&tiles, (0, 0), &Goal::Unexplored, &walking, 100, false);
+        assert!(path.is_empty());
+    }
+
+    #[test]
+    fn test_terrain_costs() {
+        let mut tiles = open_level(10, 10);
+        let walking = MovementProfile::walking();
+
+        // Column of deep water, with a single trap to cross it
+        for y in 0..10 {
+            tiles.get_mut((5, y)).mf = 22;
+            tiles.get_mut((5, y)).walkable = false;
+        }
+        tiles.get_mut((5, 9)).mf = 18;
+
+        let path = pathfinding(&tiles, (0, 0), &Goal::Coord((9, 0)), &walking, 100, false);
+        assert!(path.contains(&(5, 9)));
+
+        // Swimming straight through
+        let swimming = walking.with_swimming();
+        let path = pathfinding(&tiles, (0, 0), &Goal::Coord((9, 0)), &swimming, 100, false);
+        assert_eq!(path.len(), 9);
+
+        // Traps are impassable
+        let careful = MovementProfile {
+            trap: None,
+            ..walking
+        };
+        let path = pathfinding(&tiles, (0, 0), &Goal::Coord((9, 0)), &careful, 100, false);
+        assert!(path.is_empty());
+    }
+
+    #[test]
+    fn test_distance_map() {
+        let mut tiles = open_level(10, 10);
+        let walking = MovementProfile::walking();
+
+        let map = DistanceMap::new(&tiles, &[(0, 0), (9, 9)], &walking, 100, false);
+        assert_eq!(map.distance((0, 0)), Some(0));
+        assert_eq!(map.distance((3, 5)), Some(5));
+        assert_eq!(map.distance((7, 6)), Some(3));
+        assert_eq!(map.distance((-1, 0)), None);
+        assert_eq!(map.path_from((7, 6)).len(), 3);
+        assert_eq!(map.path_from((7, 6)).last(), Some(&(9, 9)));
+        assert_eq!(map.iter().count(), 100);
+
+        // Column of slow shallow water, with a gap at the bottom: going around is
+        // cheaper than wading through
+        for y in 0..9 {
+            tiles.get_mut((5, y)).mf = 16;
+        }
+        let wading = MovementProfile {
+            shallow_water: Some(20),
+            ..walking
+        };
+        let map = DistanceMap::new(&tiles, &[(0, 0)], &wading, 100, false);
+        assert_eq!(map.distance((5, 0)), Some(24));
+        assert_eq!(map.distance((6, 0)), Some(18));
+        assert!(map.path_from((6, 0)).contains(&(5, 9)));
+    }
+
+    /// Compare with the previous implementation (`VecDeque` sorted after each
+    /// expansion, and `Vec` closed set), on a large open level. Run with
+    /// `cargo test --release -- --ignored bench_pathfinding --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_pathfinding() {
+        let tiles = open_level(80, 70);
+        let walking = MovementProfile::walking();
+        let runs = 10;
+
+        let start = Instant::now();
+        for _ in 0..runs {
+            pathfinding(
+                &tiles,
+                (0, 0),
+                &Goal::Coord((79, 69)),
+                &walking,
+                10_000,
+                false,
+            );
+            pathfinding(&tiles, (40, 35), &Goal::Unexplored, &walking, 10_000, false);
+        }
+        let heap = start.elapsed() / runs;
+
+        let start = Instant::now();
+        for _ in 0..runs {
+            legacy::pathfinding(&tiles, (0, 0), Some((79, 69)), None, None, 10_000, false);
+            legacy::pathfinding(
+                &tiles,
+                (40, 35),
+                None,
+                None,
+                Some("unexplored"),
+                10_000,
+                false,
+            );
+        }
+        let previous = start.elapsed() / runs;
+
+        let start = Instant::now();
+        for _ in 0..runs {
+            DistanceMap::new(&tiles, &[(40, 35)], &walking, 10_000, false);
+        }
+        let distance_map = start.elapsed() / runs;
+
+        println!(
+            "Binary heap A*: {heap:?}, previous A*: {previous:?}, distance map: {distance_map:?}"
+        );
+    }
+
+    /// Previous implementation, kept for [bench_pathfinding].
+    mod legacy {
+        use crate::common::Coord;
+        use crate::tiles::Tiles;
+        use std::collections::VecDeque;
+
+        #[derive(Debug)]
+        /// Struct for each node for the A* algorithm.
+        struct Node {
+            /// Cost of moving to this node, depends on path.
+            cost: u64,
+            /// Number of movement completed to get to this node.
+            moves_done: u64,
+            /// Number of estimated moves necessary to get to goal.
+            moves_to_goal: u64,
+            /// Coordinates (x, y) of the node.
+            location: Coord,
+            /// Coordinates (x, y) of the node that is being searched.
+            end_goal: Option<Coord>,
+            /// The location in the list of nodes (explored) that the parent node is found.
+            parent_node: Option<usize>,
+            /// Extra key to make ordering deterministic
+            key_break: u64,
+        }
+
+        impl Node {
+            /// Calculate the cost by estimating the maximum absolute distance (diagonally) to the target.
+            fn calc_cost(&mut self) {
+                if let Some(end_goal) = self.end_goal {
+                    let x_max = self.location.0 - end_goal.0;
+                    let y_max = self.location.1 - end_goal.1;
+
+                    let x_max = x_max.wrapping_abs() as u64;
+                    let y_max = y_max.wrapping_abs() as u64;
+
+                    self.moves_to_goal = x_max.max(y_max);
+                }
+
+                self.cost = self.moves_done + self.moves_to_goal;
+            }
+        }
+
+        /// Computes the path to a known coordinate or unknown object using A* Algorithm and return
+        /// the path (vector of (x, y) coordinates).
+        ///
+        /// # Arguments
+        ///
+        /// * `tiles` - the [Tiles] of the level.
+        /// * `start_location` - (x, y) location to start the algorithm.
+        /// * `end_location` (optional) - stop when (x, y) is reached.
+        /// * `end_search_mf` (optional) - stop when Tile.mf == end_search_mf is reached.
+        /// * `end_search_type` - stop when specified condition is reached (specific to this implementation).
+        /// * `max_path` - maximum path steps (made to speed up when looking dozens of time per action).
+        pub(super) fn pathfinding(
+            tiles: &Tiles,
+            start_location: Coord,
+            end_location: Option<Coord>,
+            end_search_mf: Option<usize>,
+            end_search_type: Option<&'static str>,
+            max_path: u64,
+            ignore_blocked: bool,
+        ) -> Vec<Coord> {
+            // Create a list of all nodes
+            let mut explored: Vec<Node> = vec![];
+
+            // Has the final_path been found
+            let mut final_path = None;
+
+            // open is a list of Nodes not yet checked by A*.
+            let mut open: VecDeque<Node> = VecDeque::new();
+
+            // Extra key to make ordering deterministic
+            let mut key_break = 0;
+
+            // Add the starting Node (since they are nested meant to be nodes)
+            let mut first_node = Node {
+                cost: 0,
+                moves_done: 0,
+                moves_to_goal: 0,
+                location: start_location,
+                end_goal: end_location,
+                parent_node: None,
+                key_break,
+            };
+            first_node.calc_cost();
+            open.push_back(first_node);
+            key_break += 1;
+
+            // Create a closed set of Nodes already checked by A*.\
+            let mut closed: Vec<Coord> = Vec::new();
+            closed.push(start_location);
+
+            let dir_list = [
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (-1, 1),
+                (1, -1),
+                (1, 1),
+            ];
+
+            let mut end_found = false;
+            while !open.is_empty() && !end_found {
+                explored.push(open.pop_front().unwrap());
+                let index = explored.len() - 1;
+                let parent = &explored[index];
+
+                // Limit how many moves the algorithm does
+                if parent.moves_done > max_path {
+                    break;
+                }
+
+                // Look at all 8 directions
+                for direction in dir_list {
+                    let curr_x = parent.location.0 + direction.0;
+                    let curr_y = parent.location.1 + direction.1;
+                    let tile = tiles.get((curr_x, curr_y));
+
+                    // Don't look at nodes already explored, else add them to the closed Nodes
+                    if closed.contains(&(curr_x, curr_y)) {
+                        continue;
+                    }
+                    closed.push((curr_x, curr_y));
+
+                    // Has the end been found (all types)
+                    if let Some(end_type) = end_search_mf {
+                        if tile.mf == end_type {
+                            end_found = true;
+                        }
+                    }
+                    if let Some(end_type) = end_search_type {
+                        if end_type == "unexplored" && !tile.explored {
+                            end_found = true;
+                        }
+                    }
+                    if let Some(end_type) = end_location {
+                        if (curr_x, curr_y) == end_type {
+                            end_found = true;
+                        }
+                    }
+
+                    let walkable = if ignore_blocked {
+                        tile.walkable
+                    } else {
+                        tile.walkable && !tile.blocked
+                    };
+
+                    // If end found or the tile is walkable, add end to end of Nodes
+                    if end_found || walkable {
+                        let mut child = Node {
+                            cost: 0,
+                            moves_done: parent.moves_done + 1,
+                            moves_to_goal: 0,
+                            location: (curr_x, curr_y),
+                            end_goal: end_location,
+                            parent_node: Some(index),
+                            key_break,
+                        };
+                        child.calc_cost();
+                        key_break += 1;
+
+                        if end_found {
+                            final_path = Some(child);
+                            break;
+                        } else {
+                            // Add child to opened list;
+                            open.push_back(child);
+                        };
+                    }
+                }
+                open.make_contiguous()
+                    .sort_by_key(|node| (node.cost, node.key_break));
+            }
+
+            // Unravel the nested nodes - will give shortest path
+            let mut path = vec![];
+            if let Some(fp) = final_path {
+                let mut curr_node = &fp;
+                path.push(curr_node.location);
+                while curr_node.parent_node.is_some() {
+                    curr_node = &explored[curr_node.parent_node.unwrap()];
+                    path.push(curr_node.location);
+                }
+                path.pop(); // Remove the starting element
+            }
+
+            path
+        }
+    }
+}
@@ -1,12 +1,17 @@
-use crate::common::pathfinding;
 use crate::common::Coord;
-use crate::tiles::Tile;
+use crate::items::name::ItemName;
+use crate::movement::MovementProfile;
+use crate::pathfinding::{pathfinding, Goal};
+use crate::tiles::Tiles;
+use rustc_hash::FxHashMap;
 use std::cmp;
 
 #[derive(Debug)]
 pub(crate) struct Pickup {
     pub(crate) unknown: Vec<Coord>,
     pub(crate) ignore: Vec<Coord>,
+    /// Items seen on the floor (e.g. "You see here ..."), by position.
+    pub(crate) names: FxHashMap<Coord, Vec<ItemName>>,
 }
 
 impl Pickup {
@@ -14,6 +19,7 @@ impl Pickup {
         Self {
             unknown: vec![],
             ignore: vec![],
+            names: FxHashMap::default(),
         }
     }
 
@@ -45,6 +51,7 @@ impl Pickup {
         if let Some(i) = index {
             self.unknown.remove(i);
         }
+        self.names.remove(&player_coord);
     }
 
     pub(crate) fn new_ignore_item_loc(&mut self, player_coord: Coord) {
@@ -59,17 +66,18 @@ impl Pickup {
 
     fn path_to_all_items(
         &self,
-        tiles: &[Vec<Tile>],
+        tiles: &Tiles,
         player_coord: Coord,
         fov: u32,
+        profile: &MovementProfile,
     ) -> Vec<Vec<Coord>> {
         let mut path_of_items = vec![];
 
         // How far is monster from char (max = fov)
         for item_coord in self.unknown.clone() {
             // Don't bother to do pathfinding if known more than fov
-            let abs_y = (player_coord.0 as i32 - item_coord.0 as i32).abs();
-            let abs_x = (player_coord.1 as i32 - item_coord.1 as i32).abs();
+            let abs_y = (player_coord.0 - item_coord.0).abs();
+            let abs_x = (player_coord.1 - item_coord.1).abs();
             if cmp::max(abs_y, abs_x) > fov as i32 {
                 continue;
             }
@@ -77,9 +85,8 @@ impl Pickup {
             let path = pathfinding(
                 tiles,
                 player_coord,
-                Some(item_coord),
-                None,
-                None,
+                &Goal::Coord(item_coord),
+                profile,
                 fov as u64,
                 false,
             );
@@ -94,13 +101,14 @@ impl Pickup {
 
     pub(crate) fn nearest(
         &mut self,
-        tiles: &[Vec<Tile>],
+        tiles: &Tiles,
         player_coord: Coord,
         fov: u32,
+        profile: &MovementProfile,
     ) -> Vec<Coord> {
         let mut shortest_path = vec![];
 
-        for path in self.path_to_all_items(tiles, player_coord, fov) {
+        for path in self.path_to_all_items(tiles, player_coord, fov, profile) {
             if path.len() < shortest_path.len() || shortest_path.is_empty() {
                 shortest_path.clone_from(&path);
             }
@@ -1,17 +1,50 @@
-use crate::common::add_i32_to_usize;
-use crate::common::Coord;
+use crate::common::{get_array, get_bool, get_i64, get_str, get_u64, invalid_field, Coord};
+use crate::data_errors::DataError;
+use crate::status::StatusEffect;
 use crate::CrawlData;
 use serde_json::Value;
 
-const MAX_FLOOR_SIZE: usize = 500;
-
 #[derive(Debug)]
 pub(crate) struct Health {
     pub(crate) hp: i32,
     pub(crate) hp_max: i32,
+    /// Max HP without temporary changes (e.g. drain, berserk).
+    pub(crate) real_hp_max: i32,
     pub(crate) poison_survival: i32,
 }
 
+#[derive(Debug)]
+pub(crate) struct Magic {
+    pub(crate) mp: i32,
+    pub(crate) mp_max: i32,
+}
+
+#[derive(Debug)]
+pub(crate) struct Character {
+    pub(crate) name: String,
+    pub(crate) species: String,
+    pub(crate) title: String,
+    /// Experience level.
+    pub(crate) xl: u32,
+    /// Percentage of progress towards the next experience level.
+    pub(crate) progress: u32,
+}
+
+#[derive(Debug)]
+pub(crate) struct Religion {
+    /// Empty if the character has no god.
+    pub(crate) god: String,
+    /// Number of stars of piety (0 to 6).
+    pub(crate) piety_rank: i32,
+    pub(crate) penance: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct Modes {
+    pub(crate) wizard: bool,
+    pub(crate) explore: bool,
+}
+
 #[derive(Debug)]
 pub(crate) struct Stats {
     pub(crate) str: u32,
@@ -32,8 +65,21 @@ pub(crate) struct Player {
     pub(crate) health: Health,
     pub(crate) stats: Stats,
     pub(crate) defense: Defense,
+    pub(crate) magic: Magic,
+    pub(crate) character: Character,
+    pub(crate) religion: Religion,
+    pub(crate) modes: Modes,
+    pub(crate) gold: u64,
+    pub(crate) noise: i32,
+    pub(crate) contamination: i32,
+    /// Description of the quivered action (e.g. "3 stones").
+    pub(crate) quiver: String,
+    pub(crate) unarmed_attack: String,
+    /// Transformation (0 if none).
+    pub(crate) form: u64,
+    pub(crate) runes: Vec<String>,
     pub(crate) equipped: Vec<i32>,
-    pub(crate) status: Vec<String>,
+    pub(crate) status: Vec<StatusEffect>,
 }
 
 impl Health {
@@ -41,11 +87,49 @@ impl Health {
         Self {
             hp: 1,
             hp_max: 1,
+            real_hp_max: 1,
             poison_survival: 1,
         }
     }
 }
 
+impl Magic {
+    pub(crate) fn new() -> Self {
+        Self { mp: 0, mp_max: 0 }
+    }
+}
+
+impl Character {
+    pub(crate) fn new() -> Self {
+        Self {
+            name: String::new(),
+            species: String::new(),
+            title: String::new(),
+            xl: 1,
+            progress: 0,
+        }
+    }
+}
+
+impl Religion {
+    pub(crate) fn new() -> Self {
+        Self {
+            god: String::new(),
+            piety_rank: 0,
+            penance: false,
+        }
+    }
+}
+
+impl Modes {
+    pub(crate) fn new() -> Self {
+        Self {
+            wizard: false,
+            explore: false,
+        }
+    }
+}
+
 impl Stats {
     pub(crate) fn new() -> Self {
         Self {
@@ -73,90 +157,216 @@ impl Player {
             health: Health::new(),
             stats: Stats::new(),
             defense: Defense::new(),
+            magic: Magic::new(),
+            character: Character::new(),
+            religion: Religion::new(),
+            modes: Modes::new(),
+            gold: 0,
+            noise: 0,
+            contamination: 0,
+            quiver: String::new(),
+            unarmed_attack: String::new(),
+            form: 0,
+            runes: vec![],
             equipped: vec![-1; 21],
             status: vec![],
         }
     }
 
-    pub(crate) fn update_pos(&mut self, x: Value, y: Value) {
-        let offset: usize = MAX_FLOOR_SIZE / 2;
+    pub(crate) fn update_pos(&mut self, pos: &Value) -> Result<(), DataError> {
+        self.pos.0 = get_i64(pos, "x")? as i32;
+        self.pos.1 = get_i64(pos, "y")? as i32;
 
-        self.pos.0 = add_i32_to_usize(x.as_i64().unwrap() as i32, offset);
-        self.pos.1 = add_i32_to_usize(y.as_i64().unwrap() as i32, offset);
+        Ok(())
     }
 
-    pub(crate) fn update_equipped(&mut self, equipped: Value) {
-        for (equip_index, item_index) in equipped.as_object().unwrap() {
-            self.equipped[equip_index.parse::<usize>().unwrap()] =
-                item_index.as_i64().unwrap() as i32;
+    pub(crate) fn update_equipped(&mut self, equipped: &Value) -> Result<(), DataError> {
+        let equipped = equipped
+            .as_object()
+            .ok_or_else(|| invalid_field("equip", "an object"))?;
+
+        for (equip_index, item_index) in equipped {
+            let slot = equip_index
+                .parse::<usize>()
+                .ok()
+                .filter(|slot| *slot < self.equipped.len())
+                .ok_or_else(|| DataError::Parse {
+                    what: "equipment slot",
+                    text: equip_index.to_owned(),
+                })?;
+
+            self.equipped[slot] = item_index
+                .as_i64()
+                .ok_or_else(|| invalid_field(equip_index, "an integer"))?
+                as i32;
         }
+
+        Ok(())
     }
 
-    pub(crate) fn update_health(&mut self, message: &Value) {
-        let message_obj = message.as_object().unwrap();
+    pub(crate) fn update_health(&mut self, message: &Value) -> Result<(), DataError> {
+        if message.get("hp").is_some() {
+            self.health.hp = get_i64(message, "hp")? as i32;
+        }
 
-        if message_obj.contains_key("hp") {
-            self.health.hp = message["hp"].as_i64().unwrap() as i32;
+        if message.get("poison_survival").is_some() {
+            self.health.poison_survival = get_i64(message, "poison_survival")? as i32;
         }
 
-        if message_obj.contains_key("poison_survival") {
-            self.health.poison_survival = message["poison_survival"].as_i64().unwrap() as i32;
+        if message.get("hp_max").is_some() {
+            self.health.hp_max = get_i64(message, "hp_max")? as i32;
+        }
+
+        if message.get("real_hp_max").is_some() {
+            self.health.real_hp_max = get_i64(message, "real_hp_max")? as i32;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn update_magic(&mut self, message: &Value) -> Result<(), DataError> {
+        if message.get("mp").is_some() {
+            self.magic.mp = get_i64(message, "mp")? as i32;
         }
 
-        if message_obj.contains_key("hp_max") {
-            self.health.hp_max = message["hp_max"].as_i64().unwrap() as i32;
+        if message.get("mp_max").is_some() {
+            self.magic.mp_max = get_i64(message, "mp_max")? as i32;
         }
+
+        Ok(())
     }
 
-    pub(crate) fn update_stats(&mut self, message: &Value) {
-        let message_obj = message.as_object().unwrap();
+    pub(crate) fn update_character(&mut self, message: &Value) -> Result<(), DataError> {
+        if message.get("name").is_some() {
+            self.character.name = get_str(message, "name")?.to_owned();
+        }
+
+        if message.get("species").is_some() {
+            self.character.species = get_str(message, "species")?.to_owned();
+        }
+
+        if message.get("title").is_some() {
+            self.character.title = get_str(message, "title")?.to_owned();
+        }
+
+        if message.get("xl").is_some() {
+            self.character.xl = get_u64(message, "xl")? as u32;
+        }
+
+        if message.get("progress").is_some() {
+            self.character.progress = get_u64(message, "progress")? as u32;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn update_religion(&mut self, message: &Value) -> Result<(), DataError> {
+        if message.get("god").is_some() {
+            self.religion.god = get_str(message, "god")?.to_owned();
+        }
+
+        if message.get("piety_rank").is_some() {
+            self.religion.piety_rank = get_i64(message, "piety_rank")? as i32;
+        }
+
+        if message.get("penance").is_some() {
+            self.religion.penance = get_bool(message, "penance")?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn update_misc(&mut self, message: &Value) -> Result<(), DataError> {
+        if message.get("gold").is_some() {
+            self.gold = get_u64(message, "gold")?;
+        }
+
+        if message.get("noise").is_some() {
+            self.noise = get_i64(message, "noise")? as i32;
+        }
+
+        if message.get("contam").is_some() {
+            self.contamination = get_i64(message, "contam")? as i32;
+        }
+
+        if message.get("quiver_desc").is_some() {
+            self.quiver = get_str(message, "quiver_desc")?.to_owned();
+        }
+
+        if message.get("unarmed_attack").is_some() {
+            self.unarmed_attack = get_str(message, "unarmed_attack")?.to_owned();
+        }
 
-        if message_obj.contains_key("str") {
-            self.stats.str = message["str"].as_u64().unwrap() as u32;
+        if message.get("form").is_some() {
+            self.form = get_u64(message, "form")?;
         }
 
-        if message_obj.contains_key("int") {
-            self.stats.int = message["int"].as_u64().unwrap() as u32;
+        if message.get("wizard").is_some() {
+            self.modes.wizard = get_bool(message, "wizard")?;
         }
 
-        if message_obj.contains_key("dex") {
-            self.stats.dex = message["dex"].as_u64().unwrap() as u32;
+        if message.get("explore").is_some() {
+            self.modes.explore = get_bool(message, "explore")?;
         }
+
+        if message.get("runes").is_some() {
+            self.runes = get_array(message, "runes")?
+                .iter()
+                .map(|rune| rune.as_str().map(str::to_owned))
+                .collect::<Option<Vec<String>>>()
+                .ok_or_else(|| invalid_field("runes", "an array of strings"))?;
+        }
+
+        Ok(())
     }
 
-    pub(crate) fn update_defense(&mut self, message: &Value) {
-        let message_obj = message.as_object().unwrap();
+    pub(crate) fn update_stats(&mut self, message: &Value) -> Result<(), DataError> {
+        if message.get("str").is_some() {
+            self.stats.str = get_u64(message, "str")? as u32;
+        }
+
+        if message.get("int").is_some() {
+            self.stats.int = get_u64(message, "int")? as u32;
+        }
 
-        if message_obj.contains_key("ac") {
-            self.defense.ac = message["ac"].as_i64().unwrap() as i32;
+        if message.get("dex").is_some() {
+            self.stats.dex = get_u64(message, "dex")? as u32;
         }
 
-        if message_obj.contains_key("ev") {
-            self.defense.ev = message["ev"].as_i64().unwrap() as i32;
+        Ok(())
+    }
+
+    pub(crate) fn update_defense(&mut self, message: &Value) -> Result<(), DataError> {
+        if message.get("ac").is_some() {
+            self.defense.ac = get_i64(message, "ac")? as i32;
         }
 
-        if message_obj.contains_key("sh") {
-            self.defense.sh = message["sh"].as_i64().unwrap() as i32;
+        if message.get("ev").is_some() {
+            self.defense.ev = get_i64(message, "ev")? as i32;
         }
+
+        if message.get("sh").is_some() {
+            self.defense.sh = get_i64(message, "sh")? as i32;
+        }
+
+        Ok(())
     }
 
-    pub(crate) fn update_status(&mut self, messages: Value) {
+    pub(crate) fn update_status(&mut self, messages: &Value) -> Result<(), DataError> {
         // Always reset -- full list sent every time
         self.status = vec![];
 
-        let val_array = messages.as_array().unwrap();
+        let val_array = messages
+            .as_array()
+            .ok_or_else(|| invalid_field("status", "an array"))?;
 
         for message in val_array {
-            let message_obj = message.as_object().unwrap();
-
-            if message_obj.contains_key("light") {
-                self.status
-                    .push(message_obj["light"].as_str().unwrap().to_owned())
-            } else if message_obj.contains_key("text") {
-                self.status
-                    .push(message_obj["text"].as_str().unwrap().to_owned())
+            if let Some(status) = StatusEffect::from_message(message)? {
+                self.status.push(status);
             }
         }
+
+        Ok(())
     }
 }
 
@@ -164,4 +374,177 @@ impl CrawlData {
     pub fn lethally_poisoned(&self) -> bool {
         self.player.health.poison_survival <= 0
     }
+
+    /// Name of the character.
+    pub fn player_name(&self) -> &str {
+        &self.player.character.name
+    }
+
+    /// Species of the character (e.g. "Minotaur").
+    pub fn player_species(&self) -> &str {
+        &self.player.character.species
+    }
+
+    /// Title of the character (e.g. "Skirmisher").
+    pub fn player_title(&self) -> &str {
+        &self.player.character.title
+    }
+
+    /// Experience level (XL) of the character.
+    pub fn player_xl(&self) -> u32 {
+        self.player.character.xl
+    }
+
+    /// Progress towards the next experience level, in percent.
+    pub fn player_xl_progress(&self) -> u32 {
+        self.player.character.progress
+    }
+
+    /// Max HP without temporary changes (e.g. drain, berserk).
+    pub fn player_real_hp_max(&self) -> i32 {
+        self.player.health.real_hp_max
+    }
+
+    pub fn player_mp(&self) -> i32 {
+        self.player.magic.mp
+    }
+
+    pub fn player_mp_max(&self) -> i32 {
+        self.player.magic.mp_max
+    }
+
+    /// Strength, intelligence and dexterity of the character.
+    pub fn player_stats(&self) -> (u32, u32, u32) {
+        (
+            self.player.stats.str,
+            self.player.stats.int,
+            self.player.stats.dex,
+        )
+    }
+
+    /// God worshipped by the character, or [None] if none.
+    pub fn player_god(&self) -> Option<&str> {
+        Some(self.player.religion.god.as_str()).filter(|god| !god.is_empty())
+    }
+
+    /// Piety of the character, in stars (0 to 6).
+    pub fn player_piety_rank(&self) -> i32 {
+        self.player.religion.piety_rank
+    }
+
+    /// If the character is under penance with its god.
+    pub fn player_under_penance(&self) -> bool {
+        self.player.religion.penance
+    }
+
+    pub fn player_gold(&self) -> u64 {
+        self.player.gold
+    }
+
+    /// Place of the current level (e.g. "Dungeon"), empty before the first
+    /// "player" message.
+    pub fn player_place(&self) -> &str {
+        self.dungeon
+            .current
+            .as_ref()
+            .map(|(place, _)| place.as_str())
+            .unwrap_or("")
+    }
+
+    /// Depth of the current level in its place.
+    pub fn player_depth(&self) -> u64 {
+        self.dungeon
+            .current
+            .as_ref()
+            .map(|(_, depth)| *depth)
+            .unwrap_or(0)
+    }
+
+    /// Noise made by the last action of the character.
+    pub fn player_noise(&self) -> i32 {
+        self.player.noise
+    }
+
+    /// Magical contamination of the character (0 if none).
+    pub fn player_contamination(&self) -> i32 {
+        self.player.contamination
+    }
+
+    /// Description of the quivered action (e.g. "3 stones"), with colour tags.
+    pub fn player_quiver(&self) -> &str {
+        &self.player.quiver
+    }
+
+    /// Description of the unarmed attack of the character, if not wielding a weapon.
+    pub fn player_unarmed_attack(&self) -> &str {
+        &self.player.unarmed_attack
+    }
+
+    /// Transformation of the character (0 if none).
+    pub fn player_form(&self) -> u64 {
+        self.player.form
+    }
+
+    /// If the game is in wizard mode.
+    pub fn wizard_mode(&self) -> bool {
+        self.player.modes.wizard
+    }
+
+    /// If the game is in explore mode.
+    pub fn explore_mode(&self) -> bool {
+        self.player.modes.explore
+    }
+
+    /// Runes collected by the character.
+    pub fn player_runes(&self) -> &[String] {
+        &self.player.runes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_player_fields() {
+        let mut data = CrawlData::init(7, "0.32");
+        data.update_player(&json!({
+            "msg": "player",
+            "name": "Username",
+            "species": "Minotaur",
+            "title": "Skirmisher",
+            "xl": 3,
+            "progress": 42,
+            "mp": 2,
+            "mp_max": 4,
+            "god": "",
+            "penance": 0,
+            "gold": 57,
+            "place": "Dungeon",
+            "depth": 2,
+            "str": 15,
+            "int": 8,
+            "dex": 12,
+            "wizard": 1
+        }))
+        .unwrap();
+
+        assert_eq!(data.player_species(), "Minotaur");
+        assert_eq!((data.player_xl(), data.player_xl_progress()), (3, 42));
+        assert_eq!((data.player_mp(), data.player_mp_max()), (2, 4));
+        assert_eq!(data.player_god(), None);
+        assert!(!data.player_under_penance());
+        assert_eq!((data.player_place(), data.player_depth()), ("Dungeon", 2));
+        assert_eq!(data.player_stats(), (15, 8, 12));
+        assert!(data.wizard_mode());
+
+        // Partial update only changes the fields sent
+        data.update_player(&json!({"msg": "player", "god": "Trog", "piety_rank": 2}))
+            .unwrap();
+        assert_eq!(data.player_god(), Some("Trog"));
+        assert_eq!(data.player_piety_rank(), 2);
+        assert_eq!(data.player_gold(), 57);
+    }
 }
@@ -1,3 +1,5 @@
+use crate::common::invalid_field;
+use crate::data_errors::DataError;
 use crate::CrawlData;
 use rustc_hash::FxHashMap;
 use serde_json::Value;
@@ -6,7 +8,7 @@ use serde_json::Value;
 pub(crate) struct Skill {
     pub(crate) id: String,
     pub(crate) selected: i32,
-    pub(crate) _level: f64,
+    pub(crate) level: f64,
     pub(crate) _aptitude: i32,
 }
 
@@ -18,11 +20,11 @@ pub(crate) struct Skills {
 }
 
 impl Skill {
-    pub(crate) fn new(id: String, selected: i32, _level: f64, _aptitude: i32) -> Self {
+    pub(crate) fn new(id: String, selected: i32, level: f64, _aptitude: i32) -> Self {
         Self {
             id,
             selected,
-            _level,
+            level,
             _aptitude,
         }
     }
@@ -38,7 +40,7 @@ impl Skills {
     }
 
     #[allow(clippy::assigning_clones)] // Bug? line = line[][].to_owned() can't be converted
-    pub(crate) fn process_skills(&mut self) {
+    pub(crate) fn process_skills(&mut self) -> Result<(), DataError> {
         'num_lines: for i in 0..25 {
             // Ignore empty lines or non-provided data
             if !self.skill_menu_items.contains_key(&i) {
@@ -71,48 +73,57 @@ impl Skills {
                 }
 
                 // Find ID and if it's selected
-                let id = line.chars().next().unwrap().to_string();
-                let selected_text: String = line.chars().nth(2).unwrap().to_string();
-                let selected = match &selected_text[..] {
-                    "-" => 0,
-                    "+" => 1,
-                    "*" => 2,
+                let mut chars = line.chars();
+                let (Some(id), Some(selected_text)) = (chars.next(), chars.nth(1)) else {
+                    continue 'num_lines; // not a skill (skip)
+                };
+                let id = id.to_string();
+                let selected = match selected_text {
+                    '-' => 0,
+                    '+' => 1,
+                    '*' => 2,
                     _ => continue 'num_lines, // not a skill (skip)
                 };
 
                 // Get the name of the skill
-                line = line[4..line.len()].to_owned();
-                let name_end = line.find("  ").unwrap();
+                line = line.get(4..).ok_or_else(|| skill_error(&line))?.to_owned();
+                let name_end = find(&line, "  ")?;
                 let name = line[0..name_end].to_owned();
                 line = line[name_end..line.len()].trim_start().to_owned();
 
                 // Get level
-                let level_end = line.find(' ').unwrap();
-                let level = line[0..level_end].to_owned().parse::<f64>().unwrap();
+                let level_end = find(&line, " ")?;
+                let level = line[0..level_end]
+                    .parse::<f64>()
+                    .map_err(|_| skill_error(&line))?;
 
                 // Get cost
-                let end = line.find('>').unwrap();
+                let end = find(&line, ">")?;
                 line = line[end + 1..line.len()].to_owned();
-                let end: usize = line.find('>').unwrap();
+                let end = find(&line, ">")?;
                 line = line[end + 1..line.len()].to_owned();
-                let cost_end = line.find(' ').unwrap();
-                let _cost = line[0..cost_end].to_owned().parse::<f64>().unwrap();
+                let cost_end = find(&line, " ")?;
+                let _cost = line[0..cost_end]
+                    .parse::<f64>()
+                    .map_err(|_| skill_error(&line))?;
 
                 // Get aptitude
-                let end = line.find('>').unwrap();
+                let end = find(&line, ">")?;
                 line = line[end + 1..line.len()].to_owned();
-                let end = line.find('>').unwrap();
+                let end = find(&line, ">")?;
                 line = line[end + 1..line.len()].to_owned();
-                let apt_end = line.find(' ').unwrap();
-                let aptitude = line[0..apt_end].to_owned().parse::<i32>().unwrap();
+                let apt_end = find(&line, " ")?;
+                let aptitude = line[0..apt_end]
+                    .parse::<i32>()
+                    .map_err(|_| skill_error(&line))?;
 
                 // Prepare for second skill
-                let end = line.find("  ").unwrap();
+                let end = find(&line, "  ")?;
                 line = line[end + 1..line.len()].to_owned().trim_start().to_owned();
                 if line.starts_with('<') {
-                    let end = line.find('>').unwrap();
+                    let end = find(&line, ">")?;
                     line = line[end + 1..line.len()].to_owned().trim_start().to_owned();
-                    let end = line.find('>').unwrap();
+                    let end = find(&line, ">")?;
                     line = line[end + 1..line.len()].to_owned().trim_start().to_owned();
                 }
 
@@ -120,56 +131,59 @@ impl Skills {
                     .insert(name, Skill::new(id, selected, level, aptitude));
             }
         }
+
+        Ok(())
     }
 }
 
 impl CrawlData {
-    pub fn process_skills(&mut self, menu_items: &Value) {
-        let skills_obj = menu_items.as_object().unwrap();
-
+    pub fn process_skills(&mut self, menu_items: &Value) -> Result<(), DataError> {
         // Empty -- ignore
-        if !skills_obj.contains_key("lines") {
-            return;
+        if menu_items.get("lines").is_none() {
+            return Ok(());
         }
 
         // Game only sends changed lines -- so some option changes may not re-send
         // all the lines. Store them and update if new info is sent.
-        let lines_obj = skills_obj["lines"].as_object().unwrap();
+        let lines_obj = menu_items["lines"]
+            .as_object()
+            .ok_or_else(|| invalid_field("lines", "an object"))?;
         let mut has_some_data = false;
         for i in 0..25 {
-            if lines_obj.contains_key(&(i.to_string())) {
-                // Verify at least one line has data
-                if !lines_obj[&(i.to_string())]
+            if let Some(line) = lines_obj.get(&i.to_string()) {
+                let line = line
                     .as_str()
-                    .unwrap()
-                    .to_owned()
-                    .is_empty()
-                {
+                    .ok_or_else(|| invalid_field(&i.to_string(), "a string"))?;
+
+                // Verify at least one line has data
+                if !line.is_empty() {
                     has_some_data = true;
                 }
 
-                self.skills
-                    .skill_menu_items
-                    .insert(i, lines_obj[&(i.to_string())].as_str().unwrap().to_owned());
+                self.skills.skill_menu_items.insert(i, line.to_owned());
             }
         }
         // If none found, it's because the game is simply blanking everything when the menu is closed.
         if !has_some_data {
-            return;
+            return Ok(());
         }
 
-        // Identify menu options
-        let option1 = self.skills.skill_menu_items[&21].to_owned();
-        let option2 = self.skills.skill_menu_items[&22].to_owned();
-        let option3 = self.skills.skill_menu_items[&23].to_owned();
-        let options = [option1, option2, option3].join("\n");
-        let (mode, scope, view) = skills_options(options);
+        // Identify menu options (not a skill menu if they are missing)
+        let options = (21..24)
+            .map(|i| self.skills.skill_menu_items.get(&i).cloned())
+            .collect::<Option<Vec<String>>>();
+        let Some(options) = options else {
+            return Ok(());
+        };
+        let (mode, scope, view) = skills_options(options.join("\n"));
 
         if self.correct_skills_mode(mode, scope, view) {
-            self.skills.process_skills();
+            self.skills.process_skills()?;
 
             self.set_skills_training();
         }
+
+        Ok(())
     }
 
     pub(crate) fn set_skills_training(&mut self) {
@@ -238,6 +252,18 @@ impl CrawlData {
     }
 }
 
+/// Position of the pattern in a line of the skill menu, or a [DataError::Parse].
+fn find(line: &str, pattern: &str) -> Result<usize, DataError> {
+    line.find(pattern).ok_or_else(|| skill_error(line))
+}
+
+fn skill_error(line: &str) -> DataError {
+    DataError::Parse {
+        what: "skill",
+        text: line.to_owned(),
+    }
+}
+
 pub(crate) fn skills_options(options: String) -> (String, String, String) {
     // Mode type (auto or manual)
     let mut mode = "manual";
@@ -0,0 +1,338 @@
+use crate::common::{get_str, invalid_field};
+use crate::data_errors::DataError;
+use crate::CrawlData;
+use serde_json::Value;
+
+/// Colours of the status lights (as sent in the "col" field) that the game uses
+/// for a status about to expire (the darker variant of the usual colour).
+const EXPIRING_COLOURS: [u64; 4] = [
+    1, // BLUE (instead of LIGHTBLUE)
+    2, // GREEN (instead of LIGHTGREEN)
+    5, // MAGENTA (instead of LIGHTMAGENTA)
+    7, // LIGHTGREY (instead of WHITE)
+];
+
+/// Severity of the poisoning, from the status text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PoisonLevel {
+    Mild,
+    Moderate,
+    Severe,
+    /// The poison will kill the character, unless cured.
+    Lethal,
+}
+
+/// Status of the character, as shown in the status lights (0.29 to 0.32).
+/// Statuses unknown to this library are kept as [Status::Other], with their
+/// text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Haste,
+    Slow,
+    Berserk,
+    /// Fatigue after berserking.
+    Exhausted,
+    Might,
+    Brilliance,
+    Agility,
+    Poison(PoisonLevel),
+    Confused,
+    Petrifying,
+    Petrified,
+    Paralysed,
+    Mesmerised,
+    Afraid,
+    Held,
+    Corroded,
+    Weakened,
+    Sick,
+    Drained,
+    Silenced,
+    Vertigo,
+    Frozen,
+    Barbed,
+    Flying,
+    /// Immune to confusion (e.g. from confusing clouds).
+    Clarity,
+    Invisible,
+    Regenerating,
+    Resistant,
+    Swift,
+    DeathsDoor,
+    Recall,
+    Other(String),
+}
+
+/// If a [Status] helps the character, hinders it, or neither.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusKind {
+    Buff,
+    Debuff,
+    Neutral,
+}
+
+/// Hint on how long a [Status] will last, from the colour and the description
+/// sent by the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusDuration {
+    Ongoing,
+    /// The status will end soon.
+    Expiring,
+}
+
+/// A [Status] of the character, as received in the "player" message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusEffect {
+    pub status: Status,
+    pub duration: StatusDuration,
+    /// Text of the status light (or the status text if it has no light).
+    pub text: String,
+    /// Long description of the status (e.g. "You are hasted."), if sent.
+    pub desc: String,
+}
+
+impl Status {
+    /// Identify the status from the text of its light (e.g. "Conf") or its
+    /// short text (e.g. "confused").
+    ///
+    /// # Arguments
+    ///
+    /// * `light` - A &str with the text of the status light (can be empty).
+    /// * `text` - A &str with the short text of the status (can be empty).
+    pub fn from_text(light: &str, text: &str) -> Self {
+        let lower = text.to_lowercase();
+
+        if light.starts_with("Pois") || lower.contains("poisoned") {
+            let level = if lower.contains("lethal") {
+                PoisonLevel::Lethal
+            } else if lower.contains("severe")
+                || lower.contains("seriously")
+                || lower.contains("extremely")
+            {
+                PoisonLevel::Severe
+            } else if lower.contains("mild") || lower.contains("slightly") {
+                PoisonLevel::Mild
+            } else {
+                PoisonLevel::Moderate
+            };
+
+            return Self::Poison(level);
+        }
+
+        let name = if light.is_empty() { text } else { light };
+        match name.trim_end_matches(" (expiring)") {
+            "Fast" | "Haste" | "Hasted" | "hasted" => Self::Haste,
+            "Slow" | "slowed" => Self::Slow,
+            "Berserk" | "berserking" => Self::Berserk,
+            "Exh" | "Exhausted" | "exhausted" => Self::Exhausted,
+            "Might" | "mighty" => Self::Might,
+            "Brill" | "Brilliant" | "brilliant" => Self::Brilliance,
+            "Agi" | "Agile" | "agile" => Self::Agility,
+            "Conf" | "Confused" | "confused" => Self::Confused,
+            "Petr" if lower.contains("petrifying") => Self::Petrifying,
+            "petrifying" => Self::Petrifying,
+            "Petr" | "petrified" => Self::Petrified,
+            "Para" | "paralysed" => Self::Paralysed,
+            "Mesm" | "mesmerised" => Self::Mesmerised,
+            "Fear" | "Afraid" | "afraid" | "terrified" => Self::Afraid,
+            "Held" | "held" | "caught" => Self::Held,
+            "Corr" | "corroded" => Self::Corroded,
+            "Weak" | "weakened" => Self::Weakened,
+            "Sick" | "sick" | "diseased" => Self::Sick,
+            "Drain" | "drained" => Self::Drained,
+            "Sil" | "Silence" | "silenced" => Self::Silenced,
+            "Vertigo" | "vertiginous" => Self::Vertigo,
+            "Frozen" | "frozen" => Self::Frozen,
+            "Barbs" | "barbed" => Self::Barbed,
+            "Fly" | "flying" => Self::Flying,
+            "Clear" | "Clarity" | "clear-headed" => Self::Clarity,
+            "Invis" | "invisible" => Self::Invisible,
+            "Regen" | "regenerating" => Self::Regenerating,
+            "Resist" | "resistant" => Self::Resistant,
+            "Swift" | "swift" => Self::Swift,
+            "DDoor" | "death's door" => Self::DeathsDoor,
+            "Recall" | "recalling" => Self::Recall,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+
+    /// If the status helps the character ([StatusKind::Buff]), hinders it
+    /// ([StatusKind::Debuff]), or neither ([StatusKind::Neutral], including
+    /// unknown statuses).
+    pub fn kind(&self) -> StatusKind {
+        match self {
+            Self::Haste
+            | Self::Berserk
+            | Self::Might
+            | Self::Brilliance
+            | Self::Agility
+            | Self::Flying
+            | Self::Clarity
+            | Self::Invisible
+            | Self::Regenerating
+            | Self::Resistant
+            | Self::Swift
+            | Self::DeathsDoor => StatusKind::Buff,
+            Self::Slow
+            | Self::Exhausted
+            | Self::Poison(_)
+            | Self::Confused
+            | Self::Petrifying
+            | Self::Petrified
+            | Self::Paralysed
+            | Self::Mesmerised
+            | Self::Afraid
+            | Self::Held
+            | Self::Corroded
+            | Self::Weakened
+            | Self::Sick
+            | Self::Drained
+            | Self::Silenced
+            | Self::Vertigo
+            | Self::Frozen
+            | Self::Barbed => StatusKind::Debuff,
+            Self::Recall | Self::Other(_) => StatusKind::Neutral,
+        }
+    }
+
+    /// If the status prevents the character from acting normally (moving,
+    /// attacking or retreating at will).
+    pub fn is_incapacitating(&self) -> bool {
+        matches!(
+            self,
+            Self::Confused
+                | Self::Petrified
+                | Self::Paralysed
+                | Self::Mesmerised
+                | Self::Held
+                | Self::Afraid
+        )
+    }
+}
+
+impl StatusEffect {
+    /// Create a [StatusEffect] from an element of the "status" array of the
+    /// "player" message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - A [serde_json::Value] with the "light", "text", "col" and
+    ///   "desc" fields (all optional).
+    pub(crate) fn from_message(message: &Value) -> Result<Option<Self>, DataError> {
+        let light = match message.get("light") {
+            Some(_) => get_str(message, "light")?,
+            None => "",
+        };
+        let text = match message.get("text") {
+            Some(_) => get_str(message, "text")?,
+            None => "",
+        };
+
+        if light.is_empty() && text.is_empty() {
+            return Ok(None);
+        }
+
+        let desc = message["desc"].as_str().unwrap_or("");
+        let colour = match message.get("col") {
+            Some(colour) => Some(
+                colour
+                    .as_u64()
+                    .ok_or_else(|| invalid_field("col", "an unsigned integer"))?,
+            ),
+            None => None,
+        };
+
+        let expiring = text.contains("(expiring)")
+            || desc.starts_with("Expiring")
+            || colour.is_some_and(|colour| EXPIRING_COLOURS.contains(&colour));
+
+        Ok(Some(Self {
+            status: Status::from_text(light, text),
+            duration: if expiring {
+                StatusDuration::Expiring
+            } else {
+                StatusDuration::Ongoing
+            },
+            text: if light.is_empty() { text } else { light }.to_owned(),
+            desc: desc.to_owned(),
+        }))
+    }
+}
+
+impl CrawlData {
+    /// Statuses of the character, as sent in the last "status" update.
+    pub fn player_statuses(&self) -> &[StatusEffect] {
+        &self.player.status
+    }
+
+    /// If the character has the [Status] (regardless of its duration).
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - A reference to the [Status] to look for.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if data.has_status_effect(&Status::Berserk) {
+    ///     // Can't read scrolls or quaff potions
+    /// }
+    /// ```
+    pub fn has_status_effect(&self, status: &Status) -> bool {
+        self.player
+            .status
+            .iter()
+            .any(|effect| &effect.status == status)
+    }
+
+    /// Statuses of the character of the [StatusKind] (e.g. all debuffs).
+    pub fn statuses_of_kind(&self, kind: StatusKind) -> Vec<&StatusEffect> {
+        self.player
+            .status
+            .iter()
+            .filter(|effect| effect.status.kind() == kind)
+            .collect()
+    }
+
+    /// If the character has an incapacitating debuff (e.g. paralysed,
+    /// confused), see [`Status::is_incapacitating`].
+    pub fn incapacitated(&self) -> bool {
+        self.player
+            .status
+            .iter()
+            .any(|effect| effect.status.is_incapacitating())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_status() {
+        let mut data = CrawlData::init(7, "0.32");
+        data.update_player(&json!({"msg": "player", "status": [
+            {"light": "Fast", "text": "hasted (expiring)", "col": 1},
+            {"light": "Pois", "text": "severely poisoned", "col": 4},
+            {"light": "Conf", "text": "confused", "col": 4, "desc": "You are confused."},
+            {"light": "Glow", "col": 12},
+            {"light": "Slay", "col": 7},
+        ]}))
+        .unwrap();
+
+        let statuses = data.player_statuses();
+        assert_eq!(statuses[0].status, Status::Haste);
+        assert_eq!(statuses[0].duration, StatusDuration::Expiring);
+        assert_eq!(statuses[1].status, Status::Poison(PoisonLevel::Severe));
+        assert_eq!(statuses[2].duration, StatusDuration::Ongoing);
+        assert_eq!(statuses[3].status, Status::Other("Glow".to_owned()));
+        assert_eq!(statuses[4].duration, StatusDuration::Expiring);
+
+        assert!(data.incapacitated());
+        assert_eq!(data.statuses_of_kind(StatusKind::Debuff).len(), 2);
+        assert!(data.has_status("Conf"));
+        assert!(data.has_status_effect(&Status::Haste));
+    }
+}
@@ -1,11 +1,13 @@
-use crate::common::{Coord, CoordVec};
+use crate::common::{get_i64, get_u64, invalid_field, Coord, CoordVec};
+use crate::data_errors::DataError;
+use rustc_hash::FxHashMap;
 use serde_json::Value;
-use std::error::Error;
 
-use crate::common::add_i32_to_usize;
+/// Width (and height) of the square chunks the map is stored in.
+const CHUNK_SIZE: i32 = 16;
 
-/// Max floor size
-const MAX_FLOOR_SIZE: usize = 500;
+/// Number of tiles in a chunk.
+const CHUNK_AREA: usize = (CHUNK_SIZE * CHUNK_SIZE) as usize;
 
 /// Map Features (MF) received by the game (e.g. floor, wall).
 ///
@@ -46,20 +48,24 @@ const MF: [[Option<bool>; 2]; 27] = [
 #[derive(Debug)]
 /// Stores the whole tile, path and monster data structures.
 pub(crate) struct Tiles {
-    /// Stores all the individual [Tile] in a vector (x) of vectors (y).
-    /// The character is initialized at [[MAX_FLOOR_SIZE] / 2][[MAX_FLOOR_SIZE] / 2]
+    /// Stores the individual [Tile] in chunks of [CHUNK_SIZE] x [CHUNK_SIZE] tiles,
+    /// keyed by the coordinates of the chunk. Only the chunks containing a tile
+    /// received from the game are allocated. Coordinates are the ones sent by
+    /// the server (and can be negative).
     ///
     /// # Navigation
     ///
     /// ```ignore
-    /// Tiles [x, y]
+    /// Tiles (x, y)
     ///        [-y]
     ///       ↖ ↑ ↗
     ///  [-x] ← · → [+x]
     ///       ↙ ↓ ↘
     ///        [+y]
     /// ```
-    pub(crate) tiles: Vec<Vec<Tile>>,
+    chunks: FxHashMap<Coord, Box<[Tile; CHUNK_AREA]>>,
+    /// Smallest and largest (x, y) of the tiles received, if any.
+    bounds: Option<(Coord, Coord)>,
 }
 
 /// Info for each single tile on the specified floor.
@@ -76,19 +82,85 @@ pub(crate) struct Tile {
 
     /// Bool on if the tile is explored or not.
     pub(crate) explored: bool,
+
+    /// Bool on if the tile was received from the game.
+    pub(crate) known: bool,
+
+    /// Bool on if there is a cloud on the tile.
+    pub(crate) cloud: bool,
 }
 
 impl Tiles {
-    /// Create [Tiles] object with a an initialized 2D vector
-    /// based on [MAX_FLOOR_SIZE].
+    /// Create an empty [Tiles] object.
     pub(crate) fn init() -> Self {
         Self {
-            tiles: vec![vec![Tile::new(); MAX_FLOOR_SIZE]; MAX_FLOOR_SIZE],
+            chunks: FxHashMap::default(),
+            bounds: None,
         }
     }
 
+    /// Coordinates of the chunk containing the tile, and index of the tile in the chunk.
+    fn chunk_index(coord: Coord) -> (Coord, usize) {
+        let chunk = (
+            coord.0.div_euclid(CHUNK_SIZE),
+            coord.1.div_euclid(CHUNK_SIZE),
+        );
+        let index = coord.0.rem_euclid(CHUNK_SIZE) * CHUNK_SIZE + coord.1.rem_euclid(CHUNK_SIZE);
+
+        (chunk, index as usize)
+    }
+
+    /// Get a copy of the tile at the coordinates (an unexplored, unwalkable tile
+    /// if it was never received).
+    pub(crate) fn get(&self, coord: Coord) -> Tile {
+        let (chunk, index) = Self::chunk_index(coord);
+
+        self.chunks
+            .get(&chunk)
+            .map(|tiles| tiles[index])
+            .unwrap_or_else(Tile::new)
+    }
+
+    /// Get a mutable reference to the tile at the coordinates, marking it as known.
+    pub(crate) fn get_mut(&mut self, coord: Coord) -> &mut Tile {
+        self.bounds = Some(match self.bounds {
+            None => (coord, coord),
+            Some((min, max)) => (
+                (min.0.min(coord.0), min.1.min(coord.1)),
+                (max.0.max(coord.0), max.1.max(coord.1)),
+            ),
+        });
+
+        let (chunk, index) = Self::chunk_index(coord);
+        let tile = &mut self
+            .chunks
+            .entry(chunk)
+            .or_insert_with(|| Box::new([Tile::new(); CHUNK_AREA]))[index];
+        tile.known = true;
+
+        tile
+    }
+
+    /// Smallest and largest (x, y) of the tiles received, if any.
+    pub(crate) fn bounds(&self) -> Option<(Coord, Coord)> {
+        self.bounds
+    }
+
+    /// Iterate over the tiles received from the game, with their coordinates.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (Coord, &Tile)> {
+        self.chunks.iter().flat_map(|(chunk, tiles)| {
+            tiles.iter().enumerate().filter_map(move |(index, tile)| {
+                let x = chunk.0 * CHUNK_SIZE + index as i32 / CHUNK_SIZE;
+                let y = chunk.1 * CHUNK_SIZE + index as i32 % CHUNK_SIZE;
+
+                tile.known.then_some(((x, y), tile))
+            })
+        })
+    }
+
     /// Update the tiles 2D vector based on the data received from the game (as
-    /// a [serde_json::Value]). Return info on monsters and floor items.
+    /// a [serde_json::Value]). Return info on monsters and floor items, and the
+    /// unknown map features received (their tile is made unwalkable).
     ///
     /// # Arguments
     ///
@@ -103,14 +175,22 @@ impl Tiles {
     pub(crate) fn update(
         &mut self,
         cells: &Value,
-    ) -> Result<(Vec<(Coord, Value)>, CoordVec, (CoordVec, CoordVec)), Box<dyn Error>> {
+    ) -> Result<
+        (
+            Vec<(Coord, Value)>,
+            CoordVec,
+            (CoordVec, CoordVec),
+            Vec<u64>,
+        ),
+        DataError,
+    > {
         // x = moving left and right through a row
         // y = moving up and down in a column
 
         // Sometimes sends empty map (especially on altar levels) and best thing
         // is to just ignore it.
         if cells.is_null() {
-            return Ok((Vec::new(), Vec::new(), (Vec::new(), Vec::new())));
+            return Ok((Vec::new(), Vec::new(), (Vec::new(), Vec::new()), Vec::new()));
         }
 
         let mut coord = (0, 0);
@@ -123,52 +203,62 @@ impl Tiles {
         // List of items on the ground
         let mut itemlist: CoordVec = Vec::new();
 
-        for tile in cells.as_array().unwrap() {
-            let tile_object = tile.as_object().unwrap();
+        // Map features not in [MF] (e.g. added by a newer version of the game)
+        let mut unknown_features: Vec<u64> = Vec::new();
+
+        let cells = cells
+            .as_array()
+            .ok_or_else(|| invalid_field("cells", "an array"))?;
+
+        for tile in cells {
+            let tile_object = tile
+                .as_object()
+                .ok_or_else(|| invalid_field("cells", "an array of objects"))?;
 
             // if y in cell, then both y and x will be there
             // y will always be available at the start of new row of tiles
             // y will sometimes be available after a gap (space that is not reachable)
             if tile_object.contains_key("y") {
-                coord.0 = tile_object["x"].as_i64().unwrap() as i32;
-                coord.1 = tile_object["y"].as_i64().unwrap() as i32;
+                coord.0 = get_i64(tile, "x")? as i32;
+                coord.1 = get_i64(tile, "y")? as i32;
             } else {
                 // if y is not there, add x and y (from last tile + 1)
                 coord.0 += 1
             }
 
-            // Set x and y
-            let offset = MAX_FLOOR_SIZE / 2;
-            let x_pos = add_i32_to_usize(coord.0, offset);
-            let y_pos = add_i32_to_usize(coord.1, offset);
-
             // Decode MF into walkable and explored
             // Will not contain MF if MF not updated
             if tile_object.contains_key("mf") {
-                let mf = usize::try_from(tile_object["mf"].as_u64().unwrap())?;
-                assert!(MF[mf][0].is_some(), "MF {mf} not implemented.");
-                let walkable = MF[mf][0].unwrap();
-                let explored = MF[mf][1].unwrap();
+                let mf_value = get_u64(tile, "mf")?;
+                let mf = mf_value as usize;
+                let (walkable, explored) = match MF.get(mf).copied() {
+                    Some([Some(walkable), Some(explored)]) => (walkable, explored),
+                    // Avoided, and not explored again
+                    _ => {
+                        unknown_features.push(mf_value);
+                        (false, true)
+                    }
+                };
 
                 // Update the tile
-                self.tiles[x_pos][y_pos].update(mf, walkable, explored);
+                self.get_mut(coord).update(mf, walkable, explored);
 
                 // Add items to the items list (mf = 6)
-                if self.tiles[x_pos][y_pos].mf == 6 {
-                    itemlist.push((x_pos, y_pos));
+                if self.get(coord).mf == 6 {
+                    itemlist.push(coord);
                 }
             }
 
             // Add monster data, and update the "walkability of tile"
             if tile_object.contains_key("mon") {
-                monsters.push(((x_pos, y_pos), tile_object["mon"].clone()));
+                monsters.push((coord, tile_object["mon"].clone()));
 
                 if tile_object["mon"].is_null() {
                     // Update the tile to original walkability
-                    self.tiles[x_pos][y_pos].unblock();
+                    self.get_mut(coord).unblock();
                 } else {
                     // Update the tile to not walkable
-                    self.tiles[x_pos][y_pos].block();
+                    self.get_mut(coord).block();
                 }
             }
 
@@ -176,19 +266,21 @@ impl Tiles {
             if tile_object.contains_key("g") {
                 // Tiles have the "{" glyph when invisible monsters are on them (for a period of time)
                 if tile_object["g"] == "{" {
-                    invisible_monsters.push((x_pos, y_pos));
+                    invisible_monsters.push(coord);
                 } else if tile_object["g"] == "@" {
                     // If character on monster tile, delete invisible (means no longer there)
-                    remove_invisible_monsters.push((x_pos, y_pos));
+                    remove_invisible_monsters.push(coord);
                 } else if tile_object["g"] == "§"
                     || tile_object["g"] == "☼"
                     || tile_object["g"] == "○"
                     || tile_object["g"] == "°"
-                // Cloud (unsure if this will cause an issue for non-toxic clouds)
+                // Cloud (avoided depending on the MovementProfile)
                 {
-                    self.tiles[x_pos][y_pos].block();
+                    self.get_mut(coord).cloud = true;
                 } else {
-                    self.tiles[x_pos][y_pos].unblock();
+                    let tile = self.get_mut(coord);
+                    tile.unblock();
+                    tile.cloud = false;
                 }
             }
         }
@@ -197,6 +289,7 @@ impl Tiles {
             monsters,
             itemlist,
             (invisible_monsters, remove_invisible_monsters),
+            unknown_features,
         ))
     }
 }
@@ -209,6 +302,8 @@ impl Tile {
             walkable: false,
             blocked: false,
             explored: false,
+            known: false,
+            cloud: false,
         }
     }
 
@@ -222,7 +317,7 @@ impl Tile {
     /// # Examples
     ///
     /// ```ignore
-    /// self.tiles[x_pos][y_pos].update(1, True, True, False);
+    /// self.get_mut(coord).update(1, True, True, False);
     /// ```
     fn update(&mut self, mf: usize, walkable: bool, explored: bool) {
         self.mf = mf;
@@ -238,3 +333,31 @@ impl Tile {
         self.blocked = false
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_sparse_tiles() {
+        let mut tiles = Tiles::init();
+        assert_eq!(tiles.bounds(), None);
+
+        // Row of 3 tiles, starting at a negative x
+        tiles
+            .update(&json!([{"x": -1, "y": 5, "mf": 1}, {"mf": 2}, {"mf": 13}]))
+            .unwrap();
+
+        assert_eq!(tiles.get((-1, 5)).mf, 1);
+        assert!(!tiles.get((0, 5)).walkable);
+        assert_eq!(tiles.get((1, 5)).mf, 13);
+        assert!(!tiles.get((2, 5)).known);
+        assert_eq!(tiles.bounds(), Some(((-1, 5), (1, 5))));
+
+        let mut known = tiles.iter().map(|(coord, _)| coord).collect::<Vec<Coord>>();
+        known.sort();
+        assert_eq!(known, vec![(-1, 5), (0, 5), (1, 5)]);
+    }
+}
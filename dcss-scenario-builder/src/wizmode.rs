@@ -1,7 +1,7 @@
 use crate::common::{branch_keys, Coord};
 use crate::scenario_errors::Error;
 
-use dcss_api::{BlockingError, Error as APIError, Webtile};
+use dcss_api::{BlockingError, Colour, Error as APIError, FormattedText, Webtile};
 use itertools::Itertools;
 
 /// Send the commands to the API to enable the wizard mode.
@@ -108,8 +108,10 @@ pub(crate) fn setup_map(
                 && message.as_object().unwrap().contains_key("messages")
             {
                 for m in message["messages"].as_array().unwrap() {
-                    // Red problem, but ignore god/orb message
-                    if m["text"].as_str().unwrap().contains("<lightred>")
+                    let text = FormattedText::parse(m["text"].as_str().unwrap());
+
+                    // Red problem, but ignore god/monster warning message
+                    if text.has_colour(Colour::LightRed)
                         && m["channel"].as_i64().unwrap() != 3
                         && m["channel"].as_i64().unwrap() != 31
                     {